@@ -4,7 +4,8 @@ use std::fs::create_dir_all;
 use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
 
 use collectxyz::nft::{
-    ExecuteMsg, InstantiateMsg, MoveParamsResponse, QueryMsg, XyzTokensResponse,
+    ExecuteMsg, InstantiateMsg, JumpGatesResponse, LocksResponse, MoveParamsResponse,
+    PositionResponse, QueryMsg, XyzTokensResponse,
 };
 
 fn main() {
@@ -18,4 +19,7 @@ fn main() {
     export_schema(&schema_for!(QueryMsg), &out_dir);
     export_schema(&schema_for!(MoveParamsResponse), &out_dir);
     export_schema(&schema_for!(XyzTokensResponse), &out_dir);
+    export_schema(&schema_for!(LocksResponse), &out_dir);
+    export_schema(&schema_for!(PositionResponse), &out_dir);
+    export_schema(&schema_for!(JumpGatesResponse), &out_dir);
 }
@@ -38,13 +38,32 @@ pub fn execute(
         ExecuteMsg::Move {
             token_id,
             coordinates,
-        } => ExecHandler::execute_move(deps, env, info, token_id, coordinates),
+            waypoints,
+        } => ExecHandler::execute_move(
+            deps,
+            env,
+            info,
+            token_id,
+            coordinates,
+            waypoints.unwrap_or_default(),
+        ),
+        ExecuteMsg::CancelMove { token_id } => {
+            ExecHandler::execute_cancel_move(deps, env, info, token_id)
+        }
+        ExecuteMsg::Lock {
+            token_id,
+            expires_at,
+        } => ExecHandler::execute_lock(deps, env, info, token_id, expires_at),
+        ExecuteMsg::Unlock { token_id } => ExecHandler::execute_unlock(deps, info, token_id),
         ExecuteMsg::UpdateConfig { config } => {
-            ExecHandler::execute_update_config(deps, info, config)
+            ExecHandler::execute_update_config(deps, info, *config)
         }
         ExecuteMsg::UpdateCaptchaPublicKey { public_key } => {
             ExecHandler::execute_update_captcha_public_key(deps, info, public_key)
         }
+        ExecuteMsg::ReindexTokens { start_after, limit } => {
+            ExecHandler::execute_reindex_tokens(deps, info, start_after, limit)
+        }
         ExecuteMsg::Withdraw { amount } => ExecHandler::execute_withdraw(deps, env, info, amount),
         _ => ExecHandler::cw721_base_execute(deps, env, info, msg),
     }
@@ -74,17 +93,44 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::AllXyzTokens { start_after, limit } => to_binary(
             &QueryHandler::query_all_xyz_tokens(deps, start_after, limit)?,
         ),
+        QueryMsg::XyzTokensInBox {
+            min,
+            max,
+            start_after,
+            limit,
+        } => to_binary(&QueryHandler::query_xyz_tokens_in_box(
+            deps,
+            min,
+            max,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::NearestXyz { coords, limit } => {
+            to_binary(&QueryHandler::query_nearest_xyz(deps, coords, limit)?)
+        }
         QueryMsg::NumTokensForOwner { owner } => {
             to_binary(&QueryHandler::query_num_tokens_for_owner(deps, owner)?)
         }
         QueryMsg::MoveParams {
             token_id,
             coordinates,
+            waypoints,
         } => to_binary(&QueryHandler::query_move_params(
             deps,
             token_id,
             coordinates,
+            waypoints.unwrap_or_default(),
+        )?),
+        QueryMsg::Position {
+            token_id,
+            timestamp,
+        } => to_binary(&QueryHandler::query_position(
+            deps, env, token_id, timestamp,
         )?),
+        QueryMsg::JumpGates {} => to_binary(&QueryHandler::query_jump_gates(deps)?),
+        QueryMsg::Locks { token_id } => {
+            to_binary(&QueryHandler::query_locks(deps, env, token_id)?)
+        }
         _ => QueryHandler::cw721_base_query(deps, env, msg),
     }
 }
@@ -1,9 +1,14 @@
 #![cfg(test)]
 use std::str;
 
-use collectxyz::nft::{Config, Coordinates, ExecuteMsg, InstantiateMsg, QueryMsg, XyzExtension};
+use collectxyz::nft::{
+    Config, Coordinates, ExecuteMsg, InstantiateMsg, JumpGate, MoveFuel, MoveFuelCost,
+    PositionResponse, QueryMsg, XyzExtension, XyzLock, XyzTokensResponse,
+};
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{BankMsg, Binary, Coin, DepsMut, StdError, Uint128};
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, StdError, Uint128, WasmMsg,
+};
 use serde_json::json;
 
 use crate::contract::{execute, instantiate, query};
@@ -72,6 +77,9 @@ fn mock_config() -> Config {
         base_move_nanos: 10,
         move_fee_per_step: Uint128::new(1),
         base_move_fee: Coin::new(100, "uluna"),
+        lock_operators: vec![],
+        move_fuel: None,
+        jump_gates: vec![],
     }
 }
 
@@ -676,6 +684,7 @@ fn move_token() {
         ExecuteMsg::Move {
             token_id: "foo".to_string(),
             coordinates: Coordinates { x: 1, y: 2, z: 3 },
+            waypoints: None,
         },
     )
     .unwrap_err();
@@ -692,6 +701,7 @@ fn move_token() {
         ExecuteMsg::Move {
             token_id: nonowner_xyz_id.to_string(),
             coordinates: Coordinates { x: 1, y: 2, z: 3 },
+            waypoints: None,
         },
     )
     .unwrap_err();
@@ -705,6 +715,7 @@ fn move_token() {
         ExecuteMsg::Move {
             token_id: nonowner_xyz_id.to_string(),
             coordinates: nonowner_coords,
+            waypoints: None,
         },
     )
     .unwrap_err();
@@ -722,6 +733,7 @@ fn move_token() {
                 y: 0,
                 z: mock_config().max_coordinate_value + 1,
             },
+            waypoints: None,
         },
     )
     .unwrap_err();
@@ -745,6 +757,7 @@ fn move_token() {
         ExecuteMsg::Move {
             token_id: nonowner_xyz_id.to_string(),
             coordinates: nonowner_target,
+            waypoints: None,
         },
     )
     .unwrap_err();
@@ -758,6 +771,7 @@ fn move_token() {
         deps.as_ref(),
         nonowner_xyz_id.to_string(),
         nonowner_target,
+        vec![],
     )
     .unwrap();
 
@@ -771,6 +785,7 @@ fn move_token() {
         ExecuteMsg::Move {
             token_id: nonowner_xyz_id.to_string(),
             coordinates: nonowner_target,
+            waypoints: None,
         },
     )
     .unwrap();
@@ -801,6 +816,7 @@ fn move_token() {
         ExecuteMsg::Move {
             token_id: nonowner_xyz_id.to_string(),
             coordinates: nonowner_coords,
+            waypoints: None,
         },
     )
     .unwrap_err();
@@ -816,6 +832,7 @@ fn move_token() {
         ExecuteMsg::Move {
             token_id: nonowner_xyz_id.to_string(),
             coordinates: nonowner_coords,
+            waypoints: None,
         },
     );
 
@@ -828,6 +845,7 @@ fn move_token() {
         ExecuteMsg::Move {
             token_id: owner_xyz_id.to_string(),
             coordinates: owner_target,
+            waypoints: None,
         },
     )
     .unwrap();
@@ -843,6 +861,385 @@ fn move_token() {
     );
 }
 
+#[test]
+fn move_token_route() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+
+    let xyz_id = "xyz #1";
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::Mint {
+            captcha_signature: SIG_X0Y0Z0.to_string(),
+            coordinates: Coordinates { x: 0, y: 0, z: 0 },
+        },
+    )
+    .unwrap();
+
+    let waypoint = Coordinates { x: 10, y: 0, z: 0 };
+    let target = Coordinates { x: 10, y: 10, z: 0 };
+
+    // each leg is charged and timed as a separate move
+    let move_params = QueryHandler::query_move_params(
+        deps.as_ref(),
+        xyz_id.to_string(),
+        target,
+        vec![waypoint],
+    )
+    .unwrap();
+    assert_eq!(move_params.fee, Coin::new(220, "uluna"));
+    assert_eq!(move_params.duration_nanos, 40);
+
+    // routes are limited in length
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[move_params.fee.clone()]),
+        ExecuteMsg::Move {
+            token_id: xyz_id.to_string(),
+            coordinates: target,
+            waypoints: Some(vec![waypoint; 11]),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TooManyWaypoints {});
+
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[move_params.fee.clone()]),
+        ExecuteMsg::Move {
+            token_id: xyz_id.to_string(),
+            coordinates: target,
+            waypoints: Some(vec![waypoint]),
+        },
+    )
+    .unwrap();
+
+    // the position is interpolated along each leg
+    fn position_at(deps: Deps, nanos: u64) -> PositionResponse {
+        QueryHandler::query_position(
+            deps,
+            mock_env(),
+            "xyz #1".to_string(),
+            Some(mock_env().block.time.plus_nanos(nanos)),
+        )
+        .unwrap()
+    }
+    let res = position_at(deps.as_ref(), 10);
+    assert_eq!(res.coordinates, Coordinates { x: 5, y: 0, z: 0 });
+    assert!(res.in_transit);
+    let res = position_at(deps.as_ref(), 30);
+    assert_eq!(res.coordinates, Coordinates { x: 10, y: 5, z: 0 });
+    assert!(res.in_transit);
+    let res = position_at(deps.as_ref(), 40);
+    assert_eq!(res.coordinates, target);
+    assert!(!res.in_transit);
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_nanos(30);
+
+    // only the token owner can cancel a move
+    let err = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::CancelMove {
+            token_id: xyz_id.to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the token can change hands mid-move
+    let buyer = "buyer";
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::TransferNft {
+            recipient: buyer.to_string(),
+            token_id: "1".to_string(),
+        },
+    )
+    .unwrap();
+
+    // cancelling stops the token and refunds the fee for the untravelled
+    // distance to whoever paid for the move
+    let res = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(buyer, &[]),
+        ExecuteMsg::CancelMove {
+            token_id: xyz_id.to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        BankMsg::Send {
+            amount: vec![Coin::new(55, "uluna")],
+            to_address: NONOWNER.to_string(),
+        }
+        .into()
+    );
+
+    let res = QueryHandler::query_xyz_nft_info(deps.as_ref(), xyz_id.to_string()).unwrap();
+    assert_eq!(
+        res.extension,
+        XyzExtension {
+            coordinates: Coordinates { x: 10, y: 5, z: 0 },
+            prev_coordinates: Some(Coordinates { x: 0, y: 0, z: 0 }),
+            arrival: env.block.time,
+        }
+    );
+
+    // a stopped token has no move to cancel
+    let err = execute(
+        deps.as_mut(),
+        env,
+        mock_info(buyer, &[]),
+        ExecuteMsg::CancelMove {
+            token_id: xyz_id.to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::NoMoveInProgress {});
+}
+
+#[test]
+fn move_token_with_fuel() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+
+    let xyz_id = "xyz #1";
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::Mint {
+            captcha_signature: SIG_X0Y0Z0.to_string(),
+            coordinates: Coordinates { x: 0, y: 0, z: 0 },
+        },
+    )
+    .unwrap();
+
+    let mut config = mock_config();
+    config.move_fuel = Some(MoveFuel {
+        resource_id: "xyzGAS".to_string(),
+        resource_contract: Addr::unchecked("gas-contract"),
+        base_amount: Uint128::new(1000),
+        amount_per_step: Uint128::new(100),
+    });
+    let _ = ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), config)
+        .unwrap();
+
+    // fuel replaces the native move fee
+    let target = Coordinates { x: 1, y: 2, z: 3 };
+    let move_params =
+        QueryHandler::query_move_params(deps.as_ref(), xyz_id.to_string(), target, vec![])
+            .unwrap();
+    assert_eq!(move_params.fee, Coin::new(0, "uluna"));
+    assert_eq!(
+        move_params.fuel,
+        Some(MoveFuelCost {
+            resource_id: "xyzGAS".to_string(),
+            resource_contract: Addr::unchecked("gas-contract"),
+            amount: Uint128::new(1600),
+        })
+    );
+
+    // moving burns the fuel from the token's balance
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::Move {
+            token_id: xyz_id.to_string(),
+            coordinates: target,
+            waypoints: None,
+        },
+    )
+    .unwrap();
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr,
+            msg,
+            funds,
+        }) => {
+            assert_eq!(contract_addr, "gas-contract");
+            assert_eq!(
+                as_json(msg),
+                json!({
+                    "burn_from": {
+                        "owner": NONOWNER,
+                        "amount": "1600",
+                        "owner_xyz_id": xyz_id,
+                    }
+                })
+            );
+            assert_eq!(funds, &vec![]);
+        }
+        msg => panic!("unexpected message: {:?}", msg),
+    }
+}
+
+#[test]
+fn move_token_through_jump_gate() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+
+    let xyz_id = "xyz #1";
+    let origin = Coordinates { x: 0, y: 0, z: 0 };
+    let far_corner = Coordinates {
+        x: 900,
+        y: 900,
+        z: 900,
+    };
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::Mint {
+            captcha_signature: SIG_X0Y0Z0.to_string(),
+            coordinates: origin,
+        },
+    )
+    .unwrap();
+
+    // jump gates must link in-bounds coordinates
+    let mut config = mock_config();
+    config.jump_gates = vec![JumpGate {
+        a: origin,
+        b: Coordinates {
+            x: 0,
+            y: 0,
+            z: 1001,
+        },
+        travel_nanos: 5,
+        fee: Uint128::new(50),
+    }];
+    let err = ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), config)
+        .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err(
+            "coordinate values must be between -1000 and 1000"
+        ))
+    );
+
+    let gate = JumpGate {
+        a: far_corner,
+        b: origin,
+        travel_nanos: 5,
+        fee: Uint128::new(50),
+    };
+    let mut config = mock_config();
+    config.jump_gates = vec![gate.clone()];
+    let _ = ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), config)
+        .unwrap();
+
+    let res = QueryHandler::query_jump_gates(deps.as_ref()).unwrap();
+    assert_eq!(res.jump_gates, vec![gate]);
+
+    // moves between gate coordinates take the gate's time and fee
+    let move_params =
+        QueryHandler::query_move_params(deps.as_ref(), xyz_id.to_string(), far_corner, vec![])
+            .unwrap();
+    assert_eq!(move_params.fee, Coin::new(50, "uluna"));
+    assert_eq!(move_params.duration_nanos, 5);
+
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[move_params.fee]),
+        ExecuteMsg::Move {
+            token_id: xyz_id.to_string(),
+            coordinates: far_corner,
+            waypoints: None,
+        },
+    )
+    .unwrap();
+
+    // a jumping token stays at the gate until it arrives
+    let res = QueryHandler::query_position(
+        deps.as_ref(),
+        mock_env(),
+        xyz_id.to_string(),
+        Some(mock_env().block.time.plus_nanos(4)),
+    )
+    .unwrap();
+    assert_eq!(res.coordinates, origin);
+
+    let res = QueryHandler::query_xyz_nft_info(deps.as_ref(), xyz_id.to_string()).unwrap();
+    assert_eq!(
+        res.extension,
+        XyzExtension {
+            coordinates: far_corner,
+            prev_coordinates: Some(origin),
+            arrival: mock_env().block.time.plus_nanos(5),
+        }
+    );
+
+    // out and back to the gate, then through it
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_nanos(5);
+    let waypoints = vec![
+        Coordinates {
+            x: 900,
+            y: 900,
+            z: 890,
+        },
+        far_corner,
+    ];
+    let move_params = QueryHandler::query_move_params(
+        deps.as_ref(),
+        xyz_id.to_string(),
+        origin,
+        waypoints.clone(),
+    )
+    .unwrap();
+    assert_eq!(move_params.fee, Coin::new(270, "uluna"));
+    assert_eq!(move_params.duration_nanos, 45);
+
+    let _ = execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(NONOWNER, &[move_params.fee]),
+        ExecuteMsg::Move {
+            token_id: xyz_id.to_string(),
+            coordinates: origin,
+            waypoints: Some(waypoints),
+        },
+    )
+    .unwrap();
+
+    // cancelling at the gate only refunds the jump's own fee
+    env.block.time = env.block.time.plus_nanos(42);
+    let res = execute(
+        deps.as_mut(),
+        env,
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::CancelMove {
+            token_id: xyz_id.to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        BankMsg::Send {
+            amount: vec![Coin::new(50, "uluna")],
+            to_address: NONOWNER.to_string(),
+        }
+        .into()
+    );
+
+    let res = QueryHandler::query_xyz_nft_info(deps.as_ref(), xyz_id.to_string()).unwrap();
+    assert_eq!(res.extension.coordinates, far_corner);
+}
+
 #[test]
 fn xyz_nft_info_by_coords() {
     let mut deps = mock_dependencies(&[]);
@@ -885,6 +1282,164 @@ fn xyz_nft_info_by_coords() {
     assert_eq!(res["owner"], NONOWNER);
 }
 
+#[test]
+fn xyz_tokens_in_box_and_nearest() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+
+    // mint xyz #1 through xyz #5
+    for (captcha_signature, coordinates) in vec![
+        (SIG_X0Y0Z0, Coordinates { x: 0, y: 0, z: 0 }),
+        (SIG_X1Y1Z1, Coordinates { x: 1, y: 1, z: 1 }),
+        (SIG_X2Y2Z2, Coordinates { x: 2, y: 2, z: 2 }),
+        (SIG_X1Y2Z3, Coordinates { x: 1, y: 2, z: 3 }),
+        (SIG_X3Y2Z1, Coordinates { x: 3, y: 2, z: 1 }),
+    ] {
+        let _ = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::Mint {
+                captcha_signature: captcha_signature.to_string(),
+                coordinates,
+            },
+        )
+        .unwrap();
+    }
+
+    let names = |res: XyzTokensResponse| {
+        res.tokens
+            .iter()
+            .map(|token| token.name.clone())
+            .collect::<Vec<String>>()
+    };
+    let min = Coordinates { x: 0, y: 0, z: 0 };
+    let max = Coordinates { x: 2, y: 2, z: 2 };
+
+    // tokens outside the box are skipped, even if their Z-order is within it
+    let res = QueryHandler::query_xyz_tokens_in_box(deps.as_ref(), min, max, None, None).unwrap();
+    assert_eq!(names(res), vec!["xyz #1", "xyz #2", "xyz #3"]);
+
+    // paginate by coordinates
+    let res =
+        QueryHandler::query_xyz_tokens_in_box(deps.as_ref(), min, max, None, Some(2)).unwrap();
+    assert_eq!(names(res), vec!["xyz #1", "xyz #2"]);
+    let res = QueryHandler::query_xyz_tokens_in_box(
+        deps.as_ref(),
+        min,
+        max,
+        Some(Coordinates { x: 1, y: 1, z: 1 }),
+        Some(2),
+    )
+    .unwrap();
+    assert_eq!(names(res), vec!["xyz #3"]);
+
+    let err =
+        QueryHandler::query_xyz_tokens_in_box(deps.as_ref(), max, min, None, None).unwrap_err();
+    assert_eq!(err, StdError::generic_err("box min must not exceed its max"));
+
+    // nearest tokens are ordered by move distance
+    let res = QueryHandler::query_nearest_xyz(
+        deps.as_ref(),
+        Coordinates { x: 3, y: 2, z: 2 },
+        Some(3),
+    )
+    .unwrap();
+    assert_eq!(names(res), vec!["xyz #5", "xyz #3", "xyz #4"]);
+
+    let res = QueryHandler::query_nearest_xyz(
+        deps.as_ref(),
+        Coordinates {
+            x: -1000,
+            y: -1000,
+            z: -1000,
+        },
+        None,
+    )
+    .unwrap();
+    // ties keep their Z-order
+    assert_eq!(
+        names(res),
+        vec!["xyz #1", "xyz #2", "xyz #4", "xyz #5", "xyz #3"]
+    );
+}
+
+#[test]
+fn reindex_tokens() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+
+    for (captcha_signature, coordinates) in vec![
+        (SIG_X0Y0Z0, Coordinates { x: 0, y: 0, z: 0 }),
+        (SIG_X1Y1Z1, Coordinates { x: 1, y: 1, z: 1 }),
+        (SIG_X2Y2Z2, Coordinates { x: 2, y: 2, z: 2 }),
+    ] {
+        let _ = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[]),
+            ExecuteMsg::Mint {
+                captcha_signature: captcha_signature.to_string(),
+                coordinates,
+            },
+        )
+        .unwrap();
+    }
+
+    // only the owner can reindex
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::ReindexTokens {
+            start_after: None,
+            limit: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // reindexing is paged and can be repeated
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::ReindexTokens {
+            start_after: None,
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "last_token_id" && attr.value == "xyz #2"));
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::ReindexTokens {
+            start_after: Some("xyz #2".to_string()),
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "count" && attr.value == "1"));
+
+    let res = QueryHandler::query_xyz_tokens_in_box(
+        deps.as_ref(),
+        Coordinates { x: 0, y: 0, z: 0 },
+        Coordinates { x: 2, y: 2, z: 2 },
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(res.tokens.len(), 3);
+}
+
 #[test]
 fn all_xyz_tokens() {
     let mut deps = mock_dependencies(&[]);
@@ -957,3 +1512,136 @@ fn all_xyz_tokens() {
     );
     assert_eq!(res["tokens"][0]["name"], "xyz #2");
 }
+
+#[test]
+fn lock_token() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut(), None, None, None);
+
+    let xyz_id = "xyz #1";
+    let game_contract = "game-contract";
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::Mint {
+            captcha_signature: String::from(SIG_X1Y2Z3),
+            coordinates: Coordinates { x: 1, y: 2, z: 3 },
+        },
+    )
+    .unwrap();
+
+    let lock_msg = |expires_in_seconds: u64| ExecuteMsg::Lock {
+        token_id: xyz_id.to_string(),
+        expires_at: mock_env().block.time.plus_seconds(expires_in_seconds),
+    };
+
+    // only lock operators can lock tokens
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(game_contract, &[]),
+        lock_msg(100),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let mut config = mock_config();
+    config.lock_operators = vec![Addr::unchecked(game_contract)];
+    let _ = ExecHandler::execute_update_config(deps.as_mut(), mock_info(OWNER, &[]), config)
+        .unwrap();
+
+    // locks must expire in the future
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(game_contract, &[]),
+        lock_msg(0),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidLockExpiry {});
+
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(game_contract, &[]),
+        lock_msg(100),
+    )
+    .unwrap();
+    let res = QueryHandler::query_locks(deps.as_ref(), mock_env(), xyz_id.to_string()).unwrap();
+    assert_eq!(
+        res.locks,
+        vec![XyzLock {
+            locker: Addr::unchecked(game_contract),
+            expires_at: mock_env().block.time.plus_seconds(100),
+        }]
+    );
+
+    // a locked token can't be transferred, sent, or moved
+    for msg in vec![
+        ExecuteMsg::TransferNft {
+            recipient: OWNER.to_string(),
+            token_id: "1".to_string(),
+        },
+        ExecuteMsg::SendNft {
+            contract: "another-contract".to_string(),
+            token_id: "1".to_string(),
+            msg: to_binary("my msg").unwrap(),
+        },
+        ExecuteMsg::Move {
+            token_id: xyz_id.to_string(),
+            coordinates: Coordinates { x: 4, y: 5, z: 6 },
+            waypoints: None,
+        },
+    ] {
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(NONOWNER, &[Coin::new(1000, "uluna")]),
+            msg,
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Locked {});
+    }
+
+    // expired locks are ignored
+    let mut expired_env = mock_env();
+    expired_env.block.time = expired_env.block.time.plus_seconds(100);
+    let res = QueryHandler::query_locks(deps.as_ref(), expired_env, xyz_id.to_string()).unwrap();
+    assert_eq!(res.locks, vec![]);
+
+    // only the locker can unlock
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::Unlock {
+            token_id: xyz_id.to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::not_found("collectxyz::nft::XyzLock"))
+    );
+
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(game_contract, &[]),
+        ExecuteMsg::Unlock {
+            token_id: xyz_id.to_string(),
+        },
+    )
+    .unwrap();
+    let _ = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::TransferNft {
+            recipient: OWNER.to_string(),
+            token_id: "1".to_string(),
+        },
+    )
+    .unwrap();
+}
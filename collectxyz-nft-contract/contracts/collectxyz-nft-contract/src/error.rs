@@ -28,6 +28,18 @@ pub enum ContractError {
 
     #[error("Move already in progress")]
     MoveInProgress {},
+
+    #[error("No cancellable move in progress")]
+    NoMoveInProgress {},
+
+    #[error("Move route has too many waypoints")]
+    TooManyWaypoints {},
+
+    #[error("Token is locked")]
+    Locked {},
+
+    #[error("Lock must expire in the future")]
+    InvalidLockExpiry {},
 }
 
 impl From<CW721ContractError> for ContractError {
@@ -1,22 +1,41 @@
 use rsa::{hash::Hash, padding::PaddingScheme, PublicKey};
+use serde::Serialize;
 use serde_json;
 use sha2::{Digest, Sha256};
 
 use collectxyz::nft::{
     base64_token_image, full_token_id, numeric_token_id, Config, Coordinates, ExecuteMsg,
-    InstantiateMsg, MigrateMsg, XyzExtension, XyzTokenInfo,
+    InstantiateMsg, MigrateMsg, XyzExtension, XyzLock, XyzTokenInfo,
 };
 use cosmwasm_std::{
-    Attribute, BankMsg, Binary, Coin, DepsMut, Empty, Env, MessageInfo, Order, Response, StdError,
-    StdResult, Storage,
+    to_binary, Attribute, BankMsg, Binary, Coin, DepsMut, Empty, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Storage, Timestamp, Uint128, WasmMsg,
 };
 use cw721::{ContractInfoResponse, Cw721ReceiveMsg};
 use cw721_base::{msg::ExecuteMsg as Cw721ExecuteMsg, Cw721Contract};
+use cw_storage_plus::Bound;
 
 use crate::error::ContractError;
-use crate::state::{load_captcha_public_key, save_captcha_public_key, tokens, CONFIG, OWNER};
+use crate::state::{
+    load_active_locks, load_captcha_public_key, save_captcha_public_key, tokens, MoveRoute,
+    CONFIG, LOCKS, OWNER, ROUTES,
+};
 
 const XYZ: &str = "xyz";
+const MAX_WAYPOINTS: usize = 10;
+const DEFAULT_REINDEX_LIMIT: u32 = 100;
+const MAX_REINDEX_LIMIT: u32 = 300;
+
+/// The resources contract's burn message, used to burn move fuel
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ResourceExecuteMsg {
+    BurnFrom {
+        owner: String,
+        amount: Uint128,
+        owner_xyz_id: String,
+    },
+}
 
 pub fn instantiate(deps: DepsMut, info: MessageInfo, msg: InstantiateMsg) -> StdResult<Response> {
     let cw721_contract = Cw721Contract::<Coordinates, Empty>::default();
@@ -29,6 +48,7 @@ pub fn instantiate(deps: DepsMut, info: MessageInfo, msg: InstantiateMsg) -> Std
         .contract_info
         .save(deps.storage, &contract_info)?;
 
+    msg.config.check_jump_gates()?;
     CONFIG.save(deps.storage, &msg.config)?;
     OWNER.save(deps.storage, &info.sender.to_string())?;
 
@@ -176,12 +196,25 @@ fn check_coordinates(storage: &dyn Storage, coords: &Coordinates) -> Result<(),
     }
 }
 
+fn check_unlocked(
+    storage: &dyn Storage,
+    token_id: &str,
+    current_time: Timestamp,
+) -> Result<(), ContractError> {
+    if load_active_locks(storage, token_id, current_time)?.is_empty() {
+        Ok(())
+    } else {
+        Err(ContractError::Locked {})
+    }
+}
+
 pub fn execute_move(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     token_id: String,
     coordinates: Coordinates,
+    waypoints: Vec<Coordinates>,
 ) -> Result<Response, ContractError> {
     let owner = OWNER.load(deps.storage)?;
     let config = CONFIG.load(deps.storage)?;
@@ -197,30 +230,193 @@ pub fn execute_move(
         return Err(ContractError::MoveInProgress {});
     }
 
-    // check that a non-owner sent funds greater than the move fee
-    if owner != info.sender {
-        let move_fee = config.get_move_fee(token.extension.coordinates, coordinates);
-        check_sufficient_funds(info.funds, move_fee)?;
+    // check that the token isn't locked by a game contract
+    check_unlocked(deps.storage, &token_id, env.block.time)?;
+
+    // check that the waypoints are in bounds
+    if waypoints.len() > MAX_WAYPOINTS {
+        return Err(ContractError::TooManyWaypoints {});
+    }
+    for waypoint in waypoints.iter() {
+        config.check_bounds(*waypoint).map_err(ContractError::Std)?;
+    }
+
+    // check that a non-owner sent funds greater than the move fee, or burn
+    // fuel from the token's balance if moves are fuel-powered
+    let mut move_fee = config.get_route_fee(token.extension.coordinates, &waypoints, coordinates);
+    let move_fuel = config.get_route_fuel(token.extension.coordinates, &waypoints, coordinates);
+    let mut messages: Vec<WasmMsg> = vec![];
+    if owner == info.sender {
+        move_fee.amount = Uint128::zero();
+    } else if let Some(move_fuel) = move_fuel {
+        // NOTE: this burn will fail if the token has an insufficient fuel balance,
+        // or hasn't approved this contract to burn it.
+        messages.push(WasmMsg::Execute {
+            contract_addr: move_fuel.resource_contract.to_string(),
+            msg: to_binary(&ResourceExecuteMsg::BurnFrom {
+                owner: info.sender.to_string(),
+                amount: move_fuel.amount,
+                owner_xyz_id: token_id.clone(),
+            })?,
+            funds: vec![],
+        });
+        move_fee.amount = Uint128::zero();
+    } else {
+        check_sufficient_funds(info.funds, move_fee.clone())?;
     }
 
     // check that move target is unoccupied and in bounds
     check_coordinates(deps.storage, &coordinates)?;
 
+    // record the route so the move can be interpolated and cancelled
+    let mut legs = config.get_route_legs(
+        token.extension.coordinates,
+        &waypoints,
+        coordinates,
+        env.block.time,
+    );
+    if move_fee.amount.is_zero() {
+        for leg in legs.iter_mut() {
+            leg.fee = Uint128::zero();
+        }
+    }
+    let arrival = legs.last().map(|leg| leg.arrival).unwrap_or(env.block.time);
+    ROUTES.save(
+        deps.storage,
+        &token_id,
+        &MoveRoute {
+            legs,
+            fee: move_fee,
+            payer: info.sender.clone(),
+        },
+    )?;
+
     // update token with new coordinates, prev coordinates, and arrival time
     let mut new_token = token.clone();
     new_token.image = Some(base64_token_image(&coordinates));
     new_token.extension.coordinates = coordinates;
     new_token.extension.prev_coordinates = Some(token.extension.coordinates);
-    let travel_time_nanos = config.get_move_nanos(token.extension.coordinates, coordinates);
-    new_token.extension.arrival = env.block.time.plus_nanos(travel_time_nanos);
+    new_token.extension.arrival = arrival;
     tokens().replace(deps.storage, &token_id, Some(&new_token), Some(&token))?;
 
     Ok(Response::default()
+        .add_messages(messages)
         .add_attribute("action", "move")
         .add_attribute("mover", info.sender)
         .add_attribute("token_id", numeric_token_id(token_id)?))
 }
 
+pub fn execute_cancel_move(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let token = tokens().load(deps.storage, &token_id)?;
+
+    // check that the sender owns the token
+    if token.owner != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // check that the token isn't locked by a game contract
+    check_unlocked(deps.storage, &token_id, env.block.time)?;
+
+    // check that a recorded move is currently in progress
+    let route = match ROUTES.may_load(deps.storage, &token_id)? {
+        Some(route) if !token.extension.has_arrived(env.block.time) => route,
+        _ => return Err(ContractError::NoMoveInProgress {}),
+    };
+    let position = route
+        .current_leg(env.block.time)
+        .map(|leg| leg.position_at(env.block.time))
+        .unwrap_or(token.extension.coordinates);
+
+    // check that the stopping position is unoccupied
+    if position != token.extension.coordinates {
+        check_coordinates(deps.storage, &position)?;
+    }
+
+    // refund the fee of each leg in proportion to its untravelled distance to
+    // whoever paid it, even if the token has changed hands since; burned fuel
+    // isn't refunded. The refund comes out of the contract balance, so this
+    // fails if the owner has withdrawn the fees of moves still in progress.
+    let refund = route.refund(env.block.time);
+    ROUTES.remove(deps.storage, &token_id);
+
+    // stop the token at its current position
+    let mut new_token = token.clone();
+    new_token.image = Some(base64_token_image(&position));
+    new_token.extension.coordinates = position;
+    new_token.extension.arrival = env.block.time;
+    tokens().replace(deps.storage, &token_id, Some(&new_token), Some(&token))?;
+
+    let mut response = Response::new();
+    if !refund.is_zero() {
+        response = response.add_message(BankMsg::Send {
+            to_address: route.payer.to_string(),
+            amount: vec![Coin::new(refund.u128(), route.fee.denom)],
+        });
+    }
+
+    Ok(response
+        .add_attribute("action", "cancel_move")
+        .add_attribute("mover", info.sender)
+        .add_attribute("refund", refund)
+        .add_attribute("refunded_to", route.payer)
+        .add_attribute("token_id", numeric_token_id(token_id)?))
+}
+
+pub fn execute_lock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_id: String,
+    expires_at: Timestamp,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check that the sender is an authorized lock operator
+    if !config.lock_operators.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if expires_at <= env.block.time {
+        return Err(ContractError::InvalidLockExpiry {});
+    }
+
+    // check that the token exists
+    tokens().load(deps.storage, &token_id)?;
+
+    // a repeat lock by the same operator replaces its previous expiry
+    let lock = XyzLock {
+        locker: info.sender.clone(),
+        expires_at,
+    };
+    LOCKS.save(deps.storage, (&token_id, info.sender.as_str()), &lock)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "lock")
+        .add_attribute("locker", info.sender)
+        .add_attribute("expires_at", expires_at.to_string())
+        .add_attribute("token_id", numeric_token_id(token_id)?))
+}
+
+pub fn execute_unlock(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    // check that the sender holds a lock on the token
+    LOCKS.load(deps.storage, (&token_id, info.sender.as_str()))?;
+    LOCKS.remove(deps.storage, (&token_id, info.sender.as_str()));
+
+    Ok(Response::new()
+        .add_attribute("action", "unlock")
+        .add_attribute("locker", info.sender)
+        .add_attribute("token_id", numeric_token_id(token_id)?))
+}
+
 pub fn execute_update_config(
     deps: DepsMut,
     info: MessageInfo,
@@ -230,6 +426,7 @@ pub fn execute_update_config(
     if info.sender != owner {
         return Err(ContractError::Unauthorized {});
     }
+    config.check_jump_gates()?;
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::new().add_attribute("action", "update_config"))
 }
@@ -250,6 +447,40 @@ pub fn execute_update_captcha_public_key(
     Ok(Response::new().add_attribute("action", "update_captcha_public_key"))
 }
 
+pub fn execute_reindex_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let owner = OWNER.load(deps.storage)?;
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let limit = limit
+        .unwrap_or(DEFAULT_REINDEX_LIMIT)
+        .min(MAX_REINDEX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let page = tokens()
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // re-save each token, removing and re-adding its index entries
+    let mut last_token_id = String::new();
+    for (token_id, token) in page.iter() {
+        let token_id = String::from_utf8(token_id.clone()).map_err(StdError::invalid_utf8)?;
+        tokens().replace(deps.storage, &token_id, Some(token), Some(token))?;
+        last_token_id = token_id;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "reindex_tokens")
+        .add_attribute("count", page.len().to_string())
+        .add_attribute("last_token_id", last_token_id))
+}
+
 pub fn execute_withdraw(
     deps: DepsMut,
     _env: Env,
@@ -308,6 +539,15 @@ pub fn cw721_base_execute(
         _ => cw721_msg,
     };
 
+    // check that locked tokens aren't transferred
+    match &cw721_msg_full_token_id {
+        Cw721ExecuteMsg::TransferNft { token_id, .. }
+        | Cw721ExecuteMsg::SendNft { token_id, .. } => {
+            check_unlocked(deps.storage, token_id, env.block.time)?
+        }
+        _ => {}
+    }
+
     let mut response = (match cw721_msg_full_token_id {
         Cw721ExecuteMsg::SendNft {
             contract,
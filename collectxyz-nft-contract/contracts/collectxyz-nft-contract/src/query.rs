@@ -2,15 +2,18 @@ use rsa::pkcs8::ToPublicKey;
 
 use collectxyz::nft::{
     full_token_id, numeric_token_id, Config, Coordinates, Cw721AllNftInfoResponse,
-    Cw721NftInfoResponse, MoveParamsResponse, QueryMsg, XyzExtension, XyzTokenInfo,
-    XyzTokensResponse,
+    Cw721NftInfoResponse, JumpGatesResponse, LocksResponse, MoveParamsResponse,
+    PositionResponse, QueryMsg, XyzExtension, XyzTokenInfo, XyzTokensResponse,
+};
+use cosmwasm_std::{
+    to_binary, Binary, BlockInfo, Deps, Empty, Env, Order, StdError, StdResult, Storage,
+    Timestamp, Uint128,
 };
-use cosmwasm_std::{to_binary, Binary, BlockInfo, Deps, Empty, Env, Order, StdError, StdResult};
 use cw721::{NumTokensResponse, OwnerOfResponse, TokensResponse};
 use cw721_base::{msg::QueryMsg as Cw721QueryMsg, Cw721Contract};
 use cw_storage_plus::Bound;
 
-use crate::state::{load_captcha_public_key, tokens, CONFIG};
+use crate::state::{load_active_locks, load_captcha_public_key, tokens, CONFIG, ROUTES};
 
 pub fn query_config(deps: Deps) -> StdResult<Config> {
     CONFIG.load(deps.storage)
@@ -44,6 +47,8 @@ pub fn query_xyz_nft_info_by_coords(deps: Deps, coords: Coordinates) -> StdResul
 
 const DEFAULT_LIMIT: u32 = 10;
 const MAX_LIMIT: u32 = 30;
+// the most index entries a spatial query may scan
+const MAX_SCANNED: usize = 300;
 
 pub fn query_xyz_tokens(
     deps: Deps,
@@ -82,6 +87,126 @@ pub fn query_all_xyz_tokens(
     Ok(XyzTokensResponse { tokens: tokens? })
 }
 
+/// Scans the Morton index between the codes of the box corners, skipping ahead
+/// past each part of that range outside the box. Each scanned token uses up one
+/// of `budget`, and running out is an error rather than a partial result.
+fn tokens_in_box(
+    storage: &dyn Storage,
+    min: Coordinates,
+    max: Coordinates,
+    start_after: Option<Coordinates>,
+    limit: usize,
+    budget: &mut usize,
+) -> StdResult<Vec<XyzTokenInfo>> {
+    let morton_key = |code: u128| code.to_be_bytes().to_vec();
+    let mut start = match start_after {
+        Some(start_after) => Bound::exclusive(morton_key(start_after.to_morton())),
+        None => Bound::inclusive(morton_key(min.to_morton())),
+    };
+    let end = max.to_morton();
+
+    let mut found = vec![];
+    loop {
+        let mut next_start = None;
+        for item in tokens().idx.morton.range(
+            storage,
+            Some(start),
+            Some(Bound::inclusive(morton_key(end))),
+            Order::Ascending,
+        ) {
+            if *budget == 0 {
+                return Err(StdError::generic_err(
+                    "too many tokens scanned, narrow the search",
+                ));
+            }
+            *budget -= 1;
+
+            let (_, token) = item?;
+            let coordinates = token.extension.coordinates;
+            if !coordinates.is_within(min, max) {
+                next_start = Coordinates::next_morton_in_box(coordinates.to_morton(), min, max);
+                break;
+            }
+            found.push(token);
+            if found.len() >= limit {
+                return Ok(found);
+            }
+        }
+
+        match next_start {
+            Some(code) => start = Bound::inclusive(morton_key(code)),
+            None => return Ok(found),
+        }
+    }
+}
+
+pub fn query_xyz_tokens_in_box(
+    deps: Deps,
+    min: Coordinates,
+    max: Coordinates,
+    start_after: Option<Coordinates>,
+    limit: Option<u32>,
+) -> StdResult<XyzTokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    if min.x > max.x || min.y > max.y || min.z > max.z {
+        return Err(StdError::generic_err("box min must not exceed its max"));
+    }
+
+    let mut budget = MAX_SCANNED;
+    let tokens = tokens_in_box(deps.storage, min, max, start_after, limit, &mut budget)?;
+    Ok(XyzTokensResponse { tokens })
+}
+
+pub fn query_nearest_xyz(
+    deps: Deps,
+    coords: Coordinates,
+    limit: Option<u32>,
+) -> StdResult<XyzTokensResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let config = CONFIG.load(deps.storage)?;
+    config.check_bounds(coords)?;
+
+    // search boxes of doubling radius until enough tokens are within move
+    // distance of the radius, since every such token is inside the box
+    let bound = config.max_coordinate_value;
+    let mut budget = MAX_SCANNED;
+    let mut radius = 1;
+    loop {
+        let min = Coordinates {
+            x: (coords.x - radius).max(-bound),
+            y: (coords.y - radius).max(-bound),
+            z: (coords.z - radius).max(-bound),
+        };
+        let max = Coordinates {
+            x: (coords.x + radius).min(bound),
+            y: (coords.y + radius).min(bound),
+            z: (coords.z + radius).min(bound),
+        };
+        let mut tokens: Vec<_> =
+            tokens_in_box(deps.storage, min, max, None, usize::MAX, &mut budget)?
+                .into_iter()
+                .map(|token| (coords.distance(token.extension.coordinates), token))
+                .collect();
+
+        let num_within_radius = tokens
+            .iter()
+            .filter(|(distance, _)| *distance <= radius as u64)
+            .count();
+        if num_within_radius >= limit || radius >= 2 * bound {
+            tokens.sort_by_key(|(distance, _)| *distance);
+            return Ok(XyzTokensResponse {
+                tokens: tokens
+                    .into_iter()
+                    .take(limit)
+                    .map(|(_, token)| token)
+                    .collect(),
+            });
+        }
+        radius *= 2;
+    }
+}
+
 pub fn query_num_tokens_for_owner(deps: Deps, owner: String) -> StdResult<NumTokensResponse> {
     let owner = deps.api.addr_validate(&owner)?;
     let count = tokens()
@@ -97,20 +222,68 @@ pub fn query_move_params(
     deps: Deps,
     token_id: String,
     coordinates: Coordinates,
+    waypoints: Vec<Coordinates>,
 ) -> StdResult<MoveParamsResponse> {
     let config = CONFIG.load(deps.storage)?;
     let token = tokens().load(deps.storage, &token_id)?;
 
+    for waypoint in waypoints.iter() {
+        config.check_bounds(*waypoint)?;
+    }
     config.check_bounds(coordinates)?;
 
-    let fee = config.get_move_fee(token.extension.coordinates, coordinates);
-    let duration_nanos = config.get_move_nanos(token.extension.coordinates, coordinates);
+    let start = token.extension.coordinates;
+    let mut fee = config.get_route_fee(start, &waypoints, coordinates);
+    let duration_nanos = config.get_route_nanos(start, &waypoints, coordinates);
+    let fuel = config.get_route_fuel(start, &waypoints, coordinates);
+    if fuel.is_some() {
+        fee.amount = Uint128::zero();
+    }
     Ok(MoveParamsResponse {
         fee,
         duration_nanos,
+        fuel,
     })
 }
 
+pub fn query_position(
+    deps: Deps,
+    env: Env,
+    token_id: String,
+    timestamp: Option<Timestamp>,
+) -> StdResult<PositionResponse> {
+    let token = tokens().load(deps.storage, &token_id)?;
+    let time = timestamp.unwrap_or(env.block.time);
+    let in_transit = !token.extension.has_arrived(time);
+
+    // times before the latest move's departure resolve to its starting point, and
+    // moves made before routes were recorded resolve to their destination
+    let coordinates = match ROUTES.may_load(deps.storage, &token_id)? {
+        Some(route) if in_transit => route
+            .current_leg(time)
+            .map(|leg| leg.position_at(time))
+            .unwrap_or(token.extension.coordinates),
+        _ => token.extension.coordinates,
+    };
+
+    Ok(PositionResponse {
+        coordinates,
+        in_transit,
+    })
+}
+
+pub fn query_jump_gates(deps: Deps) -> StdResult<JumpGatesResponse> {
+    let config = CONFIG.load(deps.storage)?;
+    Ok(JumpGatesResponse {
+        jump_gates: config.jump_gates,
+    })
+}
+
+pub fn query_locks(deps: Deps, env: Env, token_id: String) -> StdResult<LocksResponse> {
+    let locks = load_active_locks(deps.storage, &token_id, env.block.time)?;
+    Ok(LocksResponse { locks })
+}
+
 pub fn cw721_base_query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     let cw721_contract = Cw721Contract::<XyzExtension, Empty>::default();
 
@@ -1,8 +1,10 @@
 use rsa::{pkcs8::FromPublicKey, RsaPublicKey};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
-use collectxyz::nft::{Config, XyzTokenInfo};
-use cosmwasm_std::{Addr, StdError, StdResult, Storage};
-use cw_storage_plus::{Index, IndexList, IndexedMap, Item, MultiIndex, UniqueIndex};
+use collectxyz::nft::{Config, MoveLeg, XyzLock, XyzTokenInfo};
+use cosmwasm_std::{Addr, Coin, Order, StdError, StdResult, Storage, Timestamp, Uint128};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex, UniqueIndex};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 
@@ -22,11 +24,12 @@ pub fn load_captcha_public_key(storage: &dyn Storage) -> StdResult<RsaPublicKey>
 pub struct TokenIndexes<'a> {
     pub owner: MultiIndex<'a, (Addr, Vec<u8>), XyzTokenInfo>,
     pub coordinates: UniqueIndex<'a, Vec<u8>, XyzTokenInfo>,
+    pub morton: UniqueIndex<'a, Vec<u8>, XyzTokenInfo>,
 }
 
 impl<'a> IndexList<XyzTokenInfo> for TokenIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<XyzTokenInfo>> + '_> {
-        let v: Vec<&dyn Index<XyzTokenInfo>> = vec![&self.owner, &self.coordinates];
+        let v: Vec<&dyn Index<XyzTokenInfo>> = vec![&self.owner, &self.coordinates, &self.morton];
         Box::new(v.into_iter())
     }
 }
@@ -42,8 +45,53 @@ pub fn tokens<'a>() -> IndexedMap<'a, &'a str, XyzTokenInfo, TokenIndexes<'a>> {
             |d: &XyzTokenInfo| d.extension.coordinates.to_bytes(),
             "tokens__coordinates",
         ),
+        morton: UniqueIndex::new(
+            |d: &XyzTokenInfo| d.extension.coordinates.to_morton().to_be_bytes().to_vec(),
+            "tokens__morton",
+        ),
     };
     IndexedMap::new("tokens", indexes)
 }
 
 pub const OWNER: Item<String> = Item::new("owner");
+
+/// The route of a token's latest move, and the fee paid for it and by whom
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MoveRoute {
+    pub legs: Vec<MoveLeg>,
+    pub fee: Coin,
+    pub payer: Addr,
+}
+
+impl MoveRoute {
+    /// The current leg at the given time, if the route hasn't been completed
+    pub fn current_leg(&self, time: Timestamp) -> Option<&MoveLeg> {
+        self.legs.iter().find(|leg| time < leg.arrival)
+    }
+
+    /// The fee for the legs left to travel from the given time, each in
+    /// proportion to its own untravelled distance
+    pub fn refund(&self, time: Timestamp) -> Uint128 {
+        self.legs.iter().map(|leg| leg.remaining_fee(time)).sum()
+    }
+}
+
+pub const ROUTES: Map<&str, MoveRoute> = Map::new("routes");
+
+/// Locks keyed by (token_id, locker address)
+pub const LOCKS: Map<(&str, &str), XyzLock> = Map::new("locks");
+pub fn load_active_locks(
+    storage: &dyn Storage,
+    token_id: &str,
+    current_time: Timestamp,
+) -> StdResult<Vec<XyzLock>> {
+    LOCKS
+        .prefix(token_id)
+        .range(storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, lock)| lock))
+        .filter(|lock| match lock {
+            Ok(lock) => lock.is_active(current_time),
+            Err(_) => true,
+        })
+        .collect()
+}
@@ -79,10 +79,68 @@ pub struct Config {
     /// a step is a one-dimensional coordinate increment or decrement.
     /// Assumed to be in the denom associated with base_move_fee.
     pub move_fee_per_step: Uint128,
+    /// Game contracts authorized to lock tokens for the length of a task.
+    /// A locked token can't be transferred, sent, or moved.
+    #[serde(default)]
+    pub lock_operators: Vec<Addr>,
+    /// If set, moves burn this resource from the moving xyz's balance
+    /// instead of charging the native move fee.
+    #[serde(default)]
+    pub move_fuel: Option<MoveFuel>,
+    /// Coordinate pairs linked by a fixed travel time and fee, used instead
+    /// of the distance-based ones for moves between them.
+    #[serde(default)]
+    pub jump_gates: Vec<JumpGate>,
+}
+
+/// A pair of coordinates linked by a jump in either direction
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct JumpGate {
+    pub a: Coordinates,
+    pub b: Coordinates,
+    /// The time a jump takes, regardless of distance
+    pub travel_nanos: u64,
+    /// The fee for a jump, regardless of distance.
+    /// Assumed to be in the denom associated with base_move_fee.
+    pub fee: Uint128,
+}
+
+impl JumpGate {
+    pub fn connects(&self, start: Coordinates, end: Coordinates) -> bool {
+        (self.a == start && self.b == end) || (self.a == end && self.b == start)
+    }
+}
+
+/// A resource burned to power moves. To get overall fuel per move:
+///   base_amount + amount_per_step * distance
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MoveFuel {
+    /// The resource's identifier, like "xyzGAS"
+    pub resource_id: String,
+    /// The resources contract the fuel is burned from
+    pub resource_contract: Addr,
+    pub base_amount: Uint128,
+    pub amount_per_step: Uint128,
+}
+
+/// The fuel needed for a move
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MoveFuelCost {
+    pub resource_id: String,
+    pub resource_contract: Addr,
+    pub amount: Uint128,
 }
 
 impl Config {
+    /// The jump gate between start and end, if any
+    pub fn get_jump_gate(&self, start: Coordinates, end: Coordinates) -> Option<&JumpGate> {
+        self.jump_gates.iter().find(|gate| gate.connects(start, end))
+    }
+
     pub fn get_move_fee(&self, start: Coordinates, end: Coordinates) -> Coin {
+        if let Some(gate) = self.get_jump_gate(start, end) {
+            return Coin::new(gate.fee.u128(), &self.base_move_fee.denom);
+        }
         let distance = start.distance(end) as u128;
         let move_fee_amount =
             self.base_move_fee.amount.u128() + self.move_fee_per_step.u128() * distance;
@@ -90,10 +148,108 @@ impl Config {
     }
 
     pub fn get_move_nanos(&self, start: Coordinates, end: Coordinates) -> u64 {
+        if let Some(gate) = self.get_jump_gate(start, end) {
+            return gate.travel_nanos;
+        }
         let distance = start.distance(end) as u64;
         self.base_move_nanos + self.move_nanos_per_step * distance
     }
 
+    /// The fee for a route through each waypoint in order, then to end.
+    /// Each leg is charged as a separate move.
+    pub fn get_route_fee(
+        &self,
+        start: Coordinates,
+        waypoints: &[Coordinates],
+        end: Coordinates,
+    ) -> Coin {
+        let mut route = vec![start];
+        route.extend_from_slice(waypoints);
+        route.push(end);
+        let route_fee_amount: u128 = route
+            .windows(2)
+            .map(|leg| self.get_move_fee(leg[0], leg[1]).amount.u128())
+            .sum();
+        Coin::new(route_fee_amount, &self.base_move_fee.denom)
+    }
+
+    /// The fuel for a route through each waypoint in order, then to end,
+    /// if moves are fuel-powered. Each leg is charged as a separate move.
+    pub fn get_route_fuel(
+        &self,
+        start: Coordinates,
+        waypoints: &[Coordinates],
+        end: Coordinates,
+    ) -> Option<MoveFuelCost> {
+        let move_fuel = self.move_fuel.as_ref()?;
+        let mut route = vec![start];
+        route.extend_from_slice(waypoints);
+        route.push(end);
+        let amount: u128 = route
+            .windows(2)
+            .map(|leg| {
+                // jumps only burn the base amount
+                let distance = match self.get_jump_gate(leg[0], leg[1]) {
+                    Some(_) => 0,
+                    None => leg[0].distance(leg[1]) as u128,
+                };
+                move_fuel.base_amount.u128() + move_fuel.amount_per_step.u128() * distance
+            })
+            .sum();
+        Some(MoveFuelCost {
+            resource_id: move_fuel.resource_id.clone(),
+            resource_contract: move_fuel.resource_contract.clone(),
+            amount: Uint128::new(amount),
+        })
+    }
+
+    /// The travel time for a route through each waypoint in order, then to end.
+    pub fn get_route_nanos(
+        &self,
+        start: Coordinates,
+        waypoints: &[Coordinates],
+        end: Coordinates,
+    ) -> u64 {
+        let mut route = vec![start];
+        route.extend_from_slice(waypoints);
+        route.push(end);
+        route
+            .windows(2)
+            .map(|leg| self.get_move_nanos(leg[0], leg[1]))
+            .sum()
+    }
+
+    /// The legs of a route through each waypoint in order, then to end,
+    /// departing at the given time. Each leg takes as long as a separate move.
+    pub fn get_route_legs(
+        &self,
+        start: Coordinates,
+        waypoints: &[Coordinates],
+        end: Coordinates,
+        departure: Timestamp,
+    ) -> Vec<MoveLeg> {
+        let mut route = vec![start];
+        route.extend_from_slice(waypoints);
+        route.push(end);
+        let mut departure = departure;
+        route
+            .windows(2)
+            .map(|leg| {
+                let arrival = departure.plus_nanos(self.get_move_nanos(leg[0], leg[1]));
+                let move_leg = MoveLeg {
+                    start: leg[0],
+                    end: leg[1],
+                    departure,
+                    arrival,
+                    jump: self.get_jump_gate(leg[0], leg[1]).is_some(),
+                    fee: self.get_move_fee(leg[0], leg[1]).amount,
+                };
+                departure = arrival;
+                move_leg
+            })
+            .collect()
+    }
+
     pub fn check_bounds(&self, coords: Coordinates) -> StdResult<()> {
         let min_coordinate_value = -self.max_coordinate_value;
         if vec![coords.x, coords.y, coords.z]
@@ -108,6 +264,19 @@ impl Config {
         }
         Ok(())
     }
+
+    pub fn check_jump_gates(&self) -> StdResult<()> {
+        for gate in self.jump_gates.iter() {
+            self.check_bounds(gate.a)?;
+            self.check_bounds(gate.b)?;
+            if gate.a == gate.b {
+                return Err(StdError::generic_err(
+                    "jump gate must link two different coordinates",
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
@@ -133,6 +302,116 @@ impl Coordinates {
         // the distance will always be positive, since it's a sum of absolute values
         distance.try_into().unwrap()
     }
+
+    /// The Z-order (Morton) code of the coordinates, interleaving the bits of
+    /// each dimension so that nearby coordinates tend to have nearby codes.
+    /// Every code in a box lies between the codes of its min and max corners.
+    /// Coordinate values are assumed to fit in an i32.
+    pub fn to_morton(&self) -> u128 {
+        fn spread(value: i64) -> u128 {
+            // offset into an unsigned value that preserves ordering
+            let value = (value.clamp(i32::MIN as i64, i32::MAX as i64) - i32::MIN as i64) as u128;
+            (0..32).fold(0, |code, bit| code | ((value >> bit) & 1) << (3 * bit))
+        }
+        (spread(self.x) << 2) | (spread(self.y) << 1) | spread(self.z)
+    }
+
+    /// The smallest Morton code greater than `code` that is within the box with
+    /// the given min and max corners, if any. This is the BIGMIN of Tropf and
+    /// Herzog, used to skip the parts of a Z-order range outside a box.
+    pub fn next_morton_in_box(code: u128, min: Coordinates, max: Coordinates) -> Option<u128> {
+        let mut min_code = min.to_morton();
+        let mut max_code = max.to_morton();
+        let mut next = None;
+        for bit in (0..96).rev() {
+            let mask = 1u128 << bit;
+            // the lower bits of the same dimension as this bit
+            let lower = (1..=bit / 3).fold(0u128, |lower, i| lower | (1 << (bit - 3 * i)));
+            match (code & mask != 0, min_code & mask != 0, max_code & mask != 0) {
+                (false, false, true) => {
+                    next = Some((min_code & !lower) | mask);
+                    max_code = (max_code | lower) & !mask;
+                }
+                (false, true, true) => return Some(min_code),
+                (true, false, false) => return next,
+                (true, false, true) => min_code = (min_code & !lower) | mask,
+                // equal bits narrow nothing, and min can't exceed max
+                _ => {}
+            }
+        }
+        next
+    }
+
+    /// True if the coordinates are within the box with the given min and max corners
+    pub fn is_within(&self, min: Coordinates, max: Coordinates) -> bool {
+        (min.x..=max.x).contains(&self.x)
+            && (min.y..=max.y).contains(&self.y)
+            && (min.z..=max.z).contains(&self.z)
+    }
+
+    /// The coordinates reached after taking `steps` one-dimensional steps
+    /// towards other, stepping along x, then y, then z.
+    pub fn step_towards(&self, other: Self, steps: u64) -> Self {
+        let mut remaining = steps as i64;
+        let mut step = |from: i64, to: i64| {
+            let taken = (to - from).abs().min(remaining);
+            remaining -= taken;
+            from + (to - from).signum() * taken
+        };
+        Coordinates {
+            x: step(self.x, other.x),
+            y: step(self.y, other.y),
+            z: step(self.z, other.z),
+        }
+    }
+}
+
+/// One straight leg of a move, travelled at a constant rate between
+/// departure and arrival, or a jump that reaches its end on arrival
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
+pub struct MoveLeg {
+    pub start: Coordinates,
+    pub end: Coordinates,
+    pub departure: Timestamp,
+    pub arrival: Timestamp,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub jump: bool,
+    /// The native fee paid for this leg
+    #[serde(default)]
+    pub fee: Uint128,
+}
+
+impl MoveLeg {
+    /// The interpolated position on this leg at the given time
+    pub fn position_at(&self, time: Timestamp) -> Coordinates {
+        if time <= self.departure {
+            return self.start;
+        }
+        if time >= self.arrival {
+            return self.end;
+        }
+        if self.jump {
+            return self.start;
+        }
+        let elapsed = (time.nanos() - self.departure.nanos()) as u128;
+        let duration = (self.arrival.nanos() - self.departure.nanos()) as u128;
+        let steps = self.start.distance(self.end) as u128 * elapsed / duration;
+        self.start.step_towards(self.end, steps as u64)
+    }
+
+    /// The part of this leg's fee for the distance left at the given time.
+    /// A jump hasn't moved until it arrives, so its whole fee is left.
+    pub fn remaining_fee(&self, time: Timestamp) -> Uint128 {
+        let distance = self.start.distance(self.end);
+        if time >= self.arrival {
+            return Uint128::zero();
+        }
+        if self.jump || distance == 0 {
+            return self.fee;
+        }
+        let remaining = self.position_at(time).distance(self.end);
+        self.fee.multiply_ratio(remaining, distance)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Copy)]
@@ -245,21 +524,48 @@ pub enum ExecuteMsg {
         coordinates: Coordinates,
         captcha_signature: String,
     },
-    /// Move an existing NFT to the given set of coordinates.
+    /// Move an existing NFT to the given set of coordinates, optionally
+    /// travelling through each of the given waypoints in order first.
     Move {
         token_id: String,
         coordinates: Coordinates,
+        waypoints: Option<Vec<Coordinates>>,
+    },
+    /// Stop an in-progress move at the NFT's current position on its route,
+    /// refunding each leg's fee for its untravelled distance to the account
+    /// that paid for the move. Burned fuel isn't refunded.
+    CancelMove {
+        token_id: String,
+    },
+
+    /// Lock an NFT until the given time, or until the sender unlocks it.
+    /// Only callable by a configured lock operator.
+    Lock {
+        token_id: String,
+        expires_at: Timestamp,
+    },
+    /// Release the sender's lock on an NFT.
+    Unlock {
+        token_id: String,
     },
 
     /// Update token minting and supply configuration.
     UpdateConfig {
-        config: Config,
+        config: Box<Config>,
     },
     /// Update public key used for captcha verification.
     UpdateCaptchaPublicKey {
         public_key: String,
     },
-    /// Withdraw from current contract balance to owner address.
+    /// Re-save a page of tokens so that indexes added since they were stored
+    /// are populated. Only callable by the owner after a migration.
+    ReindexTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Withdraw from current contract balance to owner address. Fees of moves
+    /// still in progress are part of that balance; cancelling such a move fails
+    /// if its refund has been withdrawn.
     Withdraw {
         amount: Vec<Coin>,
     },
@@ -365,17 +671,48 @@ pub enum QueryMsg {
     XyzNftInfoByCoords {
         coordinates: Coordinates,
     },
+    /// Lists tokens within the box with the given min and max corners, in
+    /// Z-order. Paginate by passing the last token's coordinates as start_after.
+    /// Return type: XyzTokensResponse.
+    XyzTokensInBox {
+        min: Coordinates,
+        max: Coordinates,
+        start_after: Option<Coordinates>,
+        limit: Option<u32>,
+    },
+    /// Lists the tokens nearest to the given coordinates by move distance.
+    /// Return type: XyzTokensResponse.
+    NearestXyz {
+        coords: Coordinates,
+        limit: Option<u32>,
+    },
     /// Returns the number of tokens owned by the given address
     /// Return type: NumTokensResponse
     NumTokensForOwner {
         owner: String,
     },
 
-    /// Calculates the price to move the given token to the given coordinate.
+    /// Calculates the price to move the given token to the given coordinate,
+    /// through the given waypoints if any.
     /// Return type: MoveParamsResponse
     MoveParams {
         token_id: String,
         coordinates: Coordinates,
+        waypoints: Option<Vec<Coordinates>>,
+    },
+    /// Returns the position of the given token at the given time, or now if unset.
+    /// Return type: PositionResponse
+    Position {
+        token_id: String,
+        timestamp: Option<Timestamp>,
+    },
+    /// Lists the configured jump gates.
+    /// Return type: JumpGatesResponse
+    JumpGates {},
+    /// Returns the unexpired locks held on the given token.
+    /// Return type: LocksResponse
+    Locks {
+        token_id: String,
     },
 
     // BELOW ARE COPIED FROM CW721-BASE
@@ -479,6 +816,37 @@ pub struct XyzTokensResponse {
 pub struct MoveParamsResponse {
     pub fee: Coin,
     pub duration_nanos: u64,
+    /// The fuel burned instead of the fee, if moves are fuel-powered
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel: Option<MoveFuelCost>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct JumpGatesResponse {
+    pub jump_gates: Vec<JumpGate>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct PositionResponse {
+    pub coordinates: Coordinates,
+    pub in_transit: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct XyzLock {
+    pub locker: Addr,
+    pub expires_at: Timestamp,
+}
+
+impl XyzLock {
+    pub fn is_active(&self, current_time: Timestamp) -> bool {
+        current_time < self.expires_at
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct LocksResponse {
+    pub locks: Vec<XyzLock>,
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
@@ -489,6 +857,81 @@ pub struct MigrateMsg {}
 mod tests {
     use super::*;
 
+    #[test]
+    fn coordinates_to_morton() {
+        let origin = Coordinates { x: 0, y: 0, z: 0 };
+        let corner = Coordinates { x: 1, y: 1, z: 1 };
+        assert_eq!(corner.to_morton() - origin.to_morton(), 0b111);
+        assert_eq!(
+            Coordinates { x: 0, y: 1, z: 0 }.to_morton() - origin.to_morton(),
+            0b010
+        );
+
+        // codes within a box lie between the codes of its corners
+        let min = Coordinates { x: -2, y: -2, z: -2 };
+        let max = Coordinates { x: 2, y: 2, z: 2 };
+        for x in -2..=2 {
+            for y in -2..=2 {
+                for z in -2..=2 {
+                    let code = Coordinates { x, y, z }.to_morton();
+                    assert!(min.to_morton() <= code && code <= max.to_morton());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn coordinates_next_morton_in_box() {
+        let min = Coordinates { x: -1, y: 0, z: -2 };
+        let max = Coordinates { x: 2, y: 1, z: 1 };
+        let mut codes = vec![];
+        for x in -3..=3 {
+            for y in -3..=3 {
+                for z in -3..=3 {
+                    let coords = Coordinates { x, y, z };
+                    codes.push((coords.to_morton(), coords.is_within(min, max)));
+                }
+            }
+        }
+
+        // matches the next in-box code found by brute force
+        for (code, _) in codes.iter().filter(|(_, within)| !within) {
+            let expected = codes
+                .iter()
+                .filter(|(other, within)| *within && other > code)
+                .map(|(other, _)| *other)
+                .min();
+            assert_eq!(Coordinates::next_morton_in_box(*code, min, max), expected);
+        }
+    }
+
+    #[test]
+    fn move_leg_position_at() {
+        let leg = MoveLeg {
+            start: Coordinates { x: 0, y: 0, z: 0 },
+            end: Coordinates { x: 2, y: -2, z: 0 },
+            departure: Timestamp::from_nanos(100),
+            arrival: Timestamp::from_nanos(500),
+            jump: false,
+            fee: Uint128::new(100),
+        };
+
+        assert_eq!(leg.position_at(Timestamp::from_nanos(0)), leg.start);
+        assert_eq!(
+            leg.position_at(Timestamp::from_nanos(200)),
+            Coordinates { x: 1, y: 0, z: 0 }
+        );
+        assert_eq!(
+            leg.position_at(Timestamp::from_nanos(399)),
+            Coordinates { x: 2, y: 0, z: 0 }
+        );
+        assert_eq!(
+            leg.position_at(Timestamp::from_nanos(400)),
+            Coordinates { x: 2, y: -1, z: 0 }
+        );
+        assert_eq!(leg.position_at(Timestamp::from_nanos(500)), leg.end);
+    }
+
     #[test]
     fn xyz_token_info_as_cw721_nft_info() {
         let info = XyzTokenInfo {
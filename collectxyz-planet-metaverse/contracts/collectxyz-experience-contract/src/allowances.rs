@@ -5,8 +5,12 @@ use cosmwasm_std::{
 };
 use collectxyz_experience::{AllowanceResponse, Expiration};
 
+use crate::contract::spend_balance;
 use crate::error::ContractError;
-use crate::state::{ALLOWANCES, BALANCES, TOKEN_INFO};
+use crate::state::{ALLOWANCES, TOKEN_INFO};
+
+/// Purpose that experience destroyed through `BurnFrom` is recorded under in the spent ledger
+pub const BURN_PURPOSE: &str = "burn";
 
 pub fn execute_increase_allowance(
     deps: DepsMut,
@@ -162,19 +166,8 @@ pub fn execute_burn_from(
     // deduct allowance before doing anything else have enough allowance
     deduct_allowance(deps.storage, &owner_addr, owner_xyz_id.to_string(), &info.sender, &env.block, amount)?;
 
-    // lower balance
-    BALANCES.update(
-        deps.storage,
-        &owner_xyz_id.to_string(),
-        |balance: Option<Uint128>| -> StdResult<_> {
-            Ok(balance.unwrap_or_default().checked_sub(amount)?)
-        },
-    )?;
-    // reduce total_supply
-    TOKEN_INFO.update(deps.storage, |mut meta| -> StdResult<_> {
-        meta.total_supply = meta.total_supply.checked_sub(amount)?;
-        Ok(meta)
-    })?;
+    // lower balance and supply, keeping the burn in the spent ledger
+    spend_balance(deps.storage, &owner_xyz_id, BURN_PURPOSE, amount, env.block.height)?;
 
     let res = Response::new().add_attributes(vec![
         attr("action", "burn_from"),
@@ -200,12 +193,13 @@ mod tests {
     use super::*;
 
     use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, NFT_CONTRACT_ADDRESS, NFT_OWNER_ADDRESS, NOW, XYZ_NFT_ID, default_xyz_nft_data, mock_dependencies_custom};
-    use collectxyz_experience::{TokenInfoResponse,XyzExperience, CollectXyzExperienceExecuteMsg};
+    use collectxyz_experience::{TokenInfoResponse,XyzExperience};
     use cosmwasm_std::{Coin, Timestamp};
     use cosmwasm_std::testing::{mock_env, mock_info};
 
-    use crate::contract::{execute, instantiate, query_balance, query_token_info};
-    use crate::msg::{InstantiateMsg};
+    use crate::contract::{execute, instantiate, query_balance, query_experience_info, query_token_info};
+    use crate::enumerable::query_all_spent;
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
 
     fn coins(count: u64, token: &str) -> Vec<Coin> {
         let mut _coins = vec![];
@@ -241,6 +235,7 @@ mod tests {
             mint: None,
             marketing: None,
             xyz_contract_address: Addr::unchecked(NFT_CONTRACT_ADDRESS),
+            level_curve: None,
         };
         let info = mock_info("creator", &[]);
         let env = mock_env();
@@ -277,7 +272,7 @@ mod tests {
         // set allowance with height expiration
         let allow1 = Uint128::new(7777);
         let expires = Expiration::AtHeight(5432);
-        let msg = CollectXyzExperienceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: Some(expires),
@@ -303,7 +298,7 @@ mod tests {
         // decrease it a bit with no expire set - stays the same
         let lower = Uint128::new(4444);
         let allow2 = allow1.checked_sub(lower).unwrap();
-        let msg = CollectXyzExperienceExecuteMsg::DecreaseAllowance {
+        let msg = ExecuteMsg::DecreaseAllowance {
             spender: spender.clone(),
             amount: lower,
             expires: None,
@@ -328,7 +323,7 @@ mod tests {
         let raise = Uint128::new(87654);
         let allow3 = allow2 + raise;
         let new_expire = Expiration::AtTime(Timestamp::from_seconds(8888888888));
-        let msg = CollectXyzExperienceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: raise,
             expires: Some(new_expire),
@@ -350,7 +345,7 @@ mod tests {
         );
 
         // decrease it below 0
-        let msg = CollectXyzExperienceExecuteMsg::DecreaseAllowance {
+        let msg = ExecuteMsg::DecreaseAllowance {
             spender: spender.clone(),
             amount: Uint128::new(99988647623876347),
             expires: None,
@@ -416,7 +411,7 @@ mod tests {
         // set allowance with height expiration
         let allow1 = Uint128::new(7777);
         let expires = Expiration::AtHeight(5432);
-        let msg = CollectXyzExperienceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: Some(expires),
@@ -426,7 +421,7 @@ mod tests {
 
         // set other allowance with no expiration
         let allow2 = Uint128::new(87654);
-        let msg = CollectXyzExperienceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender2.clone(),
             amount: allow2,
             expires: None,
@@ -489,7 +484,7 @@ mod tests {
         do_instantiate(deps.as_mut(), &owner, Uint128::new(12340000));
 
         // self-allowance
-        let msg = CollectXyzExperienceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: owner.clone(),
             amount: Uint128::new(7777),
             expires: None,
@@ -499,7 +494,7 @@ mod tests {
         assert_eq!(err, ContractError::CannotSetOwnAccount {});
 
         // decrease self-allowance
-        let msg = CollectXyzExperienceExecuteMsg::DecreaseAllowance {
+        let msg = ExecuteMsg::DecreaseAllowance {
             spender: owner,
             amount: Uint128::new(7777),
             expires: None,
@@ -527,7 +522,7 @@ mod tests {
 
         // provide an allowance
         let allow1 = Uint128::new(77777);
-        let msg = CollectXyzExperienceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: None,
@@ -539,7 +534,7 @@ mod tests {
 
         // valid burn of part of the allowance
         let transfer = Uint128::new(44444);
-        let msg = CollectXyzExperienceExecuteMsg::BurnFrom {
+        let msg = ExecuteMsg::BurnFrom {
             owner: owner.clone(),
             amount: transfer,
             owner_xyz_id: owner_xyz_id.to_string(),
@@ -555,6 +550,15 @@ mod tests {
             start.checked_sub(transfer).unwrap()
         );
 
+        // the burn is recorded as spent so earned still adds up
+        let experience = query_experience_info(deps.as_ref(), owner_xyz_id.to_string()).unwrap();
+        assert_eq!(experience.earned, start);
+        assert_eq!(experience.spent, transfer);
+        assert_eq!(experience.balance, start.checked_sub(transfer).unwrap());
+        let spent = query_all_spent(deps.as_ref(), owner_xyz_id.to_string(), None, None).unwrap();
+        assert_eq!(spent.spent[0].purpose, BURN_PURPOSE);
+        assert_eq!(spent.spent[0].spent, transfer);
+
         // ensure it looks good
         let allowance = query_allowance(deps.as_ref(), owner.clone(), owner_xyz_id.to_string(), spender.clone()).unwrap();
         let expect = AllowanceResponse {
@@ -564,7 +568,7 @@ mod tests {
         assert_eq!(expect, allowance);
 
         // cannot burn more than the allowance
-        let msg = CollectXyzExperienceExecuteMsg::BurnFrom {
+        let msg = ExecuteMsg::BurnFrom {
             owner: owner.clone(),
             amount: Uint128::new(33443),
             owner_xyz_id: owner_xyz_id.to_string(),
@@ -577,7 +581,7 @@ mod tests {
         // let us increase limit, but set the expiration (default env height is 12_345)
         let info = mock_info(owner.as_ref(), &[]);
         let env = mock_env();
-        let msg = CollectXyzExperienceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: Uint128::new(1000),
             expires: Some(Expiration::AtHeight(env.block.height)),
@@ -586,7 +590,7 @@ mod tests {
         execute(deps.as_mut(), env, info, msg).unwrap();
 
         // we should now get the expiration error
-        let msg = CollectXyzExperienceExecuteMsg::BurnFrom {
+        let msg = ExecuteMsg::BurnFrom {
             owner,
             amount: Uint128::new(33443),
             owner_xyz_id: owner_xyz_id.to_string(),
@@ -626,7 +630,7 @@ mod tests {
         // set allowance with height expiration
         let allow1 = Uint128::new(7777);
         let expires = Expiration::AtHeight(5432);
-        let msg = CollectXyzExperienceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: Some(expires),
@@ -666,7 +670,7 @@ mod tests {
         // set allowance with height expiration
         let allow1 = Uint128::new(7777);
         let expires = Expiration::AtHeight(5432);
-        let msg = CollectXyzExperienceExecuteMsg::DecreaseAllowance {
+        let msg = ExecuteMsg::DecreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: Some(expires),
@@ -1,23 +1,36 @@
-use collectxyz_planet_metaverse::util::{fetch_nft_data};
+use collectxyz_planet_metaverse::util::fetch_nft_data;
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    Addr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+    Addr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError,
+    StdResult, Storage, Uint128,
 };
 
 use cw2::set_contract_version;
+use cw_storage_plus::Bound;
 use collectxyz_experience::{
     BalanceResponse, XyzExperience, DownloadLogoResponse, EmbeddedLogo, Logo, LogoInfo,
     MarketingInfoResponse, MinterResponse, TokenInfoResponse,
 };
 
 use crate::allowances::{
-    execute_burn_from, execute_decrease_allowance, execute_increase_allowance, query_allowance,
+    deduct_allowance, execute_burn_from, execute_decrease_allowance, execute_increase_allowance,
+    query_allowance,
+};
+use crate::enumerable::{
+    query_all_accounts, query_all_allowances, query_all_spent, query_rank, query_top_accounts,
 };
-use crate::enumerable::{query_all_accounts, query_all_allowances};
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use crate::state::{MinterData, XyzPlanetResourceInfo, BALANCES, LOGO, MARKETING_INFO, TOKEN_INFO};
+use crate::level::LevelCurve;
+use crate::msg::{
+    ExecuteMsg, ExperienceInfoResponse, InstantiateMsg, LevelResponse, MigrateMsg, QueryMsg,
+    TotalSupplyResponse,
+};
+use crate::state::{
+    ranking_key, update_balance, update_ranking_counts, MinterData, XyzPlanetResourceInfo,
+    BALANCES, BALANCE_RANKING, EARNED, LEVEL_CURVE, LOGO, MARKETING_INFO, OWNER, SPENT,
+    TOKEN_INFO, TOTAL_SPENT, TOTAL_SUPPLY_HISTORY, TOTAL_SUPPLY_HISTORY_START, TOTAL_SUPPLY_KEY,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:collectxyz-resources-contract";
@@ -25,6 +38,10 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const LOGO_SIZE_CAP: usize = 5 * 1024;
 
+// balances added to the leaderboard ranking per reindex page
+const DEFAULT_REINDEX_LIMIT: u32 = 100;
+const MAX_REINDEX_LIMIT: u32 = 300;
+
 /// Checks if data starts with XML preamble
 fn verify_xml_preamble(data: &[u8]) -> Result<(), ContractError> {
     // The easiest way to perform this check would be just match on regex, however regex
@@ -89,7 +106,7 @@ fn verify_logo(logo: &Logo) -> Result<(), ContractError> {
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
@@ -98,7 +115,7 @@ pub fn instantiate(
     msg.validate()?;
     // create initial accounts
     let total_supply = create_accounts(
-        &mut deps, &msg.initial_balances, &msg.xyz_contract_address
+        &mut deps, &msg.initial_balances, &msg.xyz_contract_address, env.block.height
     )?;
 
     if let Some(limit) = msg.get_caps(info.sender.to_string()) {
@@ -131,6 +148,13 @@ pub fn instantiate(
         xyz_contract_address: msg.xyz_contract_address,
     };
     TOKEN_INFO.save(deps.storage, &data)?;
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &total_supply, env.block.height)?;
+    OWNER.save(deps.storage, &info.sender)?;
+
+    if let Some(level_curve) = msg.level_curve {
+        level_curve.validate()?;
+        LEVEL_CURVE.save(deps.storage, &level_curve)?;
+    }
 
     if let Some(marketing) = msg.marketing {
         let logo = if let Some(logo) = marketing.logo {
@@ -160,14 +184,15 @@ pub fn instantiate(
     Ok(Response::default())
 }
 
-pub fn create_accounts(deps: &mut DepsMut, accounts: &[XyzExperience], xyz_contract_addr: &Addr) -> StdResult<Uint128> {
+pub fn create_accounts(deps: &mut DepsMut, accounts: &[XyzExperience], xyz_contract_addr: &Addr, height: u64) -> StdResult<Uint128> {
     let mut total_supply = Uint128::zero();
     for row in accounts {
         // validate that nft_id exists
         let _ = fetch_nft_data(
             &row.xyz_id, &xyz_contract_addr, &deps.querier
         )?;
-        BALANCES.save(deps.storage, &row.xyz_id, &row.amount)?;
+        update_balance(deps.storage, &row.xyz_id, height, |_| Ok(row.amount))?;
+        EARNED.save(deps.storage, &row.xyz_id, &row.amount)?;
         total_supply += row.amount;
     }
     Ok(total_supply)
@@ -182,6 +207,7 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Mint { recipient_xyz_id, amount } => execute_mint(deps, env, info, recipient_xyz_id, amount),
+        ExecuteMsg::Spend { xyz_id, amount, purpose } => execute_spend(deps, env, info, xyz_id, amount, purpose),
         ExecuteMsg::IncreaseAllowance {
             spender,
             amount,
@@ -201,12 +227,18 @@ pub fn execute(
             marketing,
         } => execute_update_marketing(deps, env, info, project, description, marketing),
         ExecuteMsg::UploadLogo(logo) => execute_upload_logo(deps, env, info, logo),
+        ExecuteMsg::UpdateLevelCurve { level_curve } => {
+            execute_update_level_curve(deps, env, info, level_curve)
+        }
+        ExecuteMsg::ReindexRanking { start_after, limit } => {
+            execute_reindex_ranking(deps, env, info, start_after, limit)
+        }
     }
 }
 
 pub fn execute_mint(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     recipient_xyz_id: String,
     amount: Uint128,
@@ -229,6 +261,7 @@ pub fn execute_mint(
         }
     }
     TOKEN_INFO.save(deps.storage, &config)?;
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &config.total_supply, env.block.height)?;
 
     // validate that the nft id is valid  
     let _ = fetch_nft_data(
@@ -237,20 +270,117 @@ pub fn execute_mint(
         &deps.querier
     )?;
     
-    // add amount to recipient balance
-    BALANCES.update(
+    // add amount to recipient balance and lifetime earned
+    backfill_earned(deps.storage, &recipient_xyz_id)?;
+    let earned_before = load_earned(deps.storage, &recipient_xyz_id)?;
+    update_balance(
         deps.storage,
         &recipient_xyz_id,
+        env.block.height,
         |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
     )?;
+    EARNED.update(
+        deps.storage,
+        &recipient_xyz_id,
+        |earned: Option<Uint128>| -> StdResult<_> { Ok(earned.unwrap_or_default() + amount) },
+    )?;
 
-    let res = Response::new()
+    let mut res = Response::new()
         .add_attribute("action", "mint")
         .add_attribute("to_xyz_id", recipient_xyz_id)
         .add_attribute("amount", amount);
+
+    // let frontends and other contracts know when a mint crosses a level threshold
+    if let Some(level_curve) = LEVEL_CURVE.may_load(deps.storage)? {
+        let level_before = level_curve.level(earned_before);
+        let level_after = level_curve.level(earned_before + amount);
+        if level_after > level_before {
+            res = res.add_attribute("level_up", level_after.to_string());
+        }
+    }
+    Ok(res)
+}
+
+pub fn execute_spend(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    xyz_id: String,
+    amount: Uint128,
+    purpose: String,
+) -> Result<Response, ContractError> {
+    if amount == Uint128::zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    if purpose.trim().is_empty() {
+        return Err(ContractError::InvalidPurpose {});
+    }
+
+    // the sender must own xyz_id, or spend from an allowance its owner granted
+    let config = TOKEN_INFO.load(deps.storage)?;
+    let nft = fetch_nft_data(&xyz_id, &config.xyz_contract_address, &deps.querier)?;
+    if nft.owner != info.sender {
+        deduct_allowance(
+            deps.storage,
+            &nft.owner,
+            xyz_id.clone(),
+            &info.sender,
+            &env.block,
+            amount,
+        )?;
+    }
+
+    spend_balance(deps.storage, &xyz_id, &purpose, amount, env.block.height)?;
+
+    let res = Response::new()
+        .add_attribute("action", "spend")
+        .add_attribute("xyz_id", xyz_id)
+        .add_attribute("purpose", purpose)
+        .add_attribute("by", info.sender)
+        .add_attribute("amount", amount);
     Ok(res)
 }
 
+/// Removes amount from the xyz's balance and the total supply, recording it
+/// in the xyz's spent ledger under purpose so earned stays balance + spent.
+pub fn spend_balance(
+    storage: &mut dyn Storage,
+    xyz_id: &str,
+    purpose: &str,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()> {
+    backfill_earned(storage, xyz_id)?;
+
+    // lower balance
+    update_balance(
+        storage,
+        xyz_id,
+        height,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_sub(amount)?)
+        },
+    )?;
+    // record the spend in the ledger
+    SPENT.update(
+        storage,
+        (xyz_id, purpose),
+        |spent: Option<Uint128>| -> StdResult<_> { Ok(spent.unwrap_or_default() + amount) },
+    )?;
+    TOTAL_SPENT.update(
+        storage,
+        xyz_id,
+        |spent: Option<Uint128>| -> StdResult<_> { Ok(spent.unwrap_or_default() + amount) },
+    )?;
+    // reduce total_supply
+    let meta = TOKEN_INFO.update(storage, |mut meta| -> StdResult<_> {
+        meta.total_supply = meta.total_supply.checked_sub(amount)?;
+        Ok(meta)
+    })?;
+    TOTAL_SUPPLY_HISTORY.save(storage, TOTAL_SUPPLY_KEY, &meta.total_supply, height)?;
+    Ok(())
+}
+
 pub fn execute_update_marketing(
     deps: DepsMut,
     _env: Env,
@@ -339,19 +469,125 @@ pub fn execute_upload_logo(
     Ok(res)
 }
 
+pub fn execute_update_level_curve(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    level_curve: Option<LevelCurve>,
+) -> Result<Response, ContractError> {
+    let owner = OWNER.may_load(deps.storage)?;
+    if owner.map_or(true, |owner| owner != info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match level_curve {
+        Some(level_curve) => {
+            level_curve.validate()?;
+            LEVEL_CURVE.save(deps.storage, &level_curve)?;
+        }
+        None => LEVEL_CURVE.remove(deps.storage),
+    }
+
+    let res = Response::new().add_attribute("action", "update_level_curve");
+    Ok(res)
+}
+
 #[entry_point]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     let mut token_info = TOKEN_INFO.load(deps.storage)?;
     token_info.minters = Some(msg.minters);
     TOKEN_INFO.save(deps.storage, &token_info)?;
-    return Ok(Response::new().add_attribute("method", "migrate"));
+    // total supply history starts from the first migration that records it
+    if TOTAL_SUPPLY_HISTORY_START.may_load(deps.storage)?.is_none() {
+        TOTAL_SUPPLY_HISTORY_START.save(deps.storage, &env.block.height)?;
+    }
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &token_info.total_supply, env.block.height)?;
+    if let Some(owner) = msg.owner {
+        OWNER.save(deps.storage, &deps.api.addr_validate(&owner)?)?;
+    }
+
+    // index the first page of balances that existed before the leaderboard ranking was
+    // introduced, the rest are indexed by ReindexRanking
+    let (count, last_xyz_id) = index_ranking(deps.storage, None, DEFAULT_REINDEX_LIMIT)?;
+    return Ok(Response::new()
+        .add_attribute("method", "migrate")
+        .add_attribute("count", count.to_string())
+        .add_attribute("last_xyz_id", last_xyz_id));
+}
+
+/// Adds up to `limit` balances after `start_after` to the leaderboard ranking. Returns how
+/// many were read and the last xyz id read, to continue from.
+fn index_ranking(
+    storage: &mut dyn Storage,
+    start_after: Option<String>,
+    limit: u32,
+) -> StdResult<(usize, String)> {
+    let balances = BALANCES
+        .range(storage, start_after.map(Bound::exclusive), None, Order::Ascending)
+        .take(limit as usize)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((String::from_utf8(k)?, v))
+        })
+        .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+    for (xyz_id, balance) in balances.iter() {
+        if !balance.is_zero()
+            && BALANCE_RANKING
+                .may_load(storage, ranking_key(*balance, xyz_id))?
+                .is_none()
+        {
+            BALANCE_RANKING.save(storage, ranking_key(*balance, xyz_id), xyz_id)?;
+            update_ranking_counts(storage, None, Some(*balance))?;
+        }
+    }
+    let last_xyz_id = balances.last().map(|(xyz_id, _)| xyz_id.clone()).unwrap_or_default();
+    Ok((balances.len(), last_xyz_id))
+}
+
+/// Continues indexing balances from before the leaderboard ranking was introduced, after
+/// the last xyz id indexed by the migration or a previous call. Requires that the sender
+/// is the contract owner.
+pub fn execute_reindex_ranking(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let owner = OWNER.may_load(deps.storage)?;
+    if owner.map_or(true, |owner| owner != info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_REINDEX_LIMIT).min(MAX_REINDEX_LIMIT);
+    let (count, last_xyz_id) = index_ranking(deps.storage, start_after, limit)?;
+
+    let res = Response::new()
+        .add_attribute("action", "reindex_ranking")
+        .add_attribute("count", count.to_string())
+        .add_attribute("last_xyz_id", last_xyz_id);
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Balance { xyz_id } => to_binary(&query_balance(deps, xyz_id)?),
+        QueryMsg::ExperienceInfo { xyz_id } => to_binary(&query_experience_info(deps, xyz_id)?),
+        QueryMsg::AllSpent {
+            xyz_id,
+            start_after,
+            limit,
+        } => to_binary(&query_all_spent(deps, xyz_id, start_after, limit)?),
+        QueryMsg::TopAccounts { start_after, limit } => {
+            to_binary(&query_top_accounts(deps, start_after, limit)?)
+        }
+        QueryMsg::Rank { xyz_id } => to_binary(&query_rank(deps, xyz_id)?),
+        QueryMsg::Level { xyz_id } => to_binary(&query_level(deps, xyz_id)?),
+        QueryMsg::LevelCurve {} => to_binary(&LEVEL_CURVE.may_load(deps.storage)?),
+        QueryMsg::BalanceAt { xyz_id, height } => to_binary(&query_balance_at(deps, xyz_id, height)?),
+        QueryMsg::TotalSupplyAt { height } => to_binary(&query_total_supply_at(deps, height)?),
         QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
         QueryMsg::Minter {} => to_binary(&query_minters(deps)?),
         QueryMsg::Allowance { owner_xyz_id, owner, spender } => {
@@ -378,6 +614,76 @@ pub fn query_balance(deps: Deps, xyz_id: String) -> StdResult<BalanceResponse> {
     Ok(BalanceResponse { balance })
 }
 
+/// Lifetime earned experience. Accounts created before this was tracked
+/// fall back to their current balance plus everything they've spent.
+fn load_earned(storage: &dyn Storage, xyz_id: &str) -> StdResult<Uint128> {
+    if let Some(earned) = EARNED.may_load(storage, xyz_id)? {
+        return Ok(earned);
+    }
+    let balance = BALANCES.may_load(storage, xyz_id)?.unwrap_or_default();
+    let spent = TOTAL_SPENT.may_load(storage, xyz_id)?.unwrap_or_default();
+    Ok(balance + spent)
+}
+
+/// Persists lifetime earned for accounts created before it was tracked.
+/// Must be called before an account's balance changes.
+pub fn backfill_earned(storage: &mut dyn Storage, xyz_id: &str) -> StdResult<()> {
+    if EARNED.may_load(storage, xyz_id)?.is_none() {
+        let earned = load_earned(storage, xyz_id)?;
+        EARNED.save(storage, xyz_id, &earned)?;
+    }
+    Ok(())
+}
+
+pub fn query_experience_info(deps: Deps, xyz_id: String) -> StdResult<ExperienceInfoResponse> {
+    Ok(ExperienceInfoResponse {
+        earned: load_earned(deps.storage, &xyz_id)?,
+        spent: TOTAL_SPENT.may_load(deps.storage, &xyz_id)?.unwrap_or_default(),
+        balance: BALANCES.may_load(deps.storage, &xyz_id)?.unwrap_or_default(),
+    })
+}
+
+pub fn query_level(deps: Deps, xyz_id: String) -> StdResult<LevelResponse> {
+    let level_curve = LEVEL_CURVE
+        .may_load(deps.storage)?
+        .ok_or_else(|| StdError::not_found("level curve"))?;
+    let experience = load_earned(deps.storage, &xyz_id)?;
+    let level = level_curve.level(experience);
+    let current_threshold = level_curve.threshold(level).unwrap_or_default();
+
+    Ok(LevelResponse {
+        level,
+        experience,
+        progress: experience.checked_sub(current_threshold)?,
+        experience_to_next_level: level_curve
+            .threshold(level + 1)
+            .map(|next| next.checked_sub(experience))
+            .transpose()?,
+    })
+}
+
+pub fn query_balance_at(deps: Deps, xyz_id: String, height: u64) -> StdResult<BalanceResponse> {
+    let balance = BALANCES
+        .may_load_at_height(deps.storage, &xyz_id, height)?
+        .unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+pub fn query_total_supply_at(deps: Deps, height: u64) -> StdResult<TotalSupplyResponse> {
+    if let Some(start) = TOTAL_SUPPLY_HISTORY_START.may_load(deps.storage)? {
+        if height <= start {
+            return Err(StdError::generic_err(format!(
+                "total supply history is only available after height {}",
+                start
+            )));
+        }
+    }
+    let total_supply = TOTAL_SUPPLY_HISTORY
+        .may_load_at_height(deps.storage, TOTAL_SUPPLY_KEY, height)?
+        .unwrap_or_default();
+    Ok(TotalSupplyResponse { total_supply })
+}
+
 pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
     let info = TOKEN_INFO.load(deps.storage)?;
     let res = TokenInfoResponse {
@@ -423,3 +729,397 @@ pub fn query_download_logo(deps: Deps) -> StdResult<DownloadLogoResponse> {
         Logo::Url(_) => Err(StdError::not_found("logo")),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, NFT_CONTRACT_ADDRESS, NFT_OWNER_ADDRESS, NOW, XYZ_NFT_ID, default_xyz_nft_data, mock_dependencies_custom};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+    use cosmwasm_std::{Addr, StdError};
+
+    use super::*;
+    use crate::enumerable::query_all_spent;
+    use crate::msg::SpentInfo;
+
+    // this will set up the instantiation for other tests
+    fn do_instantiate(deps: DepsMut, xyz_id: &str, amount: Uint128, minter: &str) {
+        let instantiate_msg = InstantiateMsg {
+            name: "Experience".to_string(),
+            symbol: "XP".to_string(),
+            decimals: 6,
+            initial_balances: vec![XyzExperience {
+                xyz_id: xyz_id.to_string(),
+                amount,
+            }],
+            mint: Some(vec![MinterResponse {
+                minter: minter.to_string(),
+                cap: None,
+            }]),
+            marketing: None,
+            xyz_contract_address: Addr::unchecked(NFT_CONTRACT_ADDRESS),
+            level_curve: Some(LevelCurve::Thresholds {
+                thresholds: vec![Uint128::new(1000), Uint128::new(2000), Uint128::new(4000)],
+            }),
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps, mock_env(), info, instantiate_msg).unwrap();
+    }
+
+    #[test]
+    fn spend() {
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let xyz_id = XYZ_NFT_ID.to_string();
+        let minter = "minter";
+        do_instantiate(deps.as_mut(), &xyz_id, Uint128::new(1000), minter);
+
+        // minting is tracked as lifetime earned
+        let msg = ExecuteMsg::Mint {
+            recipient_xyz_id: xyz_id.clone(),
+            amount: Uint128::new(500),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap();
+
+        // cannot spend on behalf of an xyz the sender doesn't own
+        let msg = ExecuteMsg::Spend {
+            xyz_id: xyz_id.clone(),
+            amount: Uint128::new(100),
+            purpose: "speed".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone else", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::NoAllowance {});
+
+        // cannot spend without a purpose
+        let msg = ExecuteMsg::Spend {
+            xyz_id: xyz_id.clone(),
+            amount: Uint128::new(100),
+            purpose: " ".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidPurpose {});
+
+        // minters need an allowance from the owner like anyone else
+        let msg = ExecuteMsg::Spend {
+            xyz_id: xyz_id.clone(),
+            amount: Uint128::new(250),
+            purpose: "cargo".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::NoAllowance {});
+
+        // the owner can spend, and so can spenders it granted an allowance
+        let msg = ExecuteMsg::Spend {
+            xyz_id: xyz_id.clone(),
+            amount: Uint128::new(100),
+            purpose: "speed".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+        assert_eq!(res.attributes[0].value, "spend");
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: minter.to_string(),
+            amount: Uint128::new(300),
+            expires: None,
+            owner_xyz_id: xyz_id.clone(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+        let msg = ExecuteMsg::Spend {
+            xyz_id: xyz_id.clone(),
+            amount: Uint128::new(250),
+            purpose: "cargo".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap();
+        assert_eq!(
+            query_allowance(deps.as_ref(), NFT_OWNER_ADDRESS.to_string(), xyz_id.clone(), minter.to_string())
+                .unwrap()
+                .allowance,
+            Uint128::new(50)
+        );
+
+        // cannot spend more than the balance
+        let msg = ExecuteMsg::Spend {
+            xyz_id: xyz_id.clone(),
+            amount: Uint128::new(1151),
+            purpose: "speed".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+
+        assert_eq!(
+            query_experience_info(deps.as_ref(), xyz_id.clone()).unwrap(),
+            ExperienceInfoResponse {
+                earned: Uint128::new(1500),
+                spent: Uint128::new(350),
+                balance: Uint128::new(1150),
+            }
+        );
+        assert_eq!(
+            query_all_spent(deps.as_ref(), xyz_id, None, None).unwrap().spent,
+            vec![
+                SpentInfo {
+                    purpose: "cargo".to_string(),
+                    spent: Uint128::new(250),
+                },
+                SpentInfo {
+                    purpose: "speed".to_string(),
+                    spent: Uint128::new(100),
+                },
+            ]
+        );
+        assert_eq!(query_token_info(deps.as_ref()).unwrap().total_supply, Uint128::new(1150));
+    }
+
+    #[test]
+    fn level() {
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let xyz_id = XYZ_NFT_ID.to_string();
+        let minter = "minter";
+        do_instantiate(deps.as_mut(), &xyz_id, Uint128::new(1500), minter);
+
+        assert_eq!(
+            query_level(deps.as_ref(), xyz_id.clone()).unwrap(),
+            LevelResponse {
+                level: 1,
+                experience: Uint128::new(1500),
+                progress: Uint128::new(500),
+                experience_to_next_level: Some(Uint128::new(500)),
+            }
+        );
+
+        // a mint that doesn't cross a threshold has no level_up attribute
+        let msg = ExecuteMsg::Mint {
+            recipient_xyz_id: xyz_id.clone(),
+            amount: Uint128::new(100),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap();
+        assert!(res.attributes.iter().all(|attr| attr.key != "level_up"));
+
+        // a mint that crosses thresholds reports the new level
+        let msg = ExecuteMsg::Mint {
+            recipient_xyz_id: xyz_id.clone(),
+            amount: Uint128::new(3000),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap();
+        assert!(res.attributes.iter().any(|attr| attr.key == "level_up" && attr.value == "3"));
+        assert_eq!(
+            query_level(deps.as_ref(), xyz_id.clone()).unwrap(),
+            LevelResponse {
+                level: 3,
+                experience: Uint128::new(4600),
+                progress: Uint128::new(600),
+                experience_to_next_level: None,
+            }
+        );
+
+        // spending doesn't lower the level
+        let msg = ExecuteMsg::Spend {
+            xyz_id: xyz_id.clone(),
+            amount: Uint128::new(4000),
+            purpose: "speed".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+        assert_eq!(query_level(deps.as_ref(), xyz_id.clone()).unwrap().level, 3);
+
+        // only the owner can update the level curve
+        let level_curve = LevelCurve::Polynomial {
+            coefficient: Uint128::new(100),
+            exponent: 2,
+        };
+        let msg = ExecuteMsg::UpdateLevelCurve {
+            level_curve: Some(level_curve.clone()),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(query_level(deps.as_ref(), xyz_id).unwrap().level, 6);
+    }
+
+    #[test]
+    fn leaderboard() {
+        use crate::enumerable::{query_rank, query_top_accounts};
+        use crate::msg::{RankResponse, RankedAccount};
+
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let minter = "minter";
+        do_instantiate(deps.as_mut(), XYZ_NFT_ID, Uint128::new(300), minter);
+        for (xyz_id, amount) in [("xyz #2", 500u128), ("xyz #3", 100), ("xyz #4", 500)] {
+            let msg = ExecuteMsg::Mint {
+                recipient_xyz_id: xyz_id.to_string(),
+                amount: Uint128::new(amount),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap();
+        }
+
+        let ranked = |rank: u32, xyz_id: &str, balance: u128| RankedAccount {
+            rank,
+            xyz_id: xyz_id.to_string(),
+            balance: Uint128::new(balance),
+        };
+        // accounts with the same balance share a rank
+        assert_eq!(
+            query_top_accounts(deps.as_ref(), None, Some(2)).unwrap().accounts,
+            vec![ranked(1, "xyz #2", 500), ranked(1, "xyz #4", 500)]
+        );
+        assert_eq!(
+            query_top_accounts(deps.as_ref(), None, Some(3)).unwrap().accounts[2],
+            ranked(3, XYZ_NFT_ID, 300)
+        );
+
+        // ranks are recomputed rather than taken from the cursor
+        assert_eq!(
+            query_top_accounts(deps.as_ref(), Some(ranked(7, "xyz #4", 500)), None)
+                .unwrap()
+                .accounts,
+            vec![ranked(3, XYZ_NFT_ID, 300), ranked(4, "xyz #3", 100)]
+        );
+        assert_eq!(
+            query_top_accounts(deps.as_ref(), Some(ranked(1, "xyz #2", 500)), None)
+                .unwrap()
+                .accounts,
+            vec![ranked(1, "xyz #4", 500), ranked(3, XYZ_NFT_ID, 300), ranked(4, "xyz #3", 100)]
+        );
+
+        // spending moves an account down the leaderboard
+        let msg = ExecuteMsg::Spend {
+            xyz_id: "xyz #2".to_string(),
+            amount: Uint128::new(450),
+            purpose: "speed".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+        assert_eq!(
+            query_rank(deps.as_ref(), "xyz #2".to_string()).unwrap(),
+            RankResponse {
+                rank: Some(4),
+                balance: Uint128::new(50),
+            }
+        );
+        assert_eq!(query_rank(deps.as_ref(), "xyz #4".to_string()).unwrap().rank, Some(1));
+
+        // balances missing from the ranking are indexed by the owner in pages
+        BALANCE_RANKING.remove(&mut deps.storage, ranking_key(Uint128::new(500), "xyz #4"));
+        update_ranking_counts(&mut deps.storage, Some(Uint128::new(500)), None).unwrap();
+        assert_eq!(
+            query_top_accounts(deps.as_ref(), None, Some(1)).unwrap().accounts,
+            vec![ranked(1, XYZ_NFT_ID, 300)]
+        );
+        let msg = ExecuteMsg::ReindexRanking {
+            start_after: None,
+            limit: Some(2),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.attributes[1].value, "2");
+        let msg = ExecuteMsg::ReindexRanking {
+            start_after: Some(res.attributes[2].value.clone()),
+            limit: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            query_top_accounts(deps.as_ref(), None, Some(1)).unwrap().accounts,
+            vec![ranked(1, "xyz #4", 500)]
+        );
+
+        // reindexing again doesn't count accounts twice
+        let msg = ExecuteMsg::ReindexRanking {
+            start_after: None,
+            limit: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(query_rank(deps.as_ref(), "xyz #2".to_string()).unwrap().rank, Some(4));
+
+        // accounts without a balance are unranked
+        assert_eq!(
+            query_rank(deps.as_ref(), "xyz #5".to_string()).unwrap(),
+            RankResponse {
+                rank: None,
+                balance: Uint128::zero(),
+            }
+        );
+    }
+
+    #[test]
+    fn balance_and_supply_history() {
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let xyz_id = XYZ_NFT_ID.to_string();
+        let minter = "minter";
+        let mut env = mock_env();
+        let start = env.block.height;
+        do_instantiate(deps.as_mut(), &xyz_id, Uint128::new(1000), minter);
+
+        env.block.height = start + 10;
+        let msg = ExecuteMsg::Mint {
+            recipient_xyz_id: xyz_id.clone(),
+            amount: Uint128::new(500),
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(minter, &[]), msg).unwrap();
+
+        env.block.height = start + 20;
+        let msg = ExecuteMsg::Spend {
+            xyz_id: xyz_id.clone(),
+            amount: Uint128::new(200),
+            purpose: "speed".to_string(),
+        };
+        execute(deps.as_mut(), env, mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+
+        // values are as of the beginning of the queried block
+        for (height, expected) in [(start + 1, 1000u128), (start + 10, 1000), (start + 11, 1500), (start + 21, 1300)] {
+            assert_eq!(
+                query_balance_at(deps.as_ref(), xyz_id.clone(), height).unwrap().balance,
+                Uint128::new(expected)
+            );
+            assert_eq!(
+                query_total_supply_at(deps.as_ref(), height).unwrap().total_supply,
+                Uint128::new(expected)
+            );
+        }
+        assert_eq!(
+            query_balance_at(deps.as_ref(), "xyz #2".to_string(), start + 21).unwrap().balance,
+            Uint128::zero()
+        );
+
+        // after a migration, heights up to the migration height are no longer covered
+        env = mock_env();
+        env.block.height = start + 30;
+        let msg = MigrateMsg {
+            minters: vec![MinterData {
+                minter: Addr::unchecked(minter),
+                cap: None,
+            }],
+            owner: None,
+        };
+        migrate(deps.as_mut(), env, msg).unwrap();
+        assert_eq!(
+            query_total_supply_at(deps.as_ref(), start + 30).unwrap_err(),
+            StdError::generic_err(format!(
+                "total supply history is only available after height {}",
+                start + 30
+            ))
+        );
+        assert_eq!(
+            query_total_supply_at(deps.as_ref(), start + 31).unwrap().total_supply,
+            Uint128::new(1300)
+        );
+    }
+}
@@ -1,8 +1,9 @@
-use cosmwasm_std::{Deps, Order, StdResult};
+use cosmwasm_std::{Deps, Order, StdResult, Uint128};
 use collectxyz_experience::{AllAccountsResponse, AllAllowancesResponse, AllowanceInfo};
 
-use crate::state::{ALLOWANCES, BALANCES};
-use cw_storage_plus::Bound;
+use crate::msg::{AllSpentResponse, RankResponse, RankedAccount, SpentInfo, TopAccountsResponse};
+use crate::state::{ranking_counts, ranking_key, ALLOWANCES, BALANCES, BALANCE_RANKING, SPENT};
+use cw_storage_plus::{Bound, PrimaryKey};
 
 // settings for pagination
 const MAX_LIMIT: u32 = 30;
@@ -45,13 +46,110 @@ pub fn query_all_accounts(
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = start_after.map(Bound::exclusive);
 
-    let accounts: Result<Vec<_>, _> = BALANCES
-        .keys(deps.storage, start, None, Order::Ascending)
-        .map(String::from_utf8)
+    let accounts: StdResult<Vec<_>> = BALANCES
+        .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
+        .map(|item| {
+            let (k, _) = item?;
+            Ok(String::from_utf8(k)?)
+        })
         .collect();
 
     Ok(AllAccountsResponse {
         accounts: accounts?,
     })
 }
+
+pub fn query_all_spent(
+    deps: Deps,
+    xyz_id: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllSpentResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let spent: StdResult<Vec<SpentInfo>> = SPENT
+        .prefix(&xyz_id)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok(SpentInfo {
+                purpose: String::from_utf8(k)?,
+                spent: v,
+            })
+        })
+        .collect();
+    Ok(AllSpentResponse { spent: spent? })
+}
+
+pub fn query_top_accounts(
+    deps: Deps,
+    start_after: Option<RankedAccount>,
+    limit: Option<u32>,
+) -> StdResult<TopAccountsResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after
+        .map(|account| Bound::exclusive(ranking_key(account.balance, &account.xyz_id).joined_key()));
+
+    let page = BALANCE_RANKING
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (_, xyz_id) = item?;
+            let balance = BALANCES.load(deps.storage, &xyz_id)?;
+            Ok((xyz_id, balance))
+        })
+        .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+
+    // ranks come from the ranking counts rather than the cursor: the first balance on the
+    // page is looked up, then later balances rank after every account seen above them.
+    // Accounts with the same balance share a rank.
+    let mut accounts: Vec<RankedAccount> = Vec::with_capacity(page.len());
+    let mut next_rank = 0u32;
+    for (xyz_id, balance) in page {
+        let rank = match accounts.first() {
+            None => {
+                let (above, tied) = ranking_counts(deps.storage, balance)?;
+                next_rank = above + tied + 1;
+                above + 1
+            }
+            Some(first) if first.balance == balance => first.rank,
+            Some(_) => {
+                let last = accounts.last().unwrap();
+                let rank = if last.balance == balance {
+                    last.rank
+                } else {
+                    next_rank
+                };
+                next_rank += 1;
+                rank
+            }
+        };
+        accounts.push(RankedAccount {
+            rank,
+            xyz_id,
+            balance,
+        });
+    }
+    Ok(TopAccountsResponse { accounts })
+}
+
+pub fn query_rank(deps: Deps, xyz_id: String) -> StdResult<RankResponse> {
+    let balance = BALANCES
+        .may_load(deps.storage, &xyz_id)?
+        .unwrap_or_default();
+    if balance.is_zero() {
+        return Ok(RankResponse {
+            rank: None,
+            balance,
+        });
+    }
+
+    let (above, _) = ranking_counts(deps.storage, balance)?;
+    Ok(RankResponse {
+        rank: Some(above + 1),
+        balance,
+    })
+}
@@ -21,6 +21,12 @@ pub enum ContractError {
     #[error("No allowance for this account")]
     NoAllowance {},
 
+    #[error("Spend purpose must not be empty")]
+    InvalidPurpose {},
+
+    #[error("Level curve must be strictly increasing and non-zero")]
+    InvalidLevelCurve {},
+
     #[error("Minting cannot exceed the cap")]
     CannotExceedCap {},
 
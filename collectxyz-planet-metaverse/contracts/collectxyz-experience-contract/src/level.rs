@@ -0,0 +1,151 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Uint128;
+
+use crate::error::ContractError;
+
+/// Describes how much lifetime experience an xyz needs to reach each level.
+/// Every xyz starts at level 0.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelCurve {
+    /// `thresholds[i]` is the experience required to reach level `i + 1`.
+    /// Must be strictly increasing.
+    Thresholds { thresholds: Vec<Uint128> },
+    /// The experience required to reach level `n` is `coefficient * n ^ exponent`.
+    Polynomial { coefficient: Uint128, exponent: u32 },
+}
+
+impl LevelCurve {
+    pub fn validate(&self) -> Result<(), ContractError> {
+        match self {
+            LevelCurve::Thresholds { thresholds } => {
+                if thresholds.is_empty() || thresholds[0].is_zero() {
+                    return Err(ContractError::InvalidLevelCurve {});
+                }
+                if thresholds.windows(2).any(|pair| pair[0] >= pair[1]) {
+                    return Err(ContractError::InvalidLevelCurve {});
+                }
+            }
+            LevelCurve::Polynomial {
+                coefficient,
+                exponent,
+            } => {
+                if coefficient.is_zero() || *exponent == 0 {
+                    return Err(ContractError::InvalidLevelCurve {});
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The experience required to reach `level`, or None if it can't be reached.
+    pub fn threshold(&self, level: u64) -> Option<Uint128> {
+        if level == 0 {
+            return Some(Uint128::zero());
+        }
+        match self {
+            LevelCurve::Thresholds { thresholds } => thresholds.get(level as usize - 1).cloned(),
+            LevelCurve::Polynomial {
+                coefficient,
+                exponent,
+            } => (level as u128)
+                .checked_pow(*exponent)
+                .and_then(|v| v.checked_mul(coefficient.u128()))
+                .map(Uint128::from),
+        }
+    }
+
+    /// The highest level reached with the given amount of experience.
+    pub fn level(&self, experience: Uint128) -> u64 {
+        match self {
+            LevelCurve::Thresholds { thresholds } => {
+                thresholds.iter().filter(|t| **t <= experience).count() as u64
+            }
+            LevelCurve::Polynomial { .. } => {
+                let reached = |level: u64| match self.threshold(level) {
+                    Some(t) => t <= experience,
+                    None => false,
+                };
+                // find an unreached upper bound, then binary search below it
+                let mut high: u64 = 1;
+                while reached(high) {
+                    if high == u64::MAX {
+                        return u64::MAX;
+                    }
+                    high = high.checked_mul(2).unwrap_or(u64::MAX);
+                }
+                let mut low: u64 = high / 2;
+                while low + 1 < high {
+                    let mid = low + (high - low) / 2;
+                    if reached(mid) {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+                low
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_level() {
+        let curve = LevelCurve::Thresholds {
+            thresholds: vec![Uint128::new(100), Uint128::new(300), Uint128::new(600)],
+        };
+        assert_eq!(curve.level(Uint128::new(0)), 0);
+        assert_eq!(curve.level(Uint128::new(99)), 0);
+        assert_eq!(curve.level(Uint128::new(100)), 1);
+        assert_eq!(curve.level(Uint128::new(599)), 2);
+        assert_eq!(curve.level(Uint128::new(100000)), 3);
+        assert_eq!(curve.threshold(3), Some(Uint128::new(600)));
+        assert_eq!(curve.threshold(4), None);
+    }
+
+    #[test]
+    fn polynomial_level() {
+        let curve = LevelCurve::Polynomial {
+            coefficient: Uint128::new(100),
+            exponent: 2,
+        };
+        assert_eq!(curve.level(Uint128::new(99)), 0);
+        assert_eq!(curve.level(Uint128::new(100)), 1);
+        assert_eq!(curve.level(Uint128::new(399)), 1);
+        assert_eq!(curve.level(Uint128::new(400)), 2);
+        assert_eq!(curve.level(Uint128::new(1_000_000)), 100);
+        assert_eq!(curve.threshold(3), Some(Uint128::new(900)));
+
+        // levels stop at u64::MAX
+        let curve = LevelCurve::Polynomial {
+            coefficient: Uint128::new(1),
+            exponent: 1,
+        };
+        assert_eq!(curve.level(Uint128::new(u64::MAX as u128 - 1)), u64::MAX - 1);
+        assert_eq!(curve.level(Uint128::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn invalid_curves() {
+        let err = LevelCurve::Thresholds {
+            thresholds: vec![Uint128::new(100), Uint128::new(100)],
+        }
+        .validate()
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidLevelCurve {});
+
+        let err = LevelCurve::Polynomial {
+            coefficient: Uint128::new(100),
+            exponent: 0,
+        }
+        .validate()
+        .unwrap_err();
+        assert_eq!(err, ContractError::InvalidLevelCurve {});
+    }
+}
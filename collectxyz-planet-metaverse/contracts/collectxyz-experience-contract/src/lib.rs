@@ -2,6 +2,7 @@ pub mod allowances;
 pub mod contract;
 pub mod enumerable;
 mod error;
+pub mod level;
 pub mod msg;
 pub mod state;
 
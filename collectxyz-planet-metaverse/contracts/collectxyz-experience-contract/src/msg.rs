@@ -1,10 +1,9 @@
 use cosmwasm_std::{Addr, StdError, StdResult, Uint128};
-use collectxyz_experience::{XyzExperience, Logo, MinterResponse};
+use collectxyz_experience::{Expiration, XyzExperience, Logo, MinterResponse};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-pub use collectxyz_experience::CollectXyzExperienceExecuteMsg as ExecuteMsg;
-
+use crate::level::LevelCurve;
 use crate::state::MinterData;
 
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
@@ -24,6 +23,7 @@ pub struct InstantiateMsg {
     pub mint: Option<Vec<MinterResponse>>,
     pub marketing: Option<InstantiateMarketingInfo>,
     pub xyz_contract_address: Addr,
+    pub level_curve: Option<LevelCurve>,
 }
 
 impl InstantiateMsg {
@@ -73,10 +73,76 @@ fn is_valid_symbol(symbol: &str) -> bool {
     true
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Only with "mintable" extension. If authorized, creates amount new tokens
+    /// and adds to the recipient balance.
+    Mint { recipient_xyz_id: String, amount: Uint128 },
+    /// Spends amount of the xyz's experience towards the given purpose (e.g. a skill upgrade).
+    /// Spent experience is removed from the balance and recorded in the xyz's spent ledger.
+    /// Requires that the sender owns `xyz_id`, or has an allowance from its owner, which is
+    /// deducted by amount.
+    Spend {
+        xyz_id: String,
+        amount: Uint128,
+        purpose: String,
+    },
+    /// Only with "approval" extension. Allows spender to access an additional amount tokens
+    /// from the owner's (env.sender) xyz. If expires is Some(), overwrites current allowance
+    /// expiration with this one.
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+        owner_xyz_id: String,
+    },
+    /// Only with "approval" extension. Lowers the spender's access of tokens
+    /// from the owner's (env.sender) xyz by amount. If expires is Some(), overwrites current
+    /// allowance expiration with this one.
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+        owner_xyz_id: String,
+    },
+    /// Only with "approval" extension. Destroys tokens forever. Burned experience is recorded
+    /// in the xyz's spent ledger under the "burn" purpose.
+    BurnFrom {
+        owner: String,
+        amount: Uint128,
+        owner_xyz_id: String,
+    },
+    /// Only with the "marketing" extension. If authorized, updates marketing metadata.
+    /// Setting None/null for any of these will leave it unchanged.
+    /// Setting Some("") will clear this field on the contract storage
+    UpdateMarketing {
+        /// A URL pointing to the project behind this token.
+        project: Option<String>,
+        /// A longer description of the token and it's utility. Designed for tooltips or such
+        description: Option<String>,
+        /// The address (if any) who can update this data structure
+        marketing: Option<String>,
+    },
+    /// If set as the "marketing" role on the contract, upload a new URL, SVG, or PNG for the token
+    UploadLogo(Logo),
+    /// Replace the level curve used to derive levels from lifetime earned experience.
+    /// Setting None removes the curve. Requires that the sender is the contract owner.
+    UpdateLevelCurve { level_curve: Option<LevelCurve> },
+    /// Adds up to `limit` balances after `start_after` to the leaderboard ranking, for
+    /// balances from before the ranking was introduced that the migration didn't reach.
+    /// Requires that the sender is the contract owner.
+    ReindexRanking {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct MigrateMsg {
-    pub minters: Vec<MinterData>
+    pub minters: Vec<MinterData>,
+    pub owner: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -85,6 +151,31 @@ pub enum QueryMsg {
     /// Returns the current balance of the given address, 0 if unset.
     /// Return type: BalanceResponse.
     Balance { xyz_id: String },
+    /// Returns the balance of the given xyz at the beginning of the given block height,
+    /// 0 if unset. History is only available from the first balance change after upgrade.
+    /// Return type: BalanceResponse.
+    BalanceAt { xyz_id: String, height: u64 },
+    /// Returns the total supply at the beginning of the given block height. Errors for
+    /// heights up to and including the migration that introduced supply history.
+    /// Return type: TotalSupplyResponse.
+    TotalSupplyAt { height: u64 },
+    /// Returns the lifetime earned, lifetime spent and current experience of the given xyz.
+    /// Return type: ExperienceInfoResponse.
+    ExperienceInfo { xyz_id: String },
+    /// Returns the experience the given xyz has spent towards each purpose. Supports pagination.
+    /// Return type: AllSpentResponse.
+    AllSpent {
+        xyz_id: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the level of the given xyz, derived from its lifetime earned experience,
+    /// along with its progress towards the next level.
+    /// Return type: LevelResponse.
+    Level { xyz_id: String },
+    /// Returns the configured level curve, if any.
+    /// Return type: Option<LevelCurve>.
+    LevelCurve {},
     /// Returns metadata on the contract - name, decimals, supply, etc.
     /// Return type: TokenInfoResponse.
     TokenInfo {},
@@ -112,6 +203,20 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    /// Returns accounts ordered by balance, highest first. Ranks start at 1, accounts
+    /// with the same balance share a rank, and accounts with a zero balance are not
+    /// ranked. Supports pagination: pass the last account of the previous page as
+    /// `start_after`; its rank is ignored and ranks are recomputed on-chain.
+    /// Return type: TopAccountsResponse.
+    TopAccounts {
+        start_after: Option<RankedAccount>,
+        limit: Option<u32>,
+    },
+    /// Returns the leaderboard rank of the given xyz, None if it has no balance: one
+    /// more than the number of accounts with a higher balance. Takes a bounded number
+    /// of reads, so other contracts can query it.
+    /// Return type: RankResponse.
+    Rank { xyz_id: String },
     /// Only with "marketing" extension
     /// Returns more metadata on the contract to display in the client:
     /// - description, logo, project url, etc.
@@ -122,4 +227,64 @@ pub enum QueryMsg {
     /// contract.
     /// Return type: DownloadLogoResponse.
     DownloadLogo {},
-}
\ No newline at end of file
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ExperienceInfoResponse {
+    pub earned: Uint128,
+    pub spent: Uint128,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct SpentInfo {
+    pub purpose: String,
+    pub spent: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AllSpentResponse {
+    pub spent: Vec<SpentInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct LevelResponse {
+    pub level: u64,
+    /// lifetime earned experience the level is derived from
+    pub experience: Uint128,
+    /// experience earned since reaching the current level
+    pub progress: Uint128,
+    /// experience still required to reach the next level, None at the max level
+    pub experience_to_next_level: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RankedAccount {
+    pub rank: u32,
+    pub xyz_id: String,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TopAccountsResponse {
+    pub accounts: Vec<RankedAccount>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct RankResponse {
+    pub rank: Option<u32>,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TotalSupplyResponse {
+    pub total_supply: Uint128,
+}
@@ -1,11 +1,13 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy, U128Key};
 
 use collectxyz_experience::{AllowanceResponse, Logo, MarketingInfoResponse};
 
+use crate::level::LevelCurve;
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct XyzPlanetResourceInfo {
@@ -38,10 +40,153 @@ impl XyzPlanetResourceInfo {
     }
 }
 
+pub const OWNER: Item<Addr> = Item::new("owner");
 pub const TOKEN_INFO: Item<XyzPlanetResourceInfo> = Item::new("token_info");
+pub const LEVEL_CURVE: Item<LevelCurve> = Item::new("level_curve");
 pub const MARKETING_INFO: Item<MarketingInfoResponse> = Item::new("marketing_info");
 pub const LOGO: Item<Logo> = Item::new("logo");
-/// Balances are mapped by XYZ ID
-pub const BALANCES: Map<&str, Uint128> = Map::new("balance");
+/// Balances are mapped by XYZ ID. Every change is recorded in a changelog so that balances
+/// can be queried as of a past block height (history starts at the first change after upgrade).
+pub const BALANCES: SnapshotMap<&str, Uint128> = SnapshotMap::new(
+    "balance",
+    "balance__checkpoints",
+    "balance__changelog",
+    Strategy::EveryBlock,
+);
+/// Total supply history, mirroring `total_supply` in TOKEN_INFO under a single key
+pub const TOTAL_SUPPLY_HISTORY: SnapshotMap<&str, Uint128> = SnapshotMap::new(
+    "total_supply",
+    "total_supply__checkpoints",
+    "total_supply__changelog",
+    Strategy::EveryBlock,
+);
+pub const TOTAL_SUPPLY_KEY: &str = "total_supply";
+/// Height of the migration that introduced TOTAL_SUPPLY_HISTORY; unset if the history was
+/// recorded since instantiation
+pub const TOTAL_SUPPLY_HISTORY_START: Item<u64> = Item::new("total_supply_history_start");
+/// Balances ordered for the leaderboard, keyed by (u128::MAX - balance, xyz_id) so that
+/// iterating in ascending order yields the highest balances first. Zero balances are not ranked.
+pub const BALANCE_RANKING: Map<(U128Key, &str), String> = Map::new("balance_ranking");
+/// Counts of ranked balances, kept as a trie over the hex digits of each balance, most
+/// significant first. The node for a prefix of digits holds, per next digit, how many ranked
+/// accounts have a balance starting with the prefix and that digit, so counting the accounts
+/// above a balance takes one read per digit.
+pub const RANKING_COUNTS: Map<&[u8], Vec<u32>> = Map::new("ranking_counts");
+const BALANCE_DIGITS: usize = 32;
+/// Lifetime experience minted to an XYZ ID, never decreases
+pub const EARNED: Map<&str, Uint128> = Map::new("earned");
+/// Lifetime experience spent by an XYZ ID, across all purposes
+pub const TOTAL_SPENT: Map<&str, Uint128> = Map::new("total_spent");
+/// Experience spent is mapped by xyz_id,purpose
+pub const SPENT: Map<(&str, &str), Uint128> = Map::new("spent");
 /// Allowance is granted for xyz_id,xyz_owner -> some_addr
-pub const ALLOWANCES: Map<(&str, &Addr, &Addr), AllowanceResponse> = Map::new("allowance");
\ No newline at end of file
+pub const ALLOWANCES: Map<(&str, &Addr, &Addr), AllowanceResponse> = Map::new("allowance");
+
+pub fn ranking_key(balance: Uint128, xyz_id: &str) -> (U128Key, &str) {
+    (U128Key::new(u128::MAX - balance.u128()), xyz_id)
+}
+
+fn balance_digits(balance: Uint128) -> Vec<u8> {
+    balance
+        .u128()
+        .to_be_bytes()
+        .iter()
+        .flat_map(|byte| vec![byte >> 4, byte & 0xf])
+        .collect()
+}
+
+/// Moves a ranked account's count in RANKING_COUNTS from `old_balance` to `new_balance`,
+/// either of which may be None for an unranked account. Nodes the two balances share
+/// are left untouched.
+pub fn update_ranking_counts(
+    storage: &mut dyn Storage,
+    old_balance: Option<Uint128>,
+    new_balance: Option<Uint128>,
+) -> StdResult<()> {
+    let old_digits = old_balance.map(balance_digits);
+    let new_digits = new_balance.map(balance_digits);
+    for level in 0..BALANCE_DIGITS {
+        if let (Some(old), Some(new)) = (&old_digits, &new_digits) {
+            if old[..=level] == new[..=level] {
+                continue;
+            }
+        }
+        for (digits, added) in [(&old_digits, false), (&new_digits, true)] {
+            let digits = match digits {
+                Some(digits) => digits,
+                None => continue,
+            };
+            let node = &digits[..level];
+            let mut counts = RANKING_COUNTS
+                .may_load(storage, node)?
+                .unwrap_or_else(|| vec![0; 16]);
+            let count = &mut counts[digits[level] as usize];
+            *count = if added {
+                *count + 1
+            } else {
+                count
+                    .checked_sub(1)
+                    .ok_or_else(|| StdError::generic_err("ranking counts out of sync"))?
+            };
+            if counts.iter().all(|count| *count == 0) {
+                RANKING_COUNTS.remove(storage, node);
+            } else {
+                RANKING_COUNTS.save(storage, node, &counts)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns how many ranked accounts have a balance above `balance`, and how many have
+/// exactly `balance`, in at most one read per digit
+pub fn ranking_counts(storage: &dyn Storage, balance: Uint128) -> StdResult<(u32, u32)> {
+    let digits = balance_digits(balance);
+    let mut above = 0u32;
+    for level in 0..BALANCE_DIGITS {
+        let counts = match RANKING_COUNTS.may_load(storage, &digits[..level])? {
+            Some(counts) => counts,
+            None => return Ok((above, 0)),
+        };
+        let digit = digits[level] as usize;
+        above += counts[digit + 1..].iter().sum::<u32>();
+        if level == BALANCE_DIGITS - 1 {
+            return Ok((above, counts[digit]));
+        }
+    }
+    Ok((above, 0))
+}
+
+/// Updates the balance of an XYZ ID at the given height, keeping the leaderboard ranking in sync
+pub fn update_balance<A>(
+    storage: &mut dyn Storage,
+    xyz_id: &str,
+    height: u64,
+    action: A,
+) -> StdResult<Uint128>
+where
+    A: FnOnce(Option<Uint128>) -> StdResult<Uint128>,
+{
+    let old_balance = BALANCES.may_load(storage, xyz_id)?;
+    let new_balance = action(old_balance)?;
+
+    // balances from before the ranking was introduced may not be indexed yet
+    let mut old_ranked = None;
+    if let Some(old_balance) = old_balance.filter(|balance| !balance.is_zero()) {
+        if BALANCE_RANKING
+            .may_load(storage, ranking_key(old_balance, xyz_id))?
+            .is_some()
+        {
+            BALANCE_RANKING.remove(storage, ranking_key(old_balance, xyz_id));
+            old_ranked = Some(old_balance);
+        }
+    }
+    BALANCES.save(storage, xyz_id, &new_balance, height)?;
+    let new_ranked = Some(new_balance).filter(|balance| !balance.is_zero());
+    if new_ranked.is_some() {
+        BALANCE_RANKING.save(storage, ranking_key(new_balance, xyz_id), &xyz_id.to_string())?;
+    }
+    update_ranking_counts(storage, old_ranked, new_ranked)?;
+
+    Ok(new_balance)
+}
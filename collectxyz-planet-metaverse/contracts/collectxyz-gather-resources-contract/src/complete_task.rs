@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use serde_json;
 
+use collectxyz_resource_registry_contract::ResourceRegistry;
 use collectxyz_resources::CollectXyzResourceExecuteMsg;
 use cosmwasm_std::{Addr, BlockInfo, QuerierWrapper, Response, StdError, StdResult, Storage, Uint128, WasmMsg, to_binary};
 use fixed::types::I20F12;
@@ -30,7 +31,12 @@ fn generate_yield(
 }
 
 /// Mints the yield amount worth of resource
+///
+/// The resource contract is resolved against the resource registry
+/// if one is configured, otherwise taken from [ResourceGatherInfo].
 pub fn mint_yield_as_resources(
+    querier: &QuerierWrapper,
+    resource_registry: &Option<Addr>,
     resource_gather_infos: &HashMap<String, ResourceGatherInfo>,
     resource_id: &String, 
     total_yield: Uint128,
@@ -44,9 +50,20 @@ pub fn mint_yield_as_resources(
     if resource_gather_info.is_none() {
         return Err(StdError::generic_err("Could not get data"));
     }
+    let contract_addr = match resource_registry {
+        Some(registry) => ResourceRegistry(registry.clone())
+            .resource(querier, resource_id)?
+            .ok_or_else(|| StdError::generic_err(format!(
+                "No registry entry found for resource with Id: {:?}",
+                resource_id.to_string()
+            )))?
+            .contract_address
+            .to_string(),
+        None => resource_gather_info.unwrap().resource_contract_address.to_string(),
+    };
     return Ok(
         WasmMsg::Execute {
-            contract_addr: resource_gather_info.unwrap().resource_contract_address.to_string(),
+            contract_addr,
             msg: to_binary(&mint_resources_for_recipient_xyz_id)?,
             funds: vec![],
         }
@@ -198,6 +215,8 @@ pub fn complete_task(
     let resource_attributes: Vec<(String, String)>= vec![("resources_gathered".to_string(), serde_json::to_string(&resource_yield_map).unwrap())];
     for (resource_id, yield_total) in resource_yield_map.iter() {
         let mint_resource_message = mint_yield_as_resources(
+            querier,
+            &config.resource_registry,
             &resource_gather_infos, 
             resource_id, 
             yield_total.clone(), 
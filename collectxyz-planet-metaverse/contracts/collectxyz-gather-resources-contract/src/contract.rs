@@ -137,6 +137,7 @@ fn update_config(
         bonus_token_probability: update_config_data.bonus_token_probability.unwrap_or(current_config.bonus_token_probability),
         start_task_fee: update_config_data.start_task_fee.unwrap_or(current_config.start_task_fee),
         experience_mint_config: update_config_data.experience_mint_config.unwrap_or(current_config.experience_mint_config.into()).into(),
+        resource_registry: update_config_data.resource_registry.or(current_config.resource_registry),
     };
 
     CONFIG.save(storage, &new_config)?;
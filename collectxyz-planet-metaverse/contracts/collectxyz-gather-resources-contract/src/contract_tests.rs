@@ -9,13 +9,15 @@ use collectxyz_planet_metaverse::experience::{XyzExperienceMintInfo};
 use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, EXPERIENCE_CONTRACT_ADDRESS, NFT_CONTRACT_ADDRESS, NFT_OWNER_ADDRESS, NOW, PLANET_CONTRACT_ADDRESS, RANDOM_CONTRACT_ADDRESS, mock_dependencies_custom};
 use collectxyz_planet_metaverse::tasks::Task;
 use collectxyz_resources::CollectXyzResourceExecuteMsg;
-use cosmwasm_std::{Addr, Coin, CosmosMsg, DepsMut, Env, StdError, Timestamp, Uint128, WasmMsg, from_binary};
-use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use collectxyz_resource_registry_contract::msg::{QueryMsg as RegistryQueryMsg, ResourceEntry, ResourceResponse};
+use cosmwasm_std::{Addr, Coin, ContractResult, CosmosMsg, DepsMut, Env, QuerierWrapper, StdError, SystemResult, Timestamp, Uint128, WasmMsg, WasmQuery, from_binary, to_binary};
+use cosmwasm_std::testing::{MockQuerier, mock_dependencies, mock_env, mock_info};
 use cw2::ContractVersion;
 use fixed::types::I20F12;
 use rust_decimal::prelude::ToPrimitive;
 
 use crate::msg::{ExecuteMsg, InstantiateMsg};
+use crate::complete_task::mint_yield_as_resources;
 use crate::contract::{instantiate, query_config, execute};
 use crate::state::{Config, NFT_ID_GATHERING_RESOURCES, ResourceGatherInfo, TASK_REPOSITORY, load_resource_gather_info, load_all_resource_gathering_info};
 
@@ -57,7 +59,8 @@ fn setup_contract(deps: DepsMut) -> InstantiateMsg {
         experience_mint_config: XyzExperienceMintInfo { 
             experience_contract_address: Addr::unchecked(EXPERIENCE_CONTRACT_ADDRESS),
             complete_task_experience_amount: Uint128::from(1u128),
-        }
+        },
+        resource_registry: None,
     };
 
     let resource_gathering_info = vec![
@@ -819,4 +822,74 @@ pub fn default_xyz_coords() -> PlanetCoordinates {
         y: 100,
         z: 100
     };
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_mint_yield_with_resource_registry() {
+    let mut querier = MockQuerier::new(&[]);
+    querier.update_wasm(|query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == "resource-registry" => {
+            let resource = match from_binary::<RegistryQueryMsg>(msg).unwrap() {
+                RegistryQueryMsg::Resource { resource_id } if resource_id == XYZ_ICE => Some(ResourceEntry {
+                    id: XYZ_ICE.to_string(),
+                    contract_address: Addr::unchecked("ice-contract"),
+                    is_rmi_denom: true,
+                    decimals: 6,
+                    display_name: "Ice".to_string(),
+                }),
+                _ => None,
+            };
+            SystemResult::Ok(ContractResult::from(to_binary(&ResourceResponse { resource })))
+        }
+        _ => panic!("unsupported query"),
+    });
+    let querier = QuerierWrapper::new(&querier);
+
+    let mut resource_gather_infos = HashMap::new();
+    for (resource_id, contract_address) in [(XYZ_ICE, XYZ_ICE_CONTRACT_ADDRESS), (XYZ_ROCK, XYZ_ROCK_CONTRACT_ADDRESS)] {
+        resource_gather_infos.insert(resource_id.to_string(), ResourceGatherInfo {
+            resource_identifier: resource_id.to_string(),
+            resource_contract_address: contract_address.to_string(),
+            base_yield: BASE_YIELD,
+            max_deviation_yield: MAX_DEVIATION_YIELD,
+            deviation_direction_threshhold: DEVIATION_DIR_THRESHOLD,
+        });
+    }
+    let resource_registry = Some(Addr::unchecked("resource-registry"));
+    let xyz_id = XYZ_NFT_ID_1.to_string();
+
+    // the yield is minted by the contract registered for the resource
+    let msg = mint_yield_as_resources(
+        &querier, &resource_registry, &resource_gather_infos, &XYZ_ICE.to_string(), Uint128::new(10), &xyz_id
+    ).unwrap();
+    assert_eq!(msg, WasmMsg::Execute {
+        contract_addr: "ice-contract".to_string(),
+        msg: to_binary(&CollectXyzResourceExecuteMsg::Mint {
+            recipient_xyz_id: xyz_id.clone(),
+            amount: Uint128::new(10),
+        }).unwrap(),
+        funds: vec![],
+    });
+
+    // resources missing from the registry fail instead of falling back to the gather info
+    let err = mint_yield_as_resources(
+        &querier, &resource_registry, &resource_gather_infos, &XYZ_ROCK.to_string(), Uint128::new(10), &xyz_id
+    ).unwrap_err();
+    assert_eq!(err, StdError::generic_err(format!(
+        "No registry entry found for resource with Id: {:?}",
+        XYZ_ROCK.to_string()
+    )));
+
+    // without a registry the gather info's contract is used
+    let msg = mint_yield_as_resources(
+        &querier, &None, &resource_gather_infos, &XYZ_ICE.to_string(), Uint128::new(10), &xyz_id
+    ).unwrap();
+    assert_eq!(msg, WasmMsg::Execute {
+        contract_addr: XYZ_ICE_CONTRACT_ADDRESS.to_string(),
+        msg: to_binary(&CollectXyzResourceExecuteMsg::Mint {
+            recipient_xyz_id: xyz_id,
+            amount: Uint128::new(10),
+        }).unwrap(),
+        funds: vec![],
+    });
+}
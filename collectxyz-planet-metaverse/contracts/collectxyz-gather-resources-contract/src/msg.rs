@@ -47,6 +47,7 @@ pub struct UpdateConfigData {
     pub bonus_token_probability: Option<u8>,
     pub start_task_fee: Option<Coin>,
     pub experience_mint_config: Option<XyzExperienceMintInfo>,
+    pub resource_registry: Option<Addr>,
 }
 
 /// This is the current migration message. 
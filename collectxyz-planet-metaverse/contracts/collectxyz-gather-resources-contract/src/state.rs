@@ -62,6 +62,8 @@ pub struct OldConfig {
 /// * `planet_contract_address` - Planet Contract address
 /// * `gather_task_duration_seconds` - seconds requied from start time to gather resources
 /// * `gather_task_expiration_seconds` - seconds from start time before the task expires and can no longer be claimed.
+/// * `resource_registry` - when set, resource contract addresses are resolved against this registry
+///     instead of `resource_contract_address` in [ResourceGatherInfo]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct Config {
@@ -73,6 +75,8 @@ pub struct Config {
     pub bonus_token_probability: u8,
     pub start_task_fee: Coin,
     pub experience_mint_config: XyzExperienceMintInfo,
+    #[serde(default)]
+    pub resource_registry: Option<Addr>,
 }
 
 pub fn save_resource_gather_info(
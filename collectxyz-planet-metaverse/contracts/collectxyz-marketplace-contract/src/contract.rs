@@ -39,6 +39,9 @@ pub fn execute(
             price_rmi,
             deposit_rmi_denom,
             resources,
+            price_resources,
+            divisible,
+            allowed_taker_xyz_ids,
         } => ExecHandler::execute_make_listing(
             deps,
             env,
@@ -47,6 +50,9 @@ pub fn execute(
             price_rmi,
             deposit_rmi_denom,
             resources,
+            price_resources.unwrap_or_default(),
+            divisible.unwrap_or(false),
+            allowed_taker_xyz_ids.unwrap_or_default(),
         ),
         ExecuteMsg::RevokeListing { listing_id } => {
             ExecHandler::execute_revoke_listing(deps, env, info, listing_id)
@@ -55,12 +61,98 @@ pub fn execute(
             listing_id,
             taker_xyz_id,
             rmi_denom,
-        } => {
-            ExecHandler::execute_take_listing(deps, env, info, listing_id, taker_xyz_id, rmi_denom)
+            units,
+        } => ExecHandler::execute_take_listing(
+            deps,
+            env,
+            info,
+            listing_id,
+            taker_xyz_id,
+            rmi_denom,
+            units,
+        ),
+        ExecuteMsg::MakeBid {
+            bidder_xyz_id,
+            wanted_resources,
+            price_rmi,
+            rmi_denom,
+        } => ExecHandler::execute_make_bid(
+            deps,
+            env,
+            info,
+            bidder_xyz_id,
+            wanted_resources,
+            price_rmi,
+            rmi_denom,
+        ),
+        ExecuteMsg::RevokeBid { bid_id } => {
+            ExecHandler::execute_revoke_bid(deps, env, info, bid_id)
+        }
+        ExecuteMsg::FillBid {
+            bid_id,
+            seller_xyz_id,
+        } => ExecHandler::execute_fill_bid(deps, env, info, bid_id, seller_xyz_id),
+        ExecuteMsg::MakeAuction {
+            seller_xyz_id,
+            kind,
+            resources,
+            rmi_denom,
+            start_price_rmi,
+            end_price_rmi,
+        } => ExecHandler::execute_make_auction(
+            deps,
+            env,
+            info,
+            seller_xyz_id,
+            kind,
+            resources,
+            rmi_denom,
+            start_price_rmi,
+            end_price_rmi.unwrap_or_default(),
+        ),
+        ExecuteMsg::BidAuction {
+            auction_id,
+            bidder_xyz_id,
+            amount_rmi,
+        } => ExecHandler::execute_bid_auction(
+            deps,
+            env,
+            info,
+            auction_id,
+            bidder_xyz_id,
+            amount_rmi,
+        ),
+        ExecuteMsg::BuyAuction {
+            auction_id,
+            buyer_xyz_id,
+        } => ExecHandler::execute_buy_auction(deps, env, info, auction_id, buyer_xyz_id),
+        ExecuteMsg::SettleAuction { auction_id } => {
+            ExecHandler::execute_settle_auction(deps, env, info, auction_id)
+        }
+        ExecuteMsg::RevokeAuction { auction_id } => {
+            ExecHandler::execute_revoke_auction(deps, env, info, auction_id)
+        }
+        ExecuteMsg::ReceiveNft(msg) => ExecHandler::execute_receive_nft(deps, env, info, msg),
+        ExecuteMsg::BuyNft {
+            nft_listing_id,
+            payer_xyz_id,
+        } => ExecHandler::execute_buy_nft(deps, env, info, nft_listing_id, payer_xyz_id),
+        ExecuteMsg::RevokeNftListing { nft_listing_id } => {
+            ExecHandler::execute_revoke_nft_listing(deps, env, info, nft_listing_id)
         }
+        ExecuteMsg::SweepExpired { limit } => {
+            ExecHandler::execute_sweep_expired(deps, env, info, limit)
+        }
+        ExecuteMsg::WithdrawFees {
+            treasury_xyz_id,
+            recipient,
+        } => ExecHandler::execute_withdraw_fees(deps, env, info, treasury_xyz_id, recipient),
         ExecuteMsg::UpdateConfig { config_patch } => {
             ExecHandler::execute_update_config(deps, env, info, config_patch)
         }
+        ExecuteMsg::ReindexListings { start_after, limit } => {
+            ExecHandler::execute_reindex_listings(deps, env, info, start_after, limit)
+        }
     }
 }
 
@@ -88,9 +180,13 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             lister_xyz_id,
             prices,
             resources,
+            min_resource_amounts,
+            states,
             include_inactive,
+            sort_by,
             ascending,
             start_after,
+            cursor,
             limit,
         } => to_binary(&QueryHandler::query_listings(
             deps,
@@ -98,11 +194,97 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
             lister_xyz_id,
             prices,
             resources,
+            min_resource_amounts,
+            states,
             include_inactive,
+            sort_by,
             ascending,
             start_after,
+            cursor,
             limit,
         )?),
+        QueryMsg::BidInfo { bid_id } => {
+            to_binary(&QueryHandler::query_bid_info(deps, env, bid_id)?)
+        }
+        QueryMsg::Bids {
+            bidder_xyz_id,
+            prices,
+            resources,
+            include_inactive,
+            ascending,
+            start_after,
+            limit,
+        } => to_binary(&QueryHandler::query_bids(
+            deps,
+            env,
+            bidder_xyz_id,
+            prices,
+            resources,
+            include_inactive,
+            ascending,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::AuctionInfo { auction_id } => {
+            to_binary(&QueryHandler::query_auction_info(deps, env, auction_id)?)
+        }
+        QueryMsg::Auctions {
+            seller_xyz_id,
+            include_inactive,
+            ascending,
+            start_after,
+            limit,
+        } => to_binary(&QueryHandler::query_auctions(
+            deps,
+            env,
+            seller_xyz_id,
+            include_inactive,
+            ascending,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::NftListingInfo { nft_listing_id } => to_binary(
+            &QueryHandler::query_nft_listing_info(deps, env, nft_listing_id)?,
+        ),
+        QueryMsg::NftListings {
+            seller,
+            ascending,
+            start_after,
+            limit,
+        } => to_binary(&QueryHandler::query_nft_listings(
+            deps,
+            env,
+            seller,
+            ascending,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Fees {} => to_binary(&QueryHandler::query_fees(deps, env)?),
+        QueryMsg::Trades {
+            resource_id,
+            start_after,
+            limit,
+        } => to_binary(&QueryHandler::query_trades(
+            deps,
+            env,
+            resource_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Vwap {
+            resource_id,
+            rmi_denom,
+            window_seconds,
+        } => to_binary(&QueryHandler::query_vwap(
+            deps,
+            env,
+            resource_id,
+            rmi_denom,
+            window_seconds,
+        )?),
+        QueryMsg::Volume { window_seconds } => {
+            to_binary(&QueryHandler::query_volume(deps, env, window_seconds)?)
+        }
         QueryMsg::Config {} => to_binary(&QueryHandler::query_config(deps, env)?),
     }
 }
@@ -2,15 +2,25 @@ use super::*;
 use std::collections::HashMap;
 
 use cosmwasm_std::testing::{mock_env, mock_info};
-use cosmwasm_std::{from_binary, Addr, Coin, DepsMut, Response, StdError, Timestamp, Uint128};
+use cosmwasm_std::{
+    attr, from_binary, to_binary, Addr, BankMsg, Coin, Decimal, Deps, DepsMut, Response, StdError,
+    Timestamp, Uint128, WasmMsg,
+};
+use cw721::Cw721ReceiveMsg;
 
-use collectxyz::nft::{Coordinates, XyzExtension, XyzTokenInfo};
+use collectxyz::nft::{Coordinates, ExecuteMsg as XyzExecuteMsg, XyzExtension, XyzTokenInfo};
 use collectxyz_planet_metaverse::util::{burn_resource, mint_resource};
 
 use crate::contract;
 use crate::mock_querier::mock_dependencies_custom;
-use crate::msg::{ExecuteMsg, InstantiateMsg, ListingsResponse, QueryMsg};
-use crate::state::{Config, Listing, Resource};
+use crate::msg::{
+    BidsResponse, ConfigPatch, ExecuteMsg, FeesResponse, InstantiateMsg, ListingsResponse,
+    NftListingsResponse, QueryMsg, ReceiveNftMsg, TradesResponse, VolumeResponse, VwapResponse,
+};
+use crate::state::{
+    AuctionKind, Config, Listing, ListingCursor, ListingSort, ListingState, NftListing,
+    NftPrice, Resource,
+};
 
 const OWNER: &str = "owner";
 const NONOWNER: &str = "nonowner";
@@ -70,6 +80,13 @@ fn mock_config() -> Config {
         water_contract: Addr::unchecked("water-contract"),
         gem_contract: Addr::unchecked("gem-contract"),
         life_contract: Addr::unchecked("life-contract"),
+        resource_registry: None,
+        trade_fee_percent: 0,
+        royalty_percent: 0,
+        royalty_xyz_id: None,
+        expired_deposit_refund_percent: 0,
+        sweep_reward: None,
+        sweep_reward_reserve: Uint128::zero(),
     }
 }
 
@@ -110,6 +127,9 @@ fn test_make_listing() {
                     amount: Uint128::new(321000000),
                 },
             ],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap();
@@ -179,6 +199,10 @@ fn test_make_listing() {
                     amount: Uint128::new(321000000),
                 },
             ],
+            price_resources: vec![],
+            deposit_resources: vec![],
+            divisible: false,
+            allowed_taker_xyz_ids: vec![],
         }
     );
 
@@ -192,6 +216,9 @@ fn test_make_listing() {
             price_rmi: price_rmi.clone(),
             deposit_rmi_denom: deposit_rmi_denom.clone(),
             resources: vec![],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap_err();
@@ -207,6 +234,9 @@ fn test_make_listing() {
             price_rmi: price_rmi.clone(),
             deposit_rmi_denom: deposit_rmi_denom.clone(),
             resources: vec![],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap_err();
@@ -225,6 +255,9 @@ fn test_make_listing() {
             price_rmi: Uint128::new(123000000),
             deposit_rmi_denom: deposit_rmi_denom.clone(),
             resources: vec![],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap_err();
@@ -240,6 +273,9 @@ fn test_make_listing() {
             price_rmi: price_rmi.clone(),
             deposit_rmi_denom: "foobar".to_string(),
             resources: vec![],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap_err();
@@ -255,6 +291,9 @@ fn test_make_listing() {
             price_rmi: price_rmi.clone(),
             deposit_rmi_denom: deposit_rmi_denom.clone(),
             resources: vec![],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap_err();
@@ -273,6 +312,9 @@ fn test_make_listing() {
                 id: "xyzICE".to_string(),
                 amount: Uint128::new(123000001),
             }],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap_err();
@@ -294,6 +336,9 @@ fn test_make_listing() {
                 id: "barfoo".to_string(),
                 amount: Uint128::new(123000000),
             }],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap_err();
@@ -322,6 +367,9 @@ fn test_make_listing() {
                     amount: Uint128::new(123000000),
                 },
             ],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap_err();
@@ -373,6 +421,9 @@ fn revoke_listing() {
                     amount: Uint128::new(321000000),
                 },
             ],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap();
@@ -461,6 +512,9 @@ fn test_take_listing() {
                     amount: Uint128::new(1000000),
                 },
             ],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
         },
     )
     .unwrap();
@@ -474,6 +528,7 @@ fn test_take_listing() {
             listing_id: listing_id.clone(),
             taker_xyz_id: taker_xyz_id.clone(),
             rmi_denom: taker_rmi_denom.clone(),
+            units: None,
         },
     )
     .unwrap_err();
@@ -488,6 +543,7 @@ fn test_take_listing() {
             listing_id: listing_id.clone(),
             taker_xyz_id: taker_xyz_id.clone(),
             rmi_denom: taker_rmi_denom.clone(),
+            units: None,
         },
     )
     .unwrap_err();
@@ -505,6 +561,7 @@ fn test_take_listing() {
             listing_id: listing_id.clone(),
             taker_xyz_id: taker_xyz_id.clone(),
             rmi_denom: taker_rmi_denom.clone(),
+            units: None,
         },
     )
     .unwrap_err();
@@ -521,6 +578,7 @@ fn test_take_listing() {
             listing_id: listing_id.clone(),
             taker_xyz_id: taker_xyz_id.clone(),
             rmi_denom: taker_rmi_denom.clone(),
+            units: None,
         },
     )
     .unwrap_err();
@@ -539,6 +597,7 @@ fn test_take_listing() {
             listing_id: listing_id.clone(),
             taker_xyz_id: lister_xyz_id.clone(),
             rmi_denom: taker_rmi_denom.clone(),
+            units: None,
         },
     )
     .unwrap_err();
@@ -553,6 +612,7 @@ fn test_take_listing() {
             listing_id: listing_id.clone(),
             taker_xyz_id: taker_xyz_id.clone(),
             rmi_denom: taker_rmi_denom.clone(),
+            units: None,
         },
     )
     .unwrap();
@@ -648,6 +708,9 @@ fn test_query_listings() {
                         amount: Uint128::new(3000000),
                     },
                 ],
+                price_resources: None,
+                divisible: None,
+                allowed_taker_xyz_ids: None,
             },
         ),
         (
@@ -667,6 +730,9 @@ fn test_query_listings() {
                         amount: Uint128::new(2000000),
                     },
                 ],
+                price_resources: None,
+                divisible: None,
+                allowed_taker_xyz_ids: None,
             },
         ),
         (
@@ -690,6 +756,9 @@ fn test_query_listings() {
                         amount: Uint128::new(2000000),
                     },
                 ],
+                price_resources: None,
+                divisible: None,
+                allowed_taker_xyz_ids: None,
             },
         ),
     ]
@@ -721,9 +790,13 @@ fn test_query_listings() {
                 lister_xyz_id: None,
                 prices: None,
                 resources: None,
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: None,
+                sort_by: None,
                 ascending: None,
                 start_after: None,
+                cursor: None,
                 limit: None,
             },
         )
@@ -741,9 +814,13 @@ fn test_query_listings() {
                 lister_xyz_id: None,
                 prices: None,
                 resources: None,
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: Some(true),
+                sort_by: None,
                 ascending: None,
                 start_after: None,
+                cursor: None,
                 limit: None,
             },
         )
@@ -761,9 +838,13 @@ fn test_query_listings() {
                 lister_xyz_id: None,
                 prices: None,
                 resources: None,
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: Some(true),
+                sort_by: None,
                 ascending: Some(true),
                 start_after: None,
+                cursor: None,
                 limit: None,
             },
         )
@@ -781,9 +862,13 @@ fn test_query_listings() {
                 lister_xyz_id: None,
                 prices: Some(vec![Uint128::new(10), Uint128::new(100)]),
                 resources: None,
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: Some(true),
+                sort_by: None,
                 ascending: None,
                 start_after: None,
+                cursor: None,
                 limit: None,
             },
         )
@@ -801,9 +886,13 @@ fn test_query_listings() {
                 lister_xyz_id: None,
                 prices: None,
                 resources: Some(vec!["xyzROCK".to_string(), "xyzGAS".to_string()]),
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: Some(true),
+                sort_by: None,
                 ascending: None,
                 start_after: None,
+                cursor: None,
                 limit: None,
             },
         )
@@ -821,9 +910,13 @@ fn test_query_listings() {
                 lister_xyz_id: None,
                 prices: Some(vec![Uint128::new(10), Uint128::new(100)]),
                 resources: Some(vec!["xyzROCK".to_string(), "xyzGAS".to_string()]),
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: Some(true),
+                sort_by: None,
                 ascending: None,
                 start_after: None,
+                cursor: None,
                 limit: None,
             },
         )
@@ -841,9 +934,13 @@ fn test_query_listings() {
                 lister_xyz_id: Some(owner_xyz_id.clone()),
                 prices: None,
                 resources: Some(vec!["xyzROCK".to_string(), "xyzGAS".to_string()]),
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: Some(true),
+                sort_by: None,
                 ascending: None,
                 start_after: None,
+                cursor: None,
                 limit: None,
             },
         )
@@ -861,9 +958,13 @@ fn test_query_listings() {
                 lister_xyz_id: None,
                 prices: None,
                 resources: None,
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: Some(true),
+                sort_by: None,
                 ascending: None,
                 start_after: Some(3),
+                cursor: None,
                 limit: None,
             },
         )
@@ -881,9 +982,13 @@ fn test_query_listings() {
                 lister_xyz_id: None,
                 prices: None,
                 resources: None,
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: Some(true),
+                sort_by: None,
                 ascending: Some(true),
                 start_after: Some(1),
+                cursor: None,
                 limit: None,
             },
         )
@@ -901,9 +1006,13 @@ fn test_query_listings() {
                 lister_xyz_id: None,
                 prices: None,
                 resources: Some(vec!["xyzGAS".to_string()]),
+                min_resource_amounts: None,
+                states: None,
                 include_inactive: Some(true),
+                sort_by: None,
                 ascending: None,
                 start_after: None,
+                cursor: None,
                 limit: Some(2),
             },
         )
@@ -912,3 +1021,1638 @@ fn test_query_listings() {
     .unwrap();
     assert_eq!(get_listing_ids(res), vec![3, 1]);
 }
+
+#[test]
+fn test_make_listing_with_resource_registry() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    let msg = InstantiateMsg {
+        config: Config {
+            resource_registry: Some(Addr::unchecked("resource-registry")),
+            ..mock_config()
+        },
+    };
+    contract::instantiate(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+
+    let xyz_id = "xyz #1".to_string();
+    let make_listing = |resource_id: &str, deposit_rmi_denom: &str| ExecuteMsg::MakeListing {
+        lister_xyz_id: xyz_id.clone(),
+        price_rmi: Uint128::new(100),
+        deposit_rmi_denom: deposit_rmi_denom.to_string(),
+        resources: vec![Resource {
+            id: resource_id.to_string(),
+            amount: Uint128::new(1000000),
+        }],
+        price_resources: None,
+        divisible: None,
+        allowed_taker_xyz_ids: None,
+    };
+
+    // resources missing from the registry are rejected, even hardcoded ones
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing("xyzROCK", "xyzICE"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidResourceId("xyzROCK".to_string()));
+
+    // registered resources that aren't RMI denoms can't be used as the deposit
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing("xyzSAND", "xyzSAND"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidResourceId("xyzSAND".to_string()));
+
+    // a resource only known to the registry can be listed
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing("xyzSAND", "xyzICE"),
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        burn_resource(
+            OWNER.to_string(),
+            xyz_id.clone(),
+            "sand-contract".to_string(),
+            Uint128::new(1000000),
+        )
+        .unwrap(),
+        burn_resource(
+            OWNER.to_string(),
+            xyz_id.clone(),
+            "ice-contract".to_string(),
+            Uint128::new(5),
+        )
+        .unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+}
+
+#[test]
+fn test_barter_listing() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    setup_contract(deps.as_mut());
+
+    let lister_xyz_id = "xyz #1".to_string();
+    let taker_xyz_id = "xyz #2".to_string();
+    let make_listing = |price_rmi: u128, price_resources: Vec<Resource>| ExecuteMsg::MakeListing {
+        lister_xyz_id: lister_xyz_id.clone(),
+        price_rmi: Uint128::new(price_rmi),
+        deposit_rmi_denom: "".to_string(),
+        resources: vec![Resource {
+            id: "xyzGAS".to_string(),
+            amount: Uint128::new(10000000),
+        }],
+        price_resources: Some(price_resources),
+        divisible: None,
+        allowed_taker_xyz_ids: None,
+    };
+    let gem_price = vec![Resource {
+        id: "xyzGEM".to_string(),
+        amount: Uint128::new(3000000),
+    }];
+
+    // can't price a listing in both RMI and resources
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing(100, gem_price.clone()),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidListingPrice {});
+
+    // price resources are validated like listed resources
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing(
+            0,
+            vec![Resource {
+                id: "xyzGEM".to_string(),
+                amount: Uint128::new(1500000),
+            }],
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::PartialResourceAmount(Uint128::new(1500000))
+    );
+
+    // price resources can't be zero
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing(
+            0,
+            vec![Resource {
+                id: "xyzGEM".to_string(),
+                amount: Uint128::zero(),
+            }],
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::PartialResourceAmount(Uint128::zero()));
+
+    // barter listings take no RMI deposit, so a deposit denom is rejected
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        ExecuteMsg::MakeListing {
+            lister_xyz_id: lister_xyz_id.clone(),
+            price_rmi: Uint128::zero(),
+            deposit_rmi_denom: "xyzROCK".to_string(),
+            resources: vec![Resource {
+                id: "xyzGAS".to_string(),
+                amount: Uint128::new(10000000),
+            }],
+            price_resources: Some(gem_price.clone()),
+            divisible: None,
+            allowed_taker_xyz_ids: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidResourceId("xyzROCK".to_string()));
+
+    // valid barter listing burns the listed resources and a deposit in the price resources
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing(0, gem_price.clone()),
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        burn_resource(
+            OWNER.to_string(),
+            lister_xyz_id.clone(),
+            "gas-contract".to_string(),
+            Uint128::new(10000000),
+        )
+        .unwrap(),
+        burn_resource(
+            OWNER.to_string(),
+            lister_xyz_id.clone(),
+            "gem-contract".to_string(),
+            Uint128::new(150000), // 5% of 3000000
+        )
+        .unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+
+    // taking the listing pays the lister in the price resources, ignoring rmi_denom
+    let mut active_env = mock_env();
+    active_env.block.time = active_env.block.time.plus_seconds(1);
+    let res = contract::execute(
+        deps.as_mut(),
+        active_env,
+        mock_info(NONOWNER, &[action_fee()]),
+        ExecuteMsg::TakeListing {
+            listing_id: 1,
+            taker_xyz_id: taker_xyz_id.clone(),
+            rmi_denom: "xyzMETAL".to_string(),
+            units: None,
+        },
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        mint_resource(
+            lister_xyz_id.clone(),
+            "gem-contract".to_string(),
+            Uint128::new(3000000),
+        )
+        .unwrap(),
+        burn_resource(
+            NONOWNER.to_string(),
+            taker_xyz_id.clone(),
+            "gem-contract".to_string(),
+            Uint128::new(3000000),
+        )
+        .unwrap(),
+        mint_resource(
+            lister_xyz_id.clone(),
+            "gem-contract".to_string(),
+            Uint128::new(150000),
+        )
+        .unwrap(),
+        mint_resource(
+            taker_xyz_id.clone(),
+            "gas-contract".to_string(),
+            Uint128::new(10000000),
+        )
+        .unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+}
+
+#[test]
+fn test_make_and_fill_bid() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    setup_contract(deps.as_mut());
+
+    let bidder_xyz_id = "xyz #1".to_string();
+    let seller_xyz_id = "xyz #2".to_string();
+    let price_rmi = Uint128::new(100);
+    let make_bid = |price_rmi: Uint128, rmi_denom: &str| ExecuteMsg::MakeBid {
+        bidder_xyz_id: bidder_xyz_id.clone(),
+        wanted_resources: vec![Resource {
+            id: "xyzWATER".to_string(),
+            amount: Uint128::new(2000000),
+        }],
+        price_rmi,
+        rmi_denom: rmi_denom.to_string(),
+    };
+
+    // can't bid from an xyz you don't own
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[action_fee()]),
+        make_bid(price_rmi, "xyzICE"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // can't bid outside the allowed price tiers
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_bid(Uint128::new(50), "xyzICE"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidListingPrice {});
+
+    // can't escrow a non-RMI denom
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_bid(price_rmi, "xyzGAS"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidResourceId("xyzGAS".to_string()));
+
+    // valid bid escrows the price
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_bid(price_rmi, "xyzICE"),
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![burn_resource(
+        OWNER.to_string(),
+        bidder_xyz_id.clone(),
+        "ice-contract".to_string(),
+        price_rmi,
+    )
+    .unwrap()]);
+    assert_eq!(res.messages, expected.messages);
+
+    // bids can be queried by bidder and wanted resource
+    let res: BidsResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bids {
+                bidder_xyz_id: Some(bidder_xyz_id.clone()),
+                prices: None,
+                resources: Some(vec!["xyzWATER".to_string()]),
+                include_inactive: None,
+                ascending: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.bids.len(), 1);
+    assert_eq!(res.bids[0].bid_id, 1);
+
+    // can't fill own bid
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        ExecuteMsg::FillBid {
+            bid_id: 1,
+            seller_xyz_id: bidder_xyz_id.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::CantFillOwnBid {});
+
+    // can't fill an expired bid
+    let mut expired_env = mock_env();
+    expired_env.block.time = expired_env.block.time.plus_seconds(3);
+    let err = contract::execute(
+        deps.as_mut(),
+        expired_env,
+        mock_info(NONOWNER, &[action_fee()]),
+        ExecuteMsg::FillBid {
+            bid_id: 1,
+            seller_xyz_id: seller_xyz_id.clone(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InactiveBid {});
+
+    // filling a bid swaps the wanted resources for the escrowed price
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[action_fee()]),
+        ExecuteMsg::FillBid {
+            bid_id: 1,
+            seller_xyz_id: seller_xyz_id.clone(),
+        },
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        burn_resource(
+            NONOWNER.to_string(),
+            seller_xyz_id.clone(),
+            "water-contract".to_string(),
+            Uint128::new(2000000),
+        )
+        .unwrap(),
+        mint_resource(
+            bidder_xyz_id.clone(),
+            "water-contract".to_string(),
+            Uint128::new(2000000),
+        )
+        .unwrap(),
+        mint_resource(seller_xyz_id.clone(), "ice-contract".to_string(), price_rmi).unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+
+    // a filled bid is removed
+    contract::query(deps.as_ref(), mock_env(), QueryMsg::BidInfo { bid_id: 1 }).unwrap_err();
+}
+
+#[test]
+fn test_partial_fill_listing() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    setup_contract(deps.as_mut());
+
+    let lister_xyz_id = "xyz #1".to_string();
+    let taker_xyz_id = "xyz #2".to_string();
+    let make_listing = |resources: Vec<Resource>, divisible: bool| ExecuteMsg::MakeListing {
+        lister_xyz_id: lister_xyz_id.clone(),
+        price_rmi: Uint128::new(100),
+        deposit_rmi_denom: "xyzICE".to_string(),
+        resources,
+        price_resources: None,
+        divisible: Some(divisible),
+        allowed_taker_xyz_ids: None,
+    };
+    let take_listing = |listing_id: u64, units: Option<u64>| ExecuteMsg::TakeListing {
+        listing_id,
+        taker_xyz_id: taker_xyz_id.clone(),
+        rmi_denom: "xyzMETAL".to_string(),
+        units,
+    };
+    let gas = |amount: u128| Resource {
+        id: "xyzGAS".to_string(),
+        amount: Uint128::new(amount),
+    };
+
+    // divisible listings must be a single resource
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing(
+            vec![
+                gas(10000000),
+                Resource {
+                    id: "xyzGEM".to_string(),
+                    amount: Uint128::new(1000000),
+                },
+            ],
+            true,
+        ),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidDivisibleListing {});
+
+    // listing 1 is indivisible, listing 2 is divisible
+    for divisible in [false, true] {
+        contract::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[action_fee()]),
+            make_listing(vec![gas(10000000)], divisible),
+        )
+        .unwrap();
+    }
+
+    let mut active_env = mock_env();
+    active_env.block.time = active_env.block.time.plus_seconds(1);
+
+    // indivisible listings can't be partially taken
+    let err = contract::execute(
+        deps.as_mut(),
+        active_env.clone(),
+        mock_info(NONOWNER, &[action_fee()]),
+        take_listing(1, Some(3)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::IndivisibleListing {});
+
+    // can't take more units than remain
+    let err = contract::execute(
+        deps.as_mut(),
+        active_env.clone(),
+        mock_info(NONOWNER, &[action_fee()]),
+        take_listing(2, Some(11)),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFillUnits(11));
+
+    // take 3 of 10 units at a pro-rated price
+    let res = contract::execute(
+        deps.as_mut(),
+        active_env.clone(),
+        mock_info(NONOWNER, &[action_fee()]),
+        take_listing(2, Some(3)),
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        mint_resource(lister_xyz_id.clone(), "metal-contract".to_string(), Uint128::new(30))
+            .unwrap(),
+        burn_resource(
+            NONOWNER.to_string(),
+            taker_xyz_id.clone(),
+            "metal-contract".to_string(),
+            Uint128::new(30),
+        )
+        .unwrap(),
+        mint_resource(lister_xyz_id.clone(), "ice-contract".to_string(), Uint128::new(1))
+            .unwrap(),
+        mint_resource(taker_xyz_id.clone(), "gas-contract".to_string(), Uint128::new(3000000))
+            .unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+
+    // the remainder stays listed
+    let res: Listing = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ListingInfo { listing_id: 2 },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.resources, vec![gas(7000000)]);
+    assert_eq!(res.price_rmi, Uint128::new(70));
+    assert_eq!(res.deposit_rmi_amount, Uint128::new(4));
+
+    // taking the rest pays out the remaining price and deposit and removes the listing
+    let res = contract::execute(
+        deps.as_mut(),
+        active_env,
+        mock_info(NONOWNER, &[action_fee()]),
+        take_listing(2, None),
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        mint_resource(lister_xyz_id.clone(), "metal-contract".to_string(), Uint128::new(70))
+            .unwrap(),
+        burn_resource(
+            NONOWNER.to_string(),
+            taker_xyz_id.clone(),
+            "metal-contract".to_string(),
+            Uint128::new(70),
+        )
+        .unwrap(),
+        mint_resource(lister_xyz_id.clone(), "ice-contract".to_string(), Uint128::new(4))
+            .unwrap(),
+        mint_resource(taker_xyz_id.clone(), "gas-contract".to_string(), Uint128::new(7000000))
+            .unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+    contract::query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ListingInfo { listing_id: 2 },
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_english_auction() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    setup_contract(deps.as_mut());
+
+    let seller_xyz_id = "xyz #1".to_string();
+    let bidder_xyz_id = "xyz #2".to_string();
+    let bid_auction = |amount_rmi: u128| ExecuteMsg::BidAuction {
+        auction_id: 1,
+        bidder_xyz_id: bidder_xyz_id.clone(),
+        amount_rmi: Uint128::new(amount_rmi),
+    };
+
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        ExecuteMsg::MakeAuction {
+            seller_xyz_id: seller_xyz_id.clone(),
+            kind: AuctionKind::English,
+            resources: vec![Resource {
+                id: "xyzLIFE".to_string(),
+                amount: Uint128::new(1000000),
+            }],
+            rmi_denom: "xyzICE".to_string(),
+            start_price_rmi: Uint128::new(250),
+            end_price_rmi: None,
+        },
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![burn_resource(
+        OWNER.to_string(),
+        seller_xyz_id.clone(),
+        "life-contract".to_string(),
+        Uint128::new(1000000),
+    )
+    .unwrap()]);
+    assert_eq!(res.messages, expected.messages);
+
+    // can't bid below the reserve
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        bid_auction(249),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AuctionBidTooLow(Uint128::new(250)));
+
+    // first bid escrows its payment
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        bid_auction(250),
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![burn_resource(
+        NONOWNER.to_string(),
+        bidder_xyz_id.clone(),
+        "ice-contract".to_string(),
+        Uint128::new(250),
+    )
+    .unwrap()]);
+    assert_eq!(res.messages, expected.messages);
+
+    // bids must beat the highest bid
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        bid_auction(250),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AuctionBidTooLow(Uint128::new(251)));
+
+    // a higher bid refunds the outbid xyz
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        bid_auction(400),
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        mint_resource(bidder_xyz_id.clone(), "ice-contract".to_string(), Uint128::new(250))
+            .unwrap(),
+        burn_resource(
+            NONOWNER.to_string(),
+            bidder_xyz_id.clone(),
+            "ice-contract".to_string(),
+            Uint128::new(400),
+        )
+        .unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+
+    // auctions with bids can't be revoked or settled early
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RevokeAuction { auction_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AuctionHasBids {});
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::SettleAuction { auction_id: 1 },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::AuctionNotEnded {});
+
+    // settling an ended auction pays the seller and sends the bundle to the winner
+    let mut ended_env = mock_env();
+    ended_env.block.time = ended_env.block.time.plus_seconds(3);
+    let err = contract::execute(
+        deps.as_mut(),
+        ended_env.clone(),
+        mock_info(NONOWNER, &[]),
+        bid_auction(500),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InactiveAuction {});
+    let res = contract::execute(
+        deps.as_mut(),
+        ended_env,
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::SettleAuction { auction_id: 1 },
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        mint_resource(seller_xyz_id.clone(), "ice-contract".to_string(), Uint128::new(400))
+            .unwrap(),
+        mint_resource(bidder_xyz_id.clone(), "life-contract".to_string(), Uint128::new(1000000))
+            .unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+}
+
+#[test]
+fn test_dutch_auction() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    setup_contract(deps.as_mut());
+
+    let seller_xyz_id = "xyz #1".to_string();
+    let buyer_xyz_id = "xyz #2".to_string();
+    let make_auction = |start_price_rmi: u128, end_price_rmi: u128| ExecuteMsg::MakeAuction {
+        seller_xyz_id: seller_xyz_id.clone(),
+        kind: AuctionKind::Dutch,
+        resources: vec![Resource {
+            id: "xyzGEM".to_string(),
+            amount: Uint128::new(2000000),
+        }],
+        rmi_denom: "xyzROCK".to_string(),
+        start_price_rmi: Uint128::new(start_price_rmi),
+        end_price_rmi: Some(Uint128::new(end_price_rmi)),
+    };
+
+    // the price can only decay
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_auction(100, 200),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidAuctionPrice {});
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_auction(330, 30),
+    )
+    .unwrap();
+
+    // dutch auctions don't take bids
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::BidAuction {
+            auction_id: 1,
+            bidder_xyz_id: buyer_xyz_id.clone(),
+            amount_rmi: Uint128::new(330),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::WrongAuctionKind {});
+
+    // one of three seconds in, the price has decayed by a third of the range
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(1);
+    let res = contract::execute(
+        deps.as_mut(),
+        env,
+        mock_info(NONOWNER, &[action_fee()]),
+        ExecuteMsg::BuyAuction {
+            auction_id: 1,
+            buyer_xyz_id: buyer_xyz_id.clone(),
+        },
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        mint_resource(seller_xyz_id.clone(), "rock-contract".to_string(), Uint128::new(230))
+            .unwrap(),
+        burn_resource(
+            NONOWNER.to_string(),
+            buyer_xyz_id.clone(),
+            "rock-contract".to_string(),
+            Uint128::new(230),
+        )
+        .unwrap(),
+        mint_resource(buyer_xyz_id.clone(), "gem-contract".to_string(), Uint128::new(2000000))
+            .unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+    contract::query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::AuctionInfo { auction_id: 1 },
+    )
+    .unwrap_err();
+}
+
+#[test]
+fn test_trade_fees() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[action_fee()]);
+
+    // fees above 100% are rejected
+    let err = contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        InstantiateMsg {
+            config: Config {
+                trade_fee_percent: 101,
+                ..mock_config()
+            },
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidFeePercent {});
+
+    contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        InstantiateMsg {
+            config: Config {
+                trade_fee_percent: 10,
+                royalty_percent: 50,
+                royalty_xyz_id: Some("xyz #3".to_string()),
+                ..mock_config()
+            },
+        },
+    )
+    .unwrap();
+
+    let lister_xyz_id = "xyz #1".to_string();
+    let taker_xyz_id = "xyz #2".to_string();
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        ExecuteMsg::MakeListing {
+            lister_xyz_id: lister_xyz_id.clone(),
+            price_rmi: Uint128::new(1000),
+            deposit_rmi_denom: "xyzICE".to_string(),
+            resources: vec![Resource {
+                id: "xyzGAS".to_string(),
+                amount: Uint128::new(1000000),
+            }],
+            price_resources: None,
+            divisible: None,
+            allowed_taker_xyz_ids: None,
+        },
+    )
+    .unwrap();
+
+    // the lister receives the price less the fee, half of which is paid as royalty
+    let mut active_env = mock_env();
+    active_env.block.time = active_env.block.time.plus_seconds(1);
+    let res = contract::execute(
+        deps.as_mut(),
+        active_env,
+        mock_info(NONOWNER, &[action_fee()]),
+        ExecuteMsg::TakeListing {
+            listing_id: 1,
+            taker_xyz_id: taker_xyz_id.clone(),
+            rmi_denom: "xyzMETAL".to_string(),
+            units: None,
+        },
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_messages(vec![
+        mint_resource(lister_xyz_id.clone(), "metal-contract".to_string(), Uint128::new(900))
+            .unwrap(),
+        mint_resource("xyz #3".to_string(), "metal-contract".to_string(), Uint128::new(50))
+            .unwrap(),
+        burn_resource(
+            NONOWNER.to_string(),
+            taker_xyz_id.clone(),
+            "metal-contract".to_string(),
+            Uint128::new(1000),
+        )
+        .unwrap(),
+        mint_resource(lister_xyz_id.clone(), "ice-contract".to_string(), Uint128::new(50))
+            .unwrap(),
+        mint_resource(taker_xyz_id.clone(), "gas-contract".to_string(), Uint128::new(1000000))
+            .unwrap(),
+    ]);
+    assert_eq!(res.messages, expected.messages);
+
+    // the treasury share accrues per denom
+    fn fees(deps: Deps) -> FeesResponse {
+        from_binary(&contract::query(deps, mock_env(), QueryMsg::Fees {}).unwrap()).unwrap()
+    }
+    assert_eq!(
+        fees(deps.as_ref()).fees,
+        vec![Resource {
+            id: "xyzMETAL".to_string(),
+            amount: Uint128::new(50),
+        }]
+    );
+
+    // only the owner can withdraw fees
+    let withdraw_fees = ExecuteMsg::WithdrawFees {
+        treasury_xyz_id: Some("xyz #9".to_string()),
+        recipient: Some("treasury".to_string()),
+    };
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        withdraw_fees.clone(),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        withdraw_fees,
+    )
+    .unwrap();
+    let expected: Response = Response::new()
+        .add_message(
+            mint_resource("xyz #9".to_string(), "metal-contract".to_string(), Uint128::new(50))
+                .unwrap(),
+        )
+        .add_message(BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![action_fee()],
+        });
+    assert_eq!(res.messages, expected.messages);
+    assert_eq!(fees(deps.as_ref()).fees, vec![]);
+}
+
+#[test]
+fn test_sweep_expired() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[Coin::new(5, "uusd")]);
+    contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        InstantiateMsg {
+            config: Config {
+                expired_deposit_refund_percent: 40,
+                sweep_reward: Some(Coin::new(2, "uusd")),
+                sweep_reward_reserve: Uint128::new(3),
+                ..mock_config()
+            },
+        },
+    )
+    .unwrap();
+
+    let lister_xyz_id = "xyz #1".to_string();
+    let make_listing = || ExecuteMsg::MakeListing {
+        lister_xyz_id: lister_xyz_id.clone(),
+        price_rmi: Uint128::new(100),
+        deposit_rmi_denom: "xyzICE".to_string(),
+        resources: vec![Resource {
+            id: "xyzWATER".to_string(),
+            amount: Uint128::new(1000000),
+        }],
+        price_resources: None,
+        divisible: None,
+        allowed_taker_xyz_ids: None,
+    };
+
+    // listings 1-3 expire after 3 seconds, listing 4 two seconds later
+    for _ in 0..3 {
+        contract::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[action_fee()]),
+            make_listing(),
+        )
+        .unwrap();
+    }
+    let mut later_env = mock_env();
+    later_env.block.time = later_env.block.time.plus_seconds(2);
+    contract::execute(
+        deps.as_mut(),
+        later_env,
+        mock_info(OWNER, &[action_fee()]),
+        make_listing(),
+    )
+    .unwrap();
+
+    let mut expired_env = mock_env();
+    expired_env.block.time = expired_env.block.time.plus_seconds(3);
+
+    // anyone can sweep, returning resources and part of the deposit to the lister
+    let res = contract::execute(
+        deps.as_mut(),
+        expired_env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::SweepExpired { limit: Some(2) },
+    )
+    .unwrap();
+    let mut expected: Vec<WasmMsg> = vec![];
+    for _ in 0..2 {
+        expected.push(
+            mint_resource(
+                lister_xyz_id.clone(),
+                "water-contract".to_string(),
+                Uint128::new(1000000),
+            )
+            .unwrap(),
+        );
+        expected.push(
+            mint_resource(lister_xyz_id.clone(), "ice-contract".to_string(), Uint128::new(2))
+                .unwrap(),
+        );
+    }
+    let expected: Response = Response::new()
+        .add_messages(expected)
+        .add_message(BankMsg::Send {
+            to_address: "keeper".to_string(),
+            amount: vec![Coin::new(4, "uusd")],
+        })
+        .add_attribute("method", "execute")
+        .add_attribute("action", "sweep_expired")
+        .add_attribute("listing_ids", "1,2");
+    assert_eq!(res, expected);
+
+    // the sweep stops at the first listing that hasn't expired
+    let res = contract::execute(
+        deps.as_mut(),
+        expired_env.clone(),
+        mock_info("keeper", &[]),
+        ExecuteMsg::SweepExpired { limit: None },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2].value, "3");
+
+    // listings are swept by expiry rather than creation: listing 5 expires before 4
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::UpdateConfig {
+            config_patch: ConfigPatch {
+                listing_expiry_seconds: Some(1),
+                ..ConfigPatch::default()
+            },
+        },
+    )
+    .unwrap();
+    contract::execute(
+        deps.as_mut(),
+        expired_env.clone(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing(),
+    )
+    .unwrap();
+    let mut env = expired_env;
+    env.block.time = env.block.time.plus_seconds(1);
+    let res = contract::execute(
+        deps.as_mut(),
+        env,
+        mock_info("keeper", &[]),
+        ExecuteMsg::SweepExpired { limit: None },
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2].value, "5");
+
+    // withdrawing fees keeps back the sweep reward reserve
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::WithdrawFees {
+            treasury_xyz_id: None,
+            recipient: Some("treasury".to_string()),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        BankMsg::Send {
+            to_address: "treasury".to_string(),
+            amount: vec![Coin::new(2, "uusd")],
+        }
+        .into()
+    );
+
+    // the unrefunded deposits were forfeited to the treasury
+    let res: FeesResponse =
+        from_binary(&contract::query(deps.as_ref(), mock_env(), QueryMsg::Fees {}).unwrap())
+            .unwrap();
+    assert_eq!(
+        res.fees,
+        vec![Resource {
+            id: "xyzICE".to_string(),
+            amount: Uint128::new(9),
+        }]
+    );
+}
+
+#[test]
+fn test_trade_history() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    setup_contract(deps.as_mut());
+
+    // fill three bids: two for GAS alone and one for a GAS and GEM bundle
+    let gas = |amount: u128| Resource {
+        id: "xyzGAS".to_string(),
+        amount: Uint128::new(amount),
+    };
+    let bids = vec![
+        (vec![gas(2000000)], 100u128),
+        (vec![gas(3000000)], 1000u128),
+        (
+            vec![
+                gas(1000000),
+                Resource {
+                    id: "xyzGEM".to_string(),
+                    amount: Uint128::new(1000000),
+                },
+            ],
+            10u128,
+        ),
+    ];
+    for (bid_id, (wanted_resources, price_rmi)) in bids.into_iter().enumerate() {
+        contract::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(OWNER, &[action_fee()]),
+            ExecuteMsg::MakeBid {
+                bidder_xyz_id: "xyz #1".to_string(),
+                wanted_resources,
+                price_rmi: Uint128::new(price_rmi),
+                rmi_denom: "xyzICE".to_string(),
+            },
+        )
+        .unwrap();
+        contract::execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(NONOWNER, &[action_fee()]),
+            ExecuteMsg::FillBid {
+                bid_id: bid_id as u64 + 1,
+                seller_xyz_id: "xyz #2".to_string(),
+            },
+        )
+        .unwrap();
+    }
+
+    // recent trades per resource, newest first
+    let res: TradesResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Trades {
+                resource_id: "xyzGAS".to_string(),
+                start_after: None,
+                limit: Some(2),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.trades.iter().map(|t| t.trade_id).collect::<Vec<u64>>(),
+        vec![3, 2]
+    );
+    assert_eq!(res.trades[1].seller_xyz_id, "xyz #2");
+    assert_eq!(res.trades[1].buyer_xyz_id, "xyz #1");
+    assert_eq!(res.trades[1].price_rmi, Uint128::new(1000));
+
+    // the bundle trade doesn't count towards the GAS VWAP
+    let res: VwapResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Vwap {
+                resource_id: "xyzGAS".to_string(),
+                rmi_denom: "xyzICE".to_string(),
+                window_seconds: 60,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.trade_count, 2);
+    assert_eq!(res.volume, Uint128::new(5000000));
+    assert_eq!(res.vwap_rmi, Some(Decimal::from_ratio(1100u128, 5u128)));
+
+    // trades priced in other denoms don't count
+    let res: VwapResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Vwap {
+                resource_id: "xyzGAS".to_string(),
+                rmi_denom: "xyzRMI".to_string(),
+                window_seconds: 60,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.trade_count, 0);
+    assert_eq!(res.vwap_rmi, None);
+
+    // volume covers every trade in the window
+    let res: VolumeResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Volume {
+                window_seconds: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.trade_count, 3);
+    assert_eq!(
+        res.volumes,
+        vec![Resource {
+            id: "xyzICE".to_string(),
+            amount: Uint128::new(1110),
+        }]
+    );
+
+    // trades age out of the 24h window once their whole hourly bucket has
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(24 * 60 * 60 + 1);
+    let res: VolumeResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Volume {
+                window_seconds: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.trade_count, 3);
+    env.block.time = env.block.time.plus_seconds(60 * 60);
+    let res: VolumeResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            env.clone(),
+            QueryMsg::Volume {
+                window_seconds: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.trade_count, 0);
+    assert_eq!(res.volumes, vec![]);
+
+    // windows are limited to 30 days
+    let err = contract::query(
+        deps.as_ref(),
+        env,
+        QueryMsg::Volume {
+            window_seconds: Some(30 * 24 * 60 * 60 + 1),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        StdError::generic_err(format!("window_seconds can be at most {}", 30 * 24 * 60 * 60))
+    );
+}
+
+#[test]
+fn test_private_listing() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    setup_contract(deps.as_mut());
+
+    let make_listing = |allowed_taker_xyz_ids: Vec<String>| ExecuteMsg::MakeListing {
+        lister_xyz_id: "xyz #1".to_string(),
+        price_rmi: Uint128::new(10),
+        deposit_rmi_denom: "xyzICE".to_string(),
+        resources: vec![Resource {
+            id: "xyzROCK".to_string(),
+            amount: Uint128::new(1000000),
+        }],
+        price_resources: None,
+        divisible: None,
+        allowed_taker_xyz_ids: Some(allowed_taker_xyz_ids),
+    };
+    let take_listing = |listing_id: u64| ExecuteMsg::TakeListing {
+        listing_id,
+        taker_xyz_id: "xyz #2".to_string(),
+        rmi_denom: "xyzICE".to_string(),
+        units: None,
+    };
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing(vec!["xyz #3".to_string()]),
+    )
+    .unwrap();
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[action_fee()]),
+        make_listing(vec!["xyz #2".to_string(), "xyz #3".to_string()]),
+    )
+    .unwrap();
+
+    // xyz outside the allow-list can't take the listing
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[action_fee()]),
+        take_listing(1),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::TakerNotAllowed {});
+
+    // allowed takers can take the listing without waiting out the pending window
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[action_fee()]),
+        take_listing(2),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_query_listings_sorted() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    setup_contract(deps.as_mut());
+
+    // (seconds after now, price, resource, amount)
+    let listings = vec![
+        (0, 10u128, "xyzROCK", 1000000u128),
+        (1, 100, "xyzGAS", 4000000),
+        (2, 1000, "xyzGEM", 2000000),
+        (2, 10, "xyzWATER", 5000000),
+    ];
+    for (seconds, price_rmi, resource_id, amount) in listings {
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(seconds);
+        contract::execute(
+            deps.as_mut(),
+            env,
+            mock_info(OWNER, &[action_fee()]),
+            ExecuteMsg::MakeListing {
+                lister_xyz_id: "xyz #1".to_string(),
+                price_rmi: Uint128::new(price_rmi),
+                deposit_rmi_denom: "xyzICE".to_string(),
+                resources: vec![Resource {
+                    id: resource_id.to_string(),
+                    amount: Uint128::new(amount),
+                }],
+                price_resources: None,
+                divisible: None,
+                allowed_taker_xyz_ids: None,
+            },
+        )
+        .unwrap();
+    }
+
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(2);
+    let query = |msg: QueryMsg| -> ListingsResponse {
+        from_binary(&contract::query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap()
+    };
+    let listings_query = |states: Option<Vec<ListingState>>,
+                          min_resource_amounts: Option<Vec<Resource>>,
+                          sort_by: Option<ListingSort>,
+                          ascending: bool,
+                          cursor: Option<ListingCursor>,
+                          limit: Option<u32>| QueryMsg::Listings {
+        lister_xyz_id: None,
+        prices: None,
+        resources: None,
+        min_resource_amounts,
+        states,
+        include_inactive: Some(true),
+        sort_by,
+        ascending: Some(ascending),
+        start_after: None,
+        cursor,
+        limit,
+    };
+    let get_listing_ids = |response: &ListingsResponse| {
+        response
+            .listings
+            .iter()
+            .map(|o| o.listing_id)
+            .collect::<Vec<u64>>()
+    };
+
+    // sort by unit price, paging with the cursor
+    let res = query(listings_query(
+        None,
+        None,
+        Some(ListingSort::UnitPrice),
+        true,
+        None,
+        Some(2),
+    ));
+    assert_eq!(get_listing_ids(&res), vec![4, 1]);
+    let res = query(listings_query(
+        None,
+        None,
+        Some(ListingSort::UnitPrice),
+        true,
+        res.next_cursor,
+        Some(2),
+    ));
+    assert_eq!(get_listing_ids(&res), vec![2, 3]);
+
+    // sort by expiry
+    let res = query(listings_query(
+        None,
+        None,
+        Some(ListingSort::ExpiredAt),
+        false,
+        None,
+        None,
+    ));
+    assert_eq!(get_listing_ids(&res), vec![4, 3, 2, 1]);
+
+    // filter by state
+    let res = query(listings_query(
+        Some(vec![ListingState::Pending]),
+        None,
+        None,
+        false,
+        None,
+        None,
+    ));
+    assert_eq!(get_listing_ids(&res), vec![4, 3]);
+
+    // filter by minimum resource amount
+    let gas = |amount: u128| {
+        Some(vec![Resource {
+            id: "xyzGAS".to_string(),
+            amount: Uint128::new(amount),
+        }])
+    };
+    let res = query(listings_query(None, gas(4000000), None, false, None, None));
+    assert_eq!(get_listing_ids(&res), vec![2]);
+    let res = query(listings_query(None, gas(5000000), None, false, None, None));
+    assert_eq!(get_listing_ids(&res), Vec::<u64>::new());
+
+    // only the owner can reindex listings
+    let reindex = |start_after: Option<u64>| ExecuteMsg::ReindexListings {
+        start_after,
+        limit: Some(3),
+    };
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        reindex(None),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // reindexing pages through the listings by id
+    let res = contract::execute(deps.as_mut(), mock_env(), mock_info(OWNER, &[]), reindex(None))
+        .unwrap();
+    assert_eq!(res.attributes[2], attr("count", "3"));
+    assert_eq!(res.attributes[3], attr("last_listing_id", "3"));
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        reindex(Some(3)),
+    )
+    .unwrap();
+    assert_eq!(res.attributes[2], attr("count", "1"));
+    assert_eq!(res.attributes[3], attr("last_listing_id", "4"));
+    let res: ListingsResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            env,
+            listings_query(None, None, Some(ListingSort::UnitPrice), true, None, None),
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(get_listing_ids(&res), vec![4, 1, 2, 3]);
+}
+
+#[test]
+fn test_nft_listing() {
+    let xyz_balances = initial_xyz_balances();
+    let mut deps = mock_dependencies_custom(xyz_balances, &[]);
+    contract::instantiate(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        InstantiateMsg {
+            config: Config {
+                trade_fee_percent: 10,
+                ..mock_config()
+            },
+        },
+    )
+    .unwrap();
+
+    let price = Coin::new(1000, "uusd");
+    let list_nft = |bundle_xyz_id: &str| {
+        ExecuteMsg::ReceiveNft(Cw721ReceiveMsg {
+            sender: OWNER.to_string(),
+            token_id: "3".to_string(),
+            msg: to_binary(&ReceiveNftMsg::ListNft {
+                price: NftPrice::Native(price.clone()),
+                payout_xyz_id: None,
+                bundle_xyz_id: Some(bundle_xyz_id.to_string()),
+                resources: Some(vec![Resource {
+                    id: "xyzGAS".to_string(),
+                    amount: Uint128::new(1000000),
+                }]),
+            })
+            .unwrap(),
+        })
+    };
+
+    // only the xyz NFT contract can send NFTs
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other-nft-contract", &[]),
+        list_nft("xyz #1"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the bundle must be owned by the seller
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("xyz-nft-contract", &[]),
+        list_nft("xyz #2"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the bundle can't come from the xyz being listed
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("xyz-nft-contract", &[]),
+        list_nft("xyz #3"),
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::BundleIsListedXyz {});
+
+    // valid NFT listing burns the bundle from bundle_xyz_id
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("xyz-nft-contract", &[]),
+        list_nft("xyz #1"),
+    )
+    .unwrap();
+    let expected: Response = Response::new().add_message(
+        burn_resource(
+            OWNER.to_string(),
+            "xyz #1".to_string(),
+            "gas-contract".to_string(),
+            Uint128::new(1000000),
+        )
+        .unwrap(),
+    );
+    assert_eq!(res.messages, expected.messages);
+
+    let res: NftListingsResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::NftListings {
+                seller: Some(OWNER.to_string()),
+                ascending: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(
+        res.nft_listings,
+        vec![NftListing {
+            nft_listing_id: 1,
+            token_id: "3".to_string(),
+            seller: Addr::unchecked(OWNER),
+            price: NftPrice::Native(price.clone()),
+            payout_xyz_id: None,
+            bundle_xyz_id: Some("xyz #1".to_string()),
+            resources: vec![Resource {
+                id: "xyzGAS".to_string(),
+                amount: Uint128::new(1000000),
+            }],
+            created_at: mock_env().block.time,
+        }]
+    );
+
+    // insufficient price sent
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[Coin::new(999, "uusd")]),
+        ExecuteMsg::BuyNft {
+            nft_listing_id: 1,
+            payer_xyz_id: None,
+        },
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::Std(StdError::generic_err("insufficient funds sent"))
+    );
+
+    // the buyer pays the seller the full native price, receives the NFT, and the
+    // bundle is minted to the bought xyz
+    let res = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[price.clone()]),
+        ExecuteMsg::BuyNft {
+            nft_listing_id: 1,
+            payer_xyz_id: None,
+        },
+    )
+    .unwrap();
+    let expected: Response = Response::new()
+        .add_message(BankMsg::Send {
+            to_address: OWNER.to_string(),
+            amount: vec![Coin::new(1000, "uusd")],
+        })
+        .add_message(WasmMsg::Execute {
+            contract_addr: "xyz-nft-contract".to_string(),
+            msg: to_binary(&XyzExecuteMsg::TransferNft {
+                recipient: NONOWNER.to_string(),
+                token_id: "3".to_string(),
+            })
+            .unwrap(),
+            funds: vec![],
+        })
+        .add_message(
+            mint_resource(
+                "xyz #3".to_string(),
+                "gas-contract".to_string(),
+                Uint128::new(1000000),
+            )
+            .unwrap(),
+        );
+    assert_eq!(res.messages, expected.messages);
+
+    let err = contract::query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::NftListingInfo { nft_listing_id: 1 },
+    )
+    .unwrap_err();
+    assert!(matches!(err, StdError::NotFound { .. }));
+}
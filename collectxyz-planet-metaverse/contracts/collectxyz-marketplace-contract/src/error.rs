@@ -32,4 +32,55 @@ pub enum ContractError {
 
     #[error("Can't Take Own Listing")]
     CantTakeOwnListing {},
+
+    #[error("Invalid Divisible Listing")]
+    InvalidDivisibleListing {},
+
+    #[error("Indivisible Listing")]
+    IndivisibleListing {},
+
+    #[error("Invalid Fill Units: {0}")]
+    InvalidFillUnits(u64),
+
+    #[error("Inactive Bid")]
+    InactiveBid {},
+
+    #[error("Can't Fill Own Bid")]
+    CantFillOwnBid {},
+
+    #[error("Invalid Auction Price")]
+    InvalidAuctionPrice {},
+
+    #[error("Inactive Auction")]
+    InactiveAuction {},
+
+    #[error("Auction Not Ended")]
+    AuctionNotEnded {},
+
+    #[error("Wrong Auction Kind")]
+    WrongAuctionKind {},
+
+    #[error("Auction Has Bids")]
+    AuctionHasBids {},
+
+    #[error("Auction Bid Too Low: minimum {0}")]
+    AuctionBidTooLow(Uint128),
+
+    #[error("Can't Bid On Own Auction")]
+    CantBidOwnAuction {},
+
+    #[error("Invalid Fee Percent")]
+    InvalidFeePercent {},
+
+    #[error("Taker Not Allowed")]
+    TakerNotAllowed {},
+
+    #[error("Invalid Resource Bundle")]
+    InvalidResourceBundle {},
+
+    #[error("Wrong Payment Method")]
+    WrongPaymentMethod {},
+
+    #[error("Bundle Xyz Is The Listed Xyz")]
+    BundleIsListedXyz {},
 }
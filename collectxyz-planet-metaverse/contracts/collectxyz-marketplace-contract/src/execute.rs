@@ -1,12 +1,21 @@
-use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, StdResult, Uint128, WasmMsg};
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Order,
+    Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw721::Cw721ReceiveMsg;
+
+use collectxyz::nft::ExecuteMsg as XyzExecuteMsg;
 
 use collectxyz_planet_metaverse::util::{
     burn_resource, check_sufficient_funds, mint_resource, validate_nft_is_owned_by_wallet,
 };
 
 use crate::error::ContractError;
-use crate::msg::{ConfigPatch, InstantiateMsg, MigrateMsg};
-use crate::state::{Config, Listings, Resource, CONFIG, OWNER};
+use crate::msg::{ConfigPatch, InstantiateMsg, MigrateMsg, ReceiveNftMsg};
+use crate::state::{
+    AuctionBid, AuctionKind, Auctions, Bids, Config, Listings, NftListings, NftPrice, Resource,
+    Trades, CONFIG, FEES, OWNER,
+};
 
 pub fn execute_instantiate(
     deps: DepsMut,
@@ -14,6 +23,7 @@ pub fn execute_instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    msg.config.validate()?;
     OWNER.save(deps.storage, &info.sender)?;
     CONFIG.save(deps.storage, &msg.config)?;
 
@@ -22,6 +32,39 @@ pub fn execute_instantiate(
         .add_attribute("owner", info.sender))
 }
 
+/// Pays a seller the RMI price of a trade, less the protocol fee. The royalty share of
+/// the fee is minted to the royalty xyz and the treasury share accrues in `FEES`.
+fn pay_seller(
+    storage: &mut dyn Storage,
+    config: &Config,
+    seller_xyz_id: &str,
+    rmi_denom: &str,
+    rmi_denom_contract: &Addr,
+    price_rmi: Uint128,
+) -> Result<Vec<WasmMsg>, ContractError> {
+    let (seller_amount, treasury_amount, royalty_amount) = config.trade_fee(price_rmi);
+
+    let mut messages: Vec<WasmMsg> = vec![mint_resource(
+        seller_xyz_id.to_string(),
+        rmi_denom_contract.to_string(),
+        seller_amount,
+    )?];
+
+    if let Some(royalty_xyz_id) = &config.royalty_xyz_id {
+        if !royalty_amount.is_zero() {
+            messages.push(mint_resource(
+                royalty_xyz_id.clone(),
+                rmi_denom_contract.to_string(),
+                royalty_amount,
+            )?);
+        }
+    }
+
+    accrue_fee(storage, rmi_denom, treasury_amount)?;
+
+    Ok(messages)
+}
+
 pub fn execute_make_listing(
     deps: DepsMut,
     env: Env,
@@ -30,6 +73,9 @@ pub fn execute_make_listing(
     price_rmi: Uint128,
     deposit_rmi_denom: String,
     resources: Vec<Resource>,
+    price_resources: Vec<Resource>,
+    divisible: bool,
+    allowed_taker_xyz_ids: Vec<String>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -50,17 +96,21 @@ pub fn execute_make_listing(
     let listings = Listings::default();
     let listing = listings.save_listing(
         deps.storage,
+        &deps.querier,
         lister_xyz_id.clone(),
         price_rmi,
         deposit_rmi_denom.clone(),
         env.block.time,
         resources.clone(),
+        price_resources,
+        divisible,
+        allowed_taker_xyz_ids,
     )?;
 
     // burn listed resources from lister_xyz_id
     let mut messages: Vec<WasmMsg> = vec![];
     for resource in resources.iter() {
-        let resource_contract = config.resource_addr(&resource.id)?;
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
         messages.push(burn_resource(
             info.sender.to_string(),
             lister_xyz_id.clone(),
@@ -70,8 +120,8 @@ pub fn execute_make_listing(
     }
 
     // burn listing RMI deposit from lister_xyz_id
-    let rmi_denom_contract = config.resource_addr(&deposit_rmi_denom)?;
     if !listing.deposit_rmi_amount.is_zero() {
+        let rmi_denom_contract = config.resource_addr(&deps.querier, &deposit_rmi_denom)?;
         messages.push(burn_resource(
             info.sender.to_string(),
             lister_xyz_id.clone(),
@@ -80,6 +130,17 @@ pub fn execute_make_listing(
         )?);
     }
 
+    // burn listing resource deposit from lister_xyz_id (barter listings only)
+    for resource in listing.deposit_resources.iter() {
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+        messages.push(burn_resource(
+            info.sender.to_string(),
+            lister_xyz_id.clone(),
+            resource_contract.to_string(),
+            resource.amount,
+        )?)
+    }
+
     Ok(Response::new()
         .add_messages(messages)
         .add_attribute("method", "execute")
@@ -114,7 +175,7 @@ pub fn execute_revoke_listing(
     // mint listed resources back to lister_xyz_id
     let mut messages: Vec<WasmMsg> = vec![];
     for resource in listing.resources.iter() {
-        let resource_contract = config.resource_addr(&resource.id)?;
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
         messages.push(mint_resource(
             listing.lister_xyz_id.clone(),
             resource_contract.to_string(),
@@ -136,6 +197,7 @@ pub fn execute_take_listing(
     listing_id: u64,
     taker_xyz_id: String,
     rmi_denom: String,
+    units: Option<u64>,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
 
@@ -163,45 +225,591 @@ pub fn execute_take_listing(
         return Err(ContractError::CantTakeOwnListing {});
     }
 
-    // remove the listing from storage
-    listings.remove_listing(deps.storage, listing.listing_id)?;
+    // private listings can only be taken by their allowed takers
+    if !listing.can_be_taken_by(&taker_xyz_id) {
+        return Err(ContractError::TakerNotAllowed {});
+    }
+
+    // split off the filled portion, removing the listing from storage once nothing
+    // remains unfilled and otherwise saving the remainder
+    let mut remaining = listing;
+    let listing = remaining.fill(units.unwrap_or(remaining.units()))?;
+    if remaining.resources.is_empty() {
+        listings.remove_listing(deps.storage, listing.listing_id)?;
+    } else {
+        listings.update_listing(deps.storage, &remaining)?;
+    }
 
     let mut messages: Vec<WasmMsg> = vec![];
-    let rmi_denom_contract = config.resource_addr(&rmi_denom)?;
 
-    // for lister_xyz_id: mint listing.price_rmi in rmi_denom
-    messages.push(mint_resource(
+    if listing.price_resources.is_empty() {
+        let rmi_denom_contract = config.resource_addr(&deps.querier, &rmi_denom)?;
+
+        // for lister_xyz_id: mint listing.price_rmi in rmi_denom, less fees
+        messages.extend(pay_seller(
+            deps.storage,
+            &config,
+            &listing.lister_xyz_id,
+            &rmi_denom,
+            &rmi_denom_contract,
+            listing.price_rmi,
+        )?);
+
+        // for taker_xyz_id: burn listing.price_rmi in rmi_denom
+        // NOTE: this burn operation will fail if taker_xyz_id has insufficient balance of rmi_denom.
+        // we rely on this to failure that people can't buy if they don't have enough funds.
+        messages.push(burn_resource(
+            info.sender.to_string(),
+            taker_xyz_id.clone(),
+            rmi_denom_contract.to_string(),
+            listing.price_rmi,
+        )?);
+
+        // for lister_xyz_id: mint deposit_rmi_amount in listing.deposit_rmi_denom
+        if !listing.deposit_rmi_amount.is_zero() {
+            messages.push(mint_resource(
+                listing.lister_xyz_id.clone(),
+                config
+                    .resource_addr(&deps.querier, &listing.deposit_rmi_denom)?
+                    .to_string(),
+                listing.deposit_rmi_amount,
+            )?);
+        }
+    } else {
+        // barter listing: the taker pays in listing.price_resources and rmi_denom is ignored
+        for resource in listing.price_resources.iter() {
+            let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+
+            // for lister_xyz_id: mint the price resource
+            messages.push(mint_resource(
+                listing.lister_xyz_id.clone(),
+                resource_contract.to_string(),
+                resource.amount,
+            )?);
+
+            // for taker_xyz_id: burn the price resource, failing on insufficient balance
+            messages.push(burn_resource(
+                info.sender.to_string(),
+                taker_xyz_id.clone(),
+                resource_contract.to_string(),
+                resource.amount,
+            )?);
+        }
+
+        // for lister_xyz_id: mint back the resource deposit
+        for resource in listing.deposit_resources.iter() {
+            let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+            messages.push(mint_resource(
+                listing.lister_xyz_id.clone(),
+                resource_contract.to_string(),
+                resource.amount,
+            )?);
+        }
+    }
+
+    // for taker_xyz_id: mint listing.resources
+    for resource in listing.resources.iter() {
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+        messages.push(mint_resource(
+            taker_xyz_id.clone(),
+            resource_contract.to_string(),
+            resource.amount,
+        )?)
+    }
+
+    // record the trade for price history
+    let (price_rmi, rmi_denom) = if listing.price_resources.is_empty() {
+        (listing.price_rmi, rmi_denom)
+    } else {
+        (Uint128::zero(), String::new())
+    };
+    Trades::default().save_trade(
+        deps.storage,
         listing.lister_xyz_id.clone(),
+        taker_xyz_id.clone(),
+        listing.resources.clone(),
+        price_rmi,
+        rmi_denom,
+        listing.price_resources.clone(),
+        env.block.time,
+    )?;
+
+    let mut response = Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "take_listing")
+        .add_attribute("taker_xyz_id", taker_xyz_id)
+        .add_attribute("listing", serde_json::to_string(&listing).unwrap());
+    if !remaining.resources.is_empty() {
+        response = response.add_attribute(
+            "remaining_listing",
+            serde_json::to_string(&remaining).unwrap(),
+        );
+    }
+
+    Ok(response)
+}
+
+pub fn execute_make_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bidder_xyz_id: String,
+    wanted_resources: Vec<Resource>,
+    price_rmi: Uint128,
+    rmi_denom: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check that the sender owns bidder_xyz_id
+    if !validate_nft_is_owned_by_wallet(
+        &bidder_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_nft_contract,
+    )? {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // check that the sender provided sufficient make listing fee
+    check_sufficient_funds(info.funds, &config.make_listing_fee)?;
+
+    // save the bid to storage
+    let bids = Bids::default();
+    let bid = bids.save_bid(
+        deps.storage,
+        &deps.querier,
+        bidder_xyz_id.clone(),
+        price_rmi,
+        rmi_denom.clone(),
+        env.block.time,
+        wanted_resources,
+    )?;
+
+    // escrow the bid price by burning it from bidder_xyz_id
+    // NOTE: this burn operation will fail if bidder_xyz_id has insufficient balance of rmi_denom.
+    let rmi_denom_contract = config.resource_addr(&deps.querier, &rmi_denom)?;
+    let messages: Vec<WasmMsg> = vec![burn_resource(
+        info.sender.to_string(),
+        bidder_xyz_id,
         rmi_denom_contract.to_string(),
-        listing.price_rmi,
+        bid.price_rmi,
+    )?];
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "make_bid")
+        .add_attribute("bid", serde_json::to_string(&bid).unwrap()))
+}
+
+pub fn execute_revoke_bid(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    bid_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let bids = Bids::default();
+    let bid = bids.fetch_bid(deps.storage, bid_id)?;
+
+    // check that the sender owns bid.bidder_xyz_id
+    if !validate_nft_is_owned_by_wallet(
+        &bid.bidder_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_nft_contract,
+    )? {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // remove the bid from storage
+    bids.remove_bid(deps.storage, bid_id)?;
+
+    // mint the escrowed price back to bidder_xyz_id
+    let messages: Vec<WasmMsg> = vec![mint_resource(
+        bid.bidder_xyz_id.clone(),
+        config
+            .resource_addr(&deps.querier, &bid.rmi_denom)?
+            .to_string(),
+        bid.price_rmi,
+    )?];
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "revoke_bid")
+        .add_attribute("bid", serde_json::to_string(&bid).unwrap()))
+}
+
+pub fn execute_fill_bid(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    bid_id: u64,
+    seller_xyz_id: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check that the sender owns seller_xyz_id
+    if !validate_nft_is_owned_by_wallet(
+        &seller_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_nft_contract,
+    )? {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // check that the sender provided sufficient take listing fee
+    check_sufficient_funds(info.funds, &config.take_listing_fee)?;
+
+    let bids = Bids::default();
+
+    // check that the bid hasn't expired
+    let bid = bids.fetch_active_bid(deps.storage, bid_id, env.block.time)?;
+
+    // block self-fills, which would just return the escrowed payment
+    if seller_xyz_id == bid.bidder_xyz_id {
+        return Err(ContractError::CantFillOwnBid {});
+    }
+
+    // remove the bid from storage
+    bids.remove_bid(deps.storage, bid.bid_id)?;
+
+    let mut messages: Vec<WasmMsg> = vec![];
+    for resource in bid.wanted_resources.iter() {
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+
+        // for seller_xyz_id: burn the wanted resource
+        // NOTE: this burn operation will fail if seller_xyz_id has insufficient balance.
+        messages.push(burn_resource(
+            info.sender.to_string(),
+            seller_xyz_id.clone(),
+            resource_contract.to_string(),
+            resource.amount,
+        )?);
+
+        // for bidder_xyz_id: mint the wanted resource
+        messages.push(mint_resource(
+            bid.bidder_xyz_id.clone(),
+            resource_contract.to_string(),
+            resource.amount,
+        )?);
+    }
+
+    // for seller_xyz_id: mint the escrowed bid.price_rmi in bid.rmi_denom, less fees
+    let rmi_denom_contract = config.resource_addr(&deps.querier, &bid.rmi_denom)?;
+    messages.extend(pay_seller(
+        deps.storage,
+        &config,
+        &seller_xyz_id,
+        &bid.rmi_denom,
+        &rmi_denom_contract,
+        bid.price_rmi,
     )?);
 
-    // for taker_xyz_id: burn listing.price_rmi in rmi_denom
-    // NOTE: this burn operation will fail if taker_xyz_id has insufficient balance of rmi_denom.
-    // we rely on this to failure that people can't buy if they don't have enough funds.
+    // record the trade for price history
+    Trades::default().save_trade(
+        deps.storage,
+        seller_xyz_id.clone(),
+        bid.bidder_xyz_id.clone(),
+        bid.wanted_resources.clone(),
+        bid.price_rmi,
+        bid.rmi_denom.clone(),
+        vec![],
+        env.block.time,
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "fill_bid")
+        .add_attribute("seller_xyz_id", seller_xyz_id)
+        .add_attribute("bid", serde_json::to_string(&bid).unwrap()))
+}
+
+pub fn execute_make_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    seller_xyz_id: String,
+    kind: AuctionKind,
+    resources: Vec<Resource>,
+    rmi_denom: String,
+    start_price_rmi: Uint128,
+    end_price_rmi: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check that the sender owns seller_xyz_id
+    if !validate_nft_is_owned_by_wallet(
+        &seller_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_nft_contract,
+    )? {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // check that the sender provided sufficient make listing fee
+    check_sufficient_funds(info.funds, &config.make_listing_fee)?;
+
+    // save the auction to storage
+    let auctions = Auctions::default();
+    let auction = auctions.save_auction(
+        deps.storage,
+        &deps.querier,
+        seller_xyz_id.clone(),
+        kind,
+        rmi_denom,
+        start_price_rmi,
+        end_price_rmi,
+        env.block.time,
+        resources,
+    )?;
+
+    // burn auctioned resources from seller_xyz_id
+    let mut messages: Vec<WasmMsg> = vec![];
+    for resource in auction.resources.iter() {
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+        messages.push(burn_resource(
+            info.sender.to_string(),
+            seller_xyz_id.clone(),
+            resource_contract.to_string(),
+            resource.amount,
+        )?)
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "make_auction")
+        .add_attribute("auction", serde_json::to_string(&auction).unwrap()))
+}
+
+pub fn execute_bid_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+    bidder_xyz_id: String,
+    amount_rmi: Uint128,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check that the sender owns bidder_xyz_id
+    if !validate_nft_is_owned_by_wallet(
+        &bidder_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_nft_contract,
+    )? {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    let auctions = Auctions::default();
+    let mut auction = auctions.fetch_auction(deps.storage, auction_id)?;
+
+    if auction.kind != AuctionKind::English {
+        return Err(ContractError::WrongAuctionKind {});
+    }
+    if !auction.is_active(env.block.time) {
+        return Err(ContractError::InactiveAuction {});
+    }
+    if bidder_xyz_id == auction.seller_xyz_id {
+        return Err(ContractError::CantBidOwnAuction {});
+    }
+
+    let min_bid_rmi = auction.min_bid_rmi();
+    if amount_rmi < min_bid_rmi {
+        return Err(ContractError::AuctionBidTooLow(min_bid_rmi));
+    }
+
+    let rmi_denom_contract = config.resource_addr(&deps.querier, &auction.rmi_denom)?;
+    let mut messages: Vec<WasmMsg> = vec![];
+
+    // for the outbid xyz: mint back its escrowed bid
+    if let Some(outbid) = auction.highest_bid.take() {
+        messages.push(mint_resource(
+            outbid.bidder_xyz_id,
+            rmi_denom_contract.to_string(),
+            outbid.amount_rmi,
+        )?);
+    }
+
+    // for bidder_xyz_id: escrow the bid by burning amount_rmi
+    // NOTE: this burn operation will fail if bidder_xyz_id has insufficient balance of rmi_denom.
     messages.push(burn_resource(
         info.sender.to_string(),
-        taker_xyz_id.clone(),
+        bidder_xyz_id.clone(),
         rmi_denom_contract.to_string(),
-        listing.price_rmi,
+        amount_rmi,
     )?);
 
-    // for lister_xyz_id: mint deposit_rmi_amount in listing.deposit_rmi_denom
-    if !listing.deposit_rmi_amount.is_zero() {
+    auction.highest_bid = Some(AuctionBid {
+        bidder_xyz_id,
+        amount_rmi,
+    });
+    auctions.update_auction(deps.storage, &auction)?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "bid_auction")
+        .add_attribute("auction", serde_json::to_string(&auction).unwrap()))
+}
+
+pub fn execute_buy_auction(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+    buyer_xyz_id: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // check that the sender owns buyer_xyz_id
+    if !validate_nft_is_owned_by_wallet(
+        &buyer_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_nft_contract,
+    )? {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // check that the sender provided sufficient take listing fee
+    check_sufficient_funds(info.funds, &config.take_listing_fee)?;
+
+    let auctions = Auctions::default();
+    let auction = auctions.fetch_auction(deps.storage, auction_id)?;
+
+    if auction.kind != AuctionKind::Dutch {
+        return Err(ContractError::WrongAuctionKind {});
+    }
+    if !auction.is_active(env.block.time) {
+        return Err(ContractError::InactiveAuction {});
+    }
+    if buyer_xyz_id == auction.seller_xyz_id {
+        return Err(ContractError::CantBidOwnAuction {});
+    }
+
+    // remove the auction from storage
+    auctions.remove_auction(deps.storage, auction_id)?;
+
+    let price_rmi = auction.current_price_rmi(env.block.time);
+    let rmi_denom_contract = config.resource_addr(&deps.querier, &auction.rmi_denom)?;
+    let mut messages: Vec<WasmMsg> = vec![];
+
+    // for seller_xyz_id: mint the current price in rmi_denom, less fees
+    messages.extend(pay_seller(
+        deps.storage,
+        &config,
+        &auction.seller_xyz_id,
+        &auction.rmi_denom,
+        &rmi_denom_contract,
+        price_rmi,
+    )?);
+
+    // for buyer_xyz_id: burn the current price in rmi_denom
+    // NOTE: this burn operation will fail if buyer_xyz_id has insufficient balance of rmi_denom.
+    messages.push(burn_resource(
+        info.sender.to_string(),
+        buyer_xyz_id.clone(),
+        rmi_denom_contract.to_string(),
+        price_rmi,
+    )?);
+
+    // for buyer_xyz_id: mint auction.resources
+    for resource in auction.resources.iter() {
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
         messages.push(mint_resource(
-            listing.lister_xyz_id.clone(),
-            config
-                .resource_addr(&listing.deposit_rmi_denom)?
-                .to_string(),
-            listing.deposit_rmi_amount,
-        )?);
+            buyer_xyz_id.clone(),
+            resource_contract.to_string(),
+            resource.amount,
+        )?)
     }
 
-    // for taker_xyz_id: mint listing.resources
-    for resource in listing.resources.iter() {
-        let resource_contract = config.resource_addr(&resource.id)?;
+    // record the trade for price history
+    Trades::default().save_trade(
+        deps.storage,
+        auction.seller_xyz_id.clone(),
+        buyer_xyz_id.clone(),
+        auction.resources.clone(),
+        price_rmi,
+        auction.rmi_denom.clone(),
+        vec![],
+        env.block.time,
+    )?;
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "buy_auction")
+        .add_attribute("buyer_xyz_id", buyer_xyz_id)
+        .add_attribute("price_rmi", price_rmi)
+        .add_attribute("auction", serde_json::to_string(&auction).unwrap()))
+}
+
+pub fn execute_settle_auction(
+    deps: DepsMut,
+    env: Env,
+    _info: MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let auctions = Auctions::default();
+    let auction = auctions.fetch_auction(deps.storage, auction_id)?;
+
+    if auction.kind != AuctionKind::English {
+        return Err(ContractError::WrongAuctionKind {});
+    }
+    if auction.is_active(env.block.time) {
+        return Err(ContractError::AuctionNotEnded {});
+    }
+
+    // remove the auction from storage
+    auctions.remove_auction(deps.storage, auction_id)?;
+
+    let mut messages: Vec<WasmMsg> = vec![];
+
+    // the bundle goes to the highest bidder, or back to the seller if there were no bids
+    let recipient_xyz_id = match &auction.highest_bid {
+        Some(bid) => {
+            // for seller_xyz_id: mint the escrowed winning bid, less fees
+            let rmi_denom_contract = config.resource_addr(&deps.querier, &auction.rmi_denom)?;
+            messages.extend(pay_seller(
+                deps.storage,
+                &config,
+                &auction.seller_xyz_id,
+                &auction.rmi_denom,
+                &rmi_denom_contract,
+                bid.amount_rmi,
+            )?);
+
+            // record the trade for price history
+            Trades::default().save_trade(
+                deps.storage,
+                auction.seller_xyz_id.clone(),
+                bid.bidder_xyz_id.clone(),
+                auction.resources.clone(),
+                bid.amount_rmi,
+                auction.rmi_denom.clone(),
+                vec![],
+                env.block.time,
+            )?;
+            bid.bidder_xyz_id.clone()
+        }
+        None => auction.seller_xyz_id.clone(),
+    };
+
+    for resource in auction.resources.iter() {
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
         messages.push(mint_resource(
-            taker_xyz_id.clone(),
+            recipient_xyz_id.clone(),
             resource_contract.to_string(),
             resource.amount,
         )?)
@@ -210,9 +818,451 @@ pub fn execute_take_listing(
     Ok(Response::new()
         .add_messages(messages)
         .add_attribute("method", "execute")
-        .add_attribute("action", "take_listing")
-        .add_attribute("taker_xyz_id", taker_xyz_id)
-        .add_attribute("listing", serde_json::to_string(&listing).unwrap()))
+        .add_attribute("action", "settle_auction")
+        .add_attribute("recipient_xyz_id", recipient_xyz_id)
+        .add_attribute("auction", serde_json::to_string(&auction).unwrap()))
+}
+
+pub fn execute_revoke_auction(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    auction_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let auctions = Auctions::default();
+    let auction = auctions.fetch_auction(deps.storage, auction_id)?;
+
+    // check that the sender owns auction.seller_xyz_id
+    if !validate_nft_is_owned_by_wallet(
+        &auction.seller_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_nft_contract,
+    )? {
+        return Err(ContractError::Unauthorized {});
+    };
+
+    // escrowed bids must be settled instead
+    if auction.highest_bid.is_some() {
+        return Err(ContractError::AuctionHasBids {});
+    }
+
+    // remove the auction from storage
+    auctions.remove_auction(deps.storage, auction_id)?;
+
+    // mint auctioned resources back to seller_xyz_id
+    let mut messages: Vec<WasmMsg> = vec![];
+    for resource in auction.resources.iter() {
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+        messages.push(mint_resource(
+            auction.seller_xyz_id.clone(),
+            resource_contract.to_string(),
+            resource.amount,
+        )?)
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "revoke_auction")
+        .add_attribute("auction", serde_json::to_string(&auction).unwrap()))
+}
+
+pub fn execute_receive_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw721_msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    // only xyz NFTs can be listed
+    if info.sender != config.xyz_nft_contract {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let seller = deps.api.addr_validate(&cw721_msg.sender)?;
+
+    match from_binary(&cw721_msg.msg)? {
+        ReceiveNftMsg::ListNft {
+            price,
+            payout_xyz_id,
+            bundle_xyz_id,
+            resources,
+        } => {
+            // check that the bundle isn't attached to the escrowed xyz itself, and
+            // that the seller owns bundle_xyz_id
+            if let Some(bundle_xyz_id) = &bundle_xyz_id {
+                if *bundle_xyz_id == format!("xyz #{}", cw721_msg.token_id) {
+                    return Err(ContractError::BundleIsListedXyz {});
+                }
+                if !validate_nft_is_owned_by_wallet(
+                    bundle_xyz_id,
+                    &seller.to_string(),
+                    &deps.querier,
+                    &config.xyz_nft_contract,
+                )? {
+                    return Err(ContractError::Unauthorized {});
+                };
+            }
+
+            let nft_listings = NftListings::default();
+            let nft_listing = nft_listings.save_nft_listing(
+                deps.storage,
+                &deps.querier,
+                cw721_msg.token_id,
+                seller.clone(),
+                price,
+                payout_xyz_id,
+                bundle_xyz_id,
+                resources.unwrap_or_default(),
+                env.block.time,
+            )?;
+
+            // for bundle_xyz_id: burn the bundled resources
+            let mut messages: Vec<WasmMsg> = vec![];
+            if let Some(bundle_xyz_id) = &nft_listing.bundle_xyz_id {
+                for resource in nft_listing.resources.iter() {
+                    let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+                    messages.push(burn_resource(
+                        seller.to_string(),
+                        bundle_xyz_id.clone(),
+                        resource_contract.to_string(),
+                        resource.amount,
+                    )?);
+                }
+            }
+
+            Ok(Response::new()
+                .add_messages(messages)
+                .add_attribute("method", "execute")
+                .add_attribute("action", "list_nft")
+                .add_attribute("nft_listing", serde_json::to_string(&nft_listing).unwrap()))
+        }
+    }
+}
+
+pub fn execute_buy_nft(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    nft_listing_id: u64,
+    payer_xyz_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let nft_listings = NftListings::default();
+    let nft_listing = nft_listings.fetch_nft_listing(deps.storage, nft_listing_id)?;
+
+    if info.sender == nft_listing.seller {
+        return Err(ContractError::CantTakeOwnListing {});
+    }
+
+    // remove the NFT listing from storage
+    nft_listings.remove_nft_listing(deps.storage, nft_listing_id)?;
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    match &nft_listing.price {
+        NftPrice::Native(price) => {
+            // check that the sender provided the price; native sales are outside the
+            // trade fee split, so the seller is paid in full
+            check_sufficient_funds(info.funds, price)?;
+
+            if !price.amount.is_zero() {
+                messages.push(
+                    BankMsg::Send {
+                        to_address: nft_listing.seller.to_string(),
+                        amount: vec![price.clone()],
+                    }
+                    .into(),
+                );
+            }
+        }
+        NftPrice::Rmi { amount, denom } => {
+            let payer_xyz_id = payer_xyz_id.ok_or(ContractError::WrongPaymentMethod {})?;
+
+            // check that the sender owns payer_xyz_id
+            if !validate_nft_is_owned_by_wallet(
+                &payer_xyz_id,
+                &info.sender.to_string(),
+                &deps.querier,
+                &config.xyz_nft_contract,
+            )? {
+                return Err(ContractError::Unauthorized {});
+            };
+
+            let rmi_denom_contract = config.resource_addr(&deps.querier, denom)?;
+
+            // for payer_xyz_id: burn the price in denom
+            // NOTE: this burn operation will fail if payer_xyz_id has insufficient balance of denom.
+            messages.push(
+                burn_resource(
+                    info.sender.to_string(),
+                    payer_xyz_id,
+                    rmi_denom_contract.to_string(),
+                    *amount,
+                )?
+                .into(),
+            );
+
+            // for payout_xyz_id: mint the price in denom, less fees
+            let payout_xyz_id = nft_listing
+                .payout_xyz_id
+                .as_ref()
+                .ok_or(ContractError::InvalidListingPrice {})?;
+            messages.extend(
+                pay_seller(
+                    deps.storage,
+                    &config,
+                    payout_xyz_id,
+                    denom,
+                    &rmi_denom_contract,
+                    *amount,
+                )?
+                .into_iter()
+                .map(CosmosMsg::from),
+            );
+        }
+    }
+
+    // transfer the escrowed xyz to the buyer
+    messages.push(
+        WasmMsg::Execute {
+            contract_addr: config.xyz_nft_contract.to_string(),
+            msg: to_binary(&XyzExecuteMsg::TransferNft {
+                recipient: info.sender.to_string(),
+                token_id: nft_listing.token_id.clone(),
+            })?,
+            funds: vec![],
+        }
+        .into(),
+    );
+
+    // for the bought xyz: mint the bundled resources
+    for resource in nft_listing.resources.iter() {
+        let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+        messages.push(
+            mint_resource(
+                nft_listing.xyz_id(),
+                resource_contract.to_string(),
+                resource.amount,
+            )?
+            .into(),
+        );
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "buy_nft")
+        .add_attribute("buyer", info.sender)
+        .add_attribute("nft_listing", serde_json::to_string(&nft_listing).unwrap()))
+}
+
+pub fn execute_revoke_nft_listing(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    nft_listing_id: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let nft_listings = NftListings::default();
+    let nft_listing = nft_listings.fetch_nft_listing(deps.storage, nft_listing_id)?;
+
+    if info.sender != nft_listing.seller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // remove the NFT listing from storage
+    nft_listings.remove_nft_listing(deps.storage, nft_listing_id)?;
+
+    // return the escrowed xyz to the seller
+    let mut messages: Vec<WasmMsg> = vec![WasmMsg::Execute {
+        contract_addr: config.xyz_nft_contract.to_string(),
+        msg: to_binary(&XyzExecuteMsg::TransferNft {
+            recipient: nft_listing.seller.to_string(),
+            token_id: nft_listing.token_id.clone(),
+        })?,
+        funds: vec![],
+    }];
+
+    // mint bundled resources back to bundle_xyz_id
+    if let Some(bundle_xyz_id) = &nft_listing.bundle_xyz_id {
+        for resource in nft_listing.resources.iter() {
+            let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+            messages.push(mint_resource(
+                bundle_xyz_id.clone(),
+                resource_contract.to_string(),
+                resource.amount,
+            )?)
+        }
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("method", "execute")
+        .add_attribute("action", "revoke_nft_listing")
+        .add_attribute("nft_listing", serde_json::to_string(&nft_listing).unwrap()))
+}
+
+/// Accrues an amount to the treasury fees for `denom`
+fn accrue_fee(
+    storage: &mut dyn Storage,
+    denom: &str,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    if !amount.is_zero() {
+        FEES.update(storage, denom, |fees| -> StdResult<_> {
+            Ok(fees.unwrap_or_default() + amount)
+        })?;
+    }
+
+    Ok(())
+}
+
+pub fn execute_sweep_expired(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    let listings = Listings::default();
+    let expired = listings.fetch_expired_listings(deps.storage, env.block.time, limit)?;
+
+    let mut messages: Vec<WasmMsg> = vec![];
+    for listing in expired.iter() {
+        // remove the listing from storage
+        listings.remove_listing(deps.storage, listing.listing_id)?;
+
+        // mint listed resources back to lister_xyz_id
+        for resource in listing.resources.iter() {
+            let resource_contract = config.resource_addr(&deps.querier, &resource.id)?;
+            messages.push(mint_resource(
+                listing.lister_xyz_id.clone(),
+                resource_contract.to_string(),
+                resource.amount,
+            )?)
+        }
+
+        // refund part of the deposit to lister_xyz_id and forfeit the rest
+        let deposits = if listing.deposit_rmi_amount.is_zero() {
+            listing.deposit_resources.clone()
+        } else {
+            vec![Resource {
+                id: listing.deposit_rmi_denom.clone(),
+                amount: listing.deposit_rmi_amount,
+            }]
+        };
+        for deposit in deposits.iter() {
+            let refund = config.expired_deposit_refund(deposit.amount);
+            if !refund.is_zero() {
+                messages.push(mint_resource(
+                    listing.lister_xyz_id.clone(),
+                    config.resource_addr(&deps.querier, &deposit.id)?.to_string(),
+                    refund,
+                )?);
+            }
+            accrue_fee(deps.storage, &deposit.id, deposit.amount - refund)?;
+        }
+    }
+
+    let mut response = Response::new().add_messages(messages);
+
+    // pay the sweeper a reward per swept listing, capped by the contract's balance
+    if let Some(sweep_reward) = &config.sweep_reward {
+        let balance = deps
+            .querier
+            .query_balance(env.contract.address, sweep_reward.denom.clone())?;
+        let reward = (sweep_reward.amount * Uint128::from(expired.len() as u128))
+            .min(balance.amount);
+        if !reward.is_zero() {
+            response = response.add_message(BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: vec![Coin {
+                    denom: sweep_reward.denom.clone(),
+                    amount: reward,
+                }],
+            });
+        }
+    }
+
+    let listing_ids = expired
+        .iter()
+        .map(|listing| listing.listing_id.to_string())
+        .collect::<Vec<String>>();
+
+    Ok(response
+        .add_attribute("method", "execute")
+        .add_attribute("action", "sweep_expired")
+        .add_attribute("listing_ids", listing_ids.join(",")))
+}
+
+pub fn execute_withdraw_fees(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    treasury_xyz_id: Option<String>,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    let owner = OWNER.load(deps.storage)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let mut response = Response::new();
+
+    // mint the accrued treasury fees in each RMI denom to treasury_xyz_id
+    if let Some(treasury_xyz_id) = treasury_xyz_id {
+        let fees = FEES
+            .range(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        for (denom, amount) in fees {
+            let denom = String::from_utf8(denom).map_err(StdError::from)?;
+            FEES.remove(deps.storage, &denom);
+            response = response.add_message(mint_resource(
+                treasury_xyz_id.clone(),
+                config.resource_addr(&deps.querier, &denom)?.to_string(),
+                amount,
+            )?);
+        }
+    }
+
+    // send the native listing fees held by the contract to recipient, keeping back
+    // the reserve for sweep rewards
+    if let Some(recipient) = recipient {
+        let recipient = deps.api.addr_validate(&recipient)?;
+        let balance = deps
+            .querier
+            .query_all_balances(env.contract.address)?
+            .into_iter()
+            .map(|mut coin| {
+                if let Some(sweep_reward) = &config.sweep_reward {
+                    if coin.denom == sweep_reward.denom {
+                        coin.amount = coin.amount.saturating_sub(config.sweep_reward_reserve);
+                    }
+                }
+                coin
+            })
+            .filter(|coin| !coin.amount.is_zero())
+            .collect::<Vec<Coin>>();
+        if !balance.is_empty() {
+            response = response.add_message(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: balance,
+            });
+        }
+    }
+
+    Ok(response
+        .add_attribute("method", "execute")
+        .add_attribute("action", "withdraw_fees"))
 }
 
 pub fn execute_update_config(
@@ -258,7 +1308,23 @@ pub fn execute_update_config(
         water_contract: config_patch.water_contract.unwrap_or(config.water_contract),
         gem_contract: config_patch.gem_contract.unwrap_or(config.gem_contract),
         life_contract: config_patch.life_contract.unwrap_or(config.life_contract),
+        resource_registry: config_patch.resource_registry.or(config.resource_registry),
+        trade_fee_percent: config_patch
+            .trade_fee_percent
+            .unwrap_or(config.trade_fee_percent),
+        royalty_percent: config_patch
+            .royalty_percent
+            .unwrap_or(config.royalty_percent),
+        royalty_xyz_id: config_patch.royalty_xyz_id.or(config.royalty_xyz_id),
+        expired_deposit_refund_percent: config_patch
+            .expired_deposit_refund_percent
+            .unwrap_or(config.expired_deposit_refund_percent),
+        sweep_reward: config_patch.sweep_reward.or(config.sweep_reward),
+        sweep_reward_reserve: config_patch
+            .sweep_reward_reserve
+            .unwrap_or(config.sweep_reward_reserve),
     };
+    new_config.validate()?;
 
     CONFIG.save(deps.storage, &new_config)?;
 
@@ -270,5 +1336,33 @@ pub fn execute_update_config(
 pub fn execute_migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
     CONFIG.save(deps.storage, &msg.config)?;
 
+    // the listing sort indexes for listings stored before they existed are populated
+    // afterwards, a page at a time, by ReindexListings
     Ok(Response::new().add_attribute("method", "migrate"))
 }
+
+pub fn execute_reindex_listings(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    let owner = OWNER.load(deps.storage)?;
+
+    if info.sender != owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let listing_ids = Listings::default().reindex_listings(deps.storage, start_after, limit)?;
+    let last_listing_id = listing_ids
+        .last()
+        .map(|listing_id| listing_id.to_string())
+        .unwrap_or_default();
+
+    Ok(Response::new()
+        .add_attribute("method", "execute")
+        .add_attribute("action", "reindex_listings")
+        .add_attribute("count", listing_ids.len().to_string())
+        .add_attribute("last_listing_id", last_listing_id))
+}
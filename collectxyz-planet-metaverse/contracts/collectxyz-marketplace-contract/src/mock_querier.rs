@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
 use collectxyz::nft::{QueryMsg as XyzQueryMsg, XyzTokenInfo};
+use collectxyz_resource_registry_contract::msg::{
+    QueryMsg as RegistryQueryMsg, ResourceEntry, ResourceResponse,
+};
 use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
 use cosmwasm_std::{
-    from_binary, from_slice, to_binary, Coin, ContractResult, OwnedDeps, Querier, QuerierResult,
+    from_binary, from_slice, to_binary, Addr, Coin, ContractResult, OwnedDeps, Querier, QuerierResult,
     QueryRequest, StdError, SystemError, SystemResult, WasmQuery,
 };
 use terra_cosmwasm::TerraQueryWrapper;
@@ -23,6 +26,26 @@ pub fn mock_dependencies_custom(
     }
 }
 
+/// Resources registered with the mock "resource-registry" contract
+pub fn mock_registry_resources() -> Vec<ResourceEntry> {
+    vec![
+        ResourceEntry {
+            id: "xyzICE".to_string(),
+            contract_address: Addr::unchecked("ice-contract"),
+            is_rmi_denom: true,
+            decimals: 6,
+            display_name: "Ice".to_string(),
+        },
+        ResourceEntry {
+            id: "xyzSAND".to_string(),
+            contract_address: Addr::unchecked("sand-contract"),
+            is_rmi_denom: false,
+            decimals: 6,
+            display_name: "Sand".to_string(),
+        },
+    ]
+}
+
 pub struct CustomMockQuerier {
     base: MockQuerier<TerraQueryWrapper>,
     xyz_balances: HashMap<String, XyzTokenInfo>,
@@ -60,6 +83,20 @@ impl<'a> CustomMockQuerier {
                         panic!("unsupported message type! {}", msg)
                     }
                 }
+                if contract_addr == "resource-registry" {
+                    if let RegistryQueryMsg::Resource { resource_id } =
+                        from_binary::<RegistryQueryMsg>(&msg).unwrap()
+                    {
+                        let resource = mock_registry_resources()
+                            .into_iter()
+                            .find(|resource| resource.id == resource_id);
+                        return SystemResult::Ok(ContractResult::from(to_binary(
+                            &ResourceResponse { resource },
+                        )));
+                    } else {
+                        panic!("unsupported message type! {}", msg)
+                    }
+                }
                 panic!("unsupported query");
             }
             _ => self.base.handle_query(request),
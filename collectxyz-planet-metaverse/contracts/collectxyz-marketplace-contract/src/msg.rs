@@ -1,16 +1,20 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::{Addr, Coin, Uint128};
+use cosmwasm_std::{Addr, Coin, Decimal, Uint128};
+use cw721::Cw721ReceiveMsg;
 
-use crate::state::{Config, Listing, Resource};
+use crate::state::{
+    Auction, AuctionKind, Bid, Config, Listing, ListingCursor, ListingSort, ListingState,
+    NftListing, NftPrice, Resource, Trade,
+};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub config: Config,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
 pub struct ConfigPatch {
     pub listing_expiry_seconds: Option<u64>,
     pub listing_pending_seconds: Option<u64>,
@@ -26,6 +30,13 @@ pub struct ConfigPatch {
     pub water_contract: Option<Addr>,
     pub gem_contract: Option<Addr>,
     pub life_contract: Option<Addr>,
+    pub resource_registry: Option<Addr>,
+    pub trade_fee_percent: Option<u64>,
+    pub royalty_percent: Option<u64>,
+    pub royalty_xyz_id: Option<String>,
+    pub expired_deposit_refund_percent: Option<u64>,
+    pub sweep_reward: Option<Coin>,
+    pub sweep_reward_reserve: Option<Uint128>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -35,11 +46,21 @@ pub enum ExecuteMsg {
     /// xyz's balance for the relevant resource to prevent spending the listed resources,
     /// plus an RMI deposit proportional to `price_rmi`. Requires that the caller owns
     /// `lister_xyz_id` and that caller provided sufficient fees (if any are configured).
+    ///
+    /// If `price_resources` is given, the listing is instead priced in that resource
+    /// bundle of non-zero amounts: `price_rmi` must be zero, `deposit_rmi_denom` must be
+    /// empty, and the deposit is burned proportionally from each of the price's resources.
+    ///
+    /// A `divisible` listing of a single resource can be taken in part; see `TakeListing`.
+    /// If `allowed_taker_xyz_ids` is given, only those xyz can take the listing.
     MakeListing {
         lister_xyz_id: String,
         price_rmi: Uint128,
         deposit_rmi_denom: String,
         resources: Vec<Resource>,
+        price_resources: Option<Vec<Resource>>,
+        divisible: Option<bool>,
+        allowed_taker_xyz_ids: Option<Vec<String>>,
     },
     /// Remove an open listing from the marketplace. Executing this message mints
     /// the listed resource quantity back to the xyz that made the listing. Requires
@@ -47,14 +68,102 @@ pub enum ExecuteMsg {
     RevokeListing { listing_id: u64 },
     /// Take the listing using the selected RMI denom (`xyzROCK`, `xyzICE`, or `xyzMETAL`).
     /// Requires that the caller owns `taker_xyz_id` and that `taker_xyz_id` has sufficient
-    /// balance of the selected RMI denom.
+    /// balance of the selected RMI denom. For listings priced in resources, `rmi_denom`
+    /// is ignored and `taker_xyz_id` pays the listing's `price_resources` instead.
+    ///
+    /// For divisible listings, `units` takes only that many whole resource units at a
+    /// pro-rated price and leaves the rest listed. Omitting it takes the whole listing.
     TakeListing {
         listing_id: u64,
         taker_xyz_id: String,
         rmi_denom: String,
+        units: Option<u64>,
+    },
+    /// Make a bid on the marketplace for a resource bundle. Executing this message burns
+    /// `price_rmi` in `rmi_denom` from `bidder_xyz_id` to escrow the payment. Requires that
+    /// the caller owns `bidder_xyz_id` and provided sufficient fees (if any are configured).
+    MakeBid {
+        bidder_xyz_id: String,
+        wanted_resources: Vec<Resource>,
+        price_rmi: Uint128,
+        rmi_denom: String,
+    },
+    /// Remove a bid from the marketplace. Executing this message mints the escrowed
+    /// payment back to the bidding xyz. Requires that the caller owns the bidding xyz.
+    RevokeBid { bid_id: u64 },
+    /// Fill an unexpired bid. Executing this message burns the wanted resources from
+    /// `seller_xyz_id`, mints them to the bidding xyz, and mints the escrowed payment to
+    /// `seller_xyz_id`. Requires that the caller owns `seller_xyz_id`.
+    FillBid { bid_id: u64, seller_xyz_id: String },
+    /// Auction a resource bundle. Executing this message burns the bundle from
+    /// `seller_xyz_id`. English auctions take bids of at least `start_price_rmi` until
+    /// they expire; Dutch auctions sell to the first buyer at a price decaying from
+    /// `start_price_rmi` to `end_price_rmi`. Prices aren't bound to the listing tiers.
+    MakeAuction {
+        seller_xyz_id: String,
+        kind: AuctionKind,
+        resources: Vec<Resource>,
+        rmi_denom: String,
+        start_price_rmi: Uint128,
+        end_price_rmi: Option<Uint128>,
+    },
+    /// Bid on an English auction. Executing this message burns `amount_rmi` from
+    /// `bidder_xyz_id` to escrow it, and mints the previous highest bid back to its bidder.
+    BidAuction {
+        auction_id: u64,
+        bidder_xyz_id: String,
+        amount_rmi: Uint128,
+    },
+    /// Buy a Dutch auction at its current price.
+    BuyAuction {
+        auction_id: u64,
+        buyer_xyz_id: String,
+    },
+    /// Settle an ended English auction, sending the bundle to the highest bidder and the
+    /// winning bid to the seller, or the bundle back to the seller if there were no bids.
+    /// Callable by anyone.
+    SettleAuction { auction_id: u64 },
+    /// Remove an auction without bids and mint the bundle back to the seller. Requires
+    /// that the caller owns the seller xyz.
+    RevokeAuction { auction_id: u64 },
+    /// Hook for the xyz NFT contract's `SendNft`. The sent xyz is escrowed by the
+    /// marketplace and listed as described by the `ReceiveNftMsg` payload.
+    ReceiveNft(Cw721ReceiveMsg),
+    /// Buy a listed xyz NFT. Native prices must be sent as funds and are paid to the
+    /// seller in full, without the trade fee or royalty; RMI prices are burned from
+    /// `payer_xyz_id`, which the caller must own, and split like any other trade. The
+    /// NFT is transferred to the caller and the attached resource bundle, if any, is
+    /// minted to the bought xyz.
+    BuyNft {
+        nft_listing_id: u64,
+        payer_xyz_id: Option<String>,
+    },
+    /// Remove an NFT listing, returning the xyz and its resource bundle. Requires that
+    /// the caller is the seller.
+    RevokeNftListing { nft_listing_id: u64 },
+    /// Remove up to `limit` expired listings, soonest expired first. Callable by anyone. Listed
+    /// resources are minted back to each lister, `expired_deposit_refund_percent` of the
+    /// deposit is refunded and the rest forfeited to the treasury, and the caller is paid
+    /// the configured `sweep_reward` per swept listing while collected fees allow.
+    SweepExpired { limit: Option<u32> },
+    /// Withdraw accrued fees. Owner only. The treasury's share of trade fees is minted
+    /// to `treasury_xyz_id` in each RMI denom, and native listing fees held by the
+    /// contract are sent to `recipient`, less the `sweep_reward_reserve` kept back to
+    /// pay sweepers. Either side is skipped when not given.
+    WithdrawFees {
+        treasury_xyz_id: Option<String>,
+        recipient: Option<String>,
     },
     /// Make a patch update to the contract config.
     UpdateConfig { config_patch: ConfigPatch },
+    /// Re-save a page of listings so that the unit price and expiry indexes are populated
+    /// for listings stored before they existed. Owner only, run after a migration until a
+    /// page comes back empty; until then, sorted queries and `SweepExpired` miss the
+    /// listings not yet reindexed.
+    ReindexListings {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
@@ -69,15 +178,76 @@ pub enum QueryMsg {
     ListingInfo {
         listing_id: u64,
     },
+    /// Listings matching all the given filters. `states` takes precedence over
+    /// `include_inactive`. Sorted by `sort_by` (listing ID by default); pages of a
+    /// sorted query continue from the previous page's `next_cursor`, while
+    /// `start_after` is kept for listing ID sorts.
     Listings {
         lister_xyz_id: Option<String>,
         prices: Option<Vec<Uint128>>,
         resources: Option<Vec<String>>,
+        min_resource_amounts: Option<Vec<Resource>>,
+        states: Option<Vec<ListingState>>,
+        include_inactive: Option<bool>,
+        sort_by: Option<ListingSort>,
+        ascending: Option<bool>,
+        start_after: Option<u64>,
+        cursor: Option<ListingCursor>,
+        limit: Option<u32>,
+    },
+    BidInfo {
+        bid_id: u64,
+    },
+    Bids {
+        bidder_xyz_id: Option<String>,
+        prices: Option<Vec<Uint128>>,
+        resources: Option<Vec<String>>,
+        include_inactive: Option<bool>,
+        ascending: Option<bool>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    AuctionInfo {
+        auction_id: u64,
+    },
+    Auctions {
+        seller_xyz_id: Option<String>,
         include_inactive: Option<bool>,
         ascending: Option<bool>,
         start_after: Option<u64>,
         limit: Option<u32>,
     },
+    NftListingInfo {
+        nft_listing_id: u64,
+    },
+    NftListings {
+        seller: Option<String>,
+        ascending: Option<bool>,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    Fees {},
+    /// Most recent trades including the given resource, newest first
+    Trades {
+        resource_id: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Volume-weighted average price of one whole unit of the given resource in
+    /// `rmi_denom` over the last `window_seconds`. Only trades of that resource alone
+    /// priced in that denom count, since bundle prices can't be attributed to a single
+    /// resource. Trades are summed in hourly buckets, so the window is rounded out to
+    /// whole hours; it can be at most 30 days.
+    Vwap {
+        resource_id: String,
+        rmi_denom: String,
+        window_seconds: u64,
+    },
+    /// RMI volume per denom over the last `window_seconds`, 24 hours by default. Summed
+    /// in hourly buckets like `Vwap`, with the same 30 day limit.
+    Volume {
+        window_seconds: Option<u64>,
+    },
     Config {},
 }
 
@@ -85,4 +255,74 @@ pub enum QueryMsg {
 #[serde(rename_all = "snake_case")]
 pub struct ListingsResponse {
     pub listings: Vec<Listing>,
+    /// Cursor after the last listing in this page
+    pub next_cursor: Option<ListingCursor>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct BidsResponse {
+    pub bids: Vec<Bid>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AuctionsResponse {
+    pub auctions: Vec<Auction>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct FeesResponse {
+    /// Accrued treasury fees, as an amount per RMI denom
+    pub fees: Vec<Resource>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TradesResponse {
+    pub trades: Vec<Trade>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct VwapResponse {
+    pub resource_id: String,
+    pub rmi_denom: String,
+    pub trade_count: u64,
+    /// Total resource amount traded
+    pub volume: Uint128,
+    /// Total RMI paid
+    pub value_rmi: Uint128,
+    /// RMI per whole resource unit, if there were any trades
+    pub vwap_rmi: Option<Decimal>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct VolumeResponse {
+    pub trade_count: u64,
+    /// RMI volume, as an amount per RMI denom
+    pub volumes: Vec<Resource>,
+}
+
+/// Payload of a `SendNft` to the marketplace
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiveNftMsg {
+    /// List the sent xyz at `price`. RMI prices are paid out to `payout_xyz_id`. If
+    /// `bundle_xyz_id` is given, `resources` are burned from it and go with the sale;
+    /// it can't be the sent xyz itself.
+    ListNft {
+        price: NftPrice,
+        payout_xyz_id: Option<String>,
+        bundle_xyz_id: Option<String>,
+        resources: Option<Vec<Resource>>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct NftListingsResponse {
+    pub nft_listings: Vec<NftListing>,
 }
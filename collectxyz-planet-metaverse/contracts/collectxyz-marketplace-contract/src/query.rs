@@ -1,7 +1,15 @@
-use cosmwasm_std::{Deps, Env, StdResult, Uint128};
+use cosmwasm_std::{Decimal, Deps, Env, Order, StdResult, Uint128};
 
-use crate::msg::ListingsResponse;
-use crate::state::{Config, Listing, Listings, CONFIG};
+use crate::msg::{
+    AuctionsResponse, BidsResponse, FeesResponse, ListingsResponse, NftListingsResponse,
+    TradesResponse, VolumeResponse, VwapResponse,
+};
+use crate::state::{
+    Auction, Auctions, Bid, Bids, Config, Listing, ListingCursor, ListingSort, ListingState,
+    Listings, NftListing, NftListings, Resource, Trades, CONFIG, FEES, RESOURCE_INTEGER_AMOUNT,
+};
+
+const DEFAULT_VOLUME_WINDOW_SECONDS: u64 = 24 * 60 * 60;
 
 pub fn query_listing_info(deps: Deps, _env: Env, listing_id: u64) -> StdResult<Listing> {
     let listings = Listings::default();
@@ -14,11 +22,16 @@ pub fn query_listings(
     lister_xyz_id: Option<String>,
     prices: Option<Vec<Uint128>>,
     resources: Option<Vec<String>>,
+    min_resource_amounts: Option<Vec<Resource>>,
+    states: Option<Vec<ListingState>>,
     include_inactive: Option<bool>,
+    sort_by: Option<ListingSort>,
     ascending: Option<bool>,
     start_after: Option<u64>,
+    cursor: Option<ListingCursor>,
     limit: Option<u32>,
 ) -> StdResult<ListingsResponse> {
+    let sort_by = sort_by.unwrap_or(ListingSort::ListingId);
     let listings = Listings::default();
     let result = listings.fetch_listings(
         deps.storage,
@@ -26,13 +39,193 @@ pub fn query_listings(
         lister_xyz_id,
         prices.unwrap_or(vec![]),
         resources.unwrap_or(vec![]),
+        min_resource_amounts.unwrap_or(vec![]),
+        states.unwrap_or(vec![]),
+        include_inactive.unwrap_or(false),
+        &sort_by,
+        ascending.unwrap_or(false),
+        start_after,
+        cursor,
+        limit,
+    )?;
+    let next_cursor = result.last().map(|listing| ListingCursor {
+        sort_key: listing.sort_key(&sort_by),
+        listing_id: listing.listing_id,
+    });
+
+    Ok(ListingsResponse {
+        listings: result,
+        next_cursor,
+    })
+}
+
+pub fn query_bid_info(deps: Deps, _env: Env, bid_id: u64) -> StdResult<Bid> {
+    let bids = Bids::default();
+    bids.fetch_bid(deps.storage, bid_id)
+}
+
+pub fn query_bids(
+    deps: Deps,
+    env: Env,
+    bidder_xyz_id: Option<String>,
+    prices: Option<Vec<Uint128>>,
+    resources: Option<Vec<String>>,
+    include_inactive: Option<bool>,
+    ascending: Option<bool>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<BidsResponse> {
+    let bids = Bids::default();
+    let result = bids.fetch_bids(
+        deps.storage,
+        env.block.time,
+        bidder_xyz_id,
+        prices.unwrap_or(vec![]),
+        resources.unwrap_or(vec![]),
         include_inactive.unwrap_or(false),
         ascending.unwrap_or(false),
         start_after,
         limit,
     )?;
 
-    Ok(ListingsResponse { listings: result })
+    Ok(BidsResponse { bids: result })
+}
+
+pub fn query_auction_info(deps: Deps, _env: Env, auction_id: u64) -> StdResult<Auction> {
+    let auctions = Auctions::default();
+    auctions.fetch_auction(deps.storage, auction_id)
+}
+
+pub fn query_auctions(
+    deps: Deps,
+    env: Env,
+    seller_xyz_id: Option<String>,
+    include_inactive: Option<bool>,
+    ascending: Option<bool>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<AuctionsResponse> {
+    let auctions = Auctions::default();
+    let result = auctions.fetch_auctions(
+        deps.storage,
+        env.block.time,
+        seller_xyz_id,
+        include_inactive.unwrap_or(false),
+        ascending.unwrap_or(false),
+        start_after,
+        limit,
+    )?;
+
+    Ok(AuctionsResponse { auctions: result })
+}
+
+pub fn query_nft_listing_info(
+    deps: Deps,
+    _env: Env,
+    nft_listing_id: u64,
+) -> StdResult<NftListing> {
+    let nft_listings = NftListings::default();
+    nft_listings.fetch_nft_listing(deps.storage, nft_listing_id)
+}
+
+pub fn query_nft_listings(
+    deps: Deps,
+    _env: Env,
+    seller: Option<String>,
+    ascending: Option<bool>,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<NftListingsResponse> {
+    let seller = seller.map(|s| deps.api.addr_validate(&s)).transpose()?;
+    let nft_listings = NftListings::default();
+    let result = nft_listings.fetch_nft_listings(
+        deps.storage,
+        seller,
+        ascending.unwrap_or(false),
+        start_after,
+        limit,
+    )?;
+
+    Ok(NftListingsResponse {
+        nft_listings: result,
+    })
+}
+
+pub fn query_fees(deps: Deps, _env: Env) -> StdResult<FeesResponse> {
+    let fees: StdResult<Vec<Resource>> = FEES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (denom, amount) = item?;
+            Ok(Resource {
+                id: String::from_utf8(denom)?,
+                amount,
+            })
+        })
+        .collect();
+
+    Ok(FeesResponse { fees: fees? })
+}
+
+pub fn query_trades(
+    deps: Deps,
+    _env: Env,
+    resource_id: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<TradesResponse> {
+    let trades = Trades::default();
+    let result = trades.fetch_recent_trades(deps.storage, &resource_id, start_after, limit)?;
+
+    Ok(TradesResponse { trades: result })
+}
+
+pub fn query_vwap(
+    deps: Deps,
+    env: Env,
+    resource_id: String,
+    rmi_denom: String,
+    window_seconds: u64,
+) -> StdResult<VwapResponse> {
+    let vwap = Trades::default().vwap_since(
+        deps.storage,
+        &resource_id,
+        &rmi_denom,
+        env.block.time,
+        window_seconds,
+    )?;
+
+    let vwap_rmi = if vwap.volume.is_zero() {
+        None
+    } else {
+        let value_rmi = vwap
+            .value_rmi
+            .checked_mul(Uint128::from(RESOURCE_INTEGER_AMOUNT))?;
+        Some(Decimal::from_ratio(value_rmi, vwap.volume))
+    };
+
+    Ok(VwapResponse {
+        resource_id,
+        rmi_denom,
+        trade_count: vwap.trade_count,
+        volume: vwap.volume,
+        value_rmi: vwap.value_rmi,
+        vwap_rmi,
+    })
+}
+
+pub fn query_volume(
+    deps: Deps,
+    env: Env,
+    window_seconds: Option<u64>,
+) -> StdResult<VolumeResponse> {
+    let window_seconds = window_seconds.unwrap_or(DEFAULT_VOLUME_WINDOW_SECONDS);
+    let mut volume = Trades::default().volume_since(deps.storage, env.block.time, window_seconds)?;
+    volume.volumes.sort_by(|a, b| a.id.cmp(&b.id));
+
+    Ok(VolumeResponse {
+        trade_count: volume.trade_count,
+        volumes: volume.volumes,
+    })
 }
 
 pub fn query_config(deps: Deps, _env: Env) -> StdResult<Config> {
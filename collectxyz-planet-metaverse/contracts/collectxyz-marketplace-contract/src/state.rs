@@ -3,13 +3,20 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 use std::iter::FromIterator;
 
-use cosmwasm_std::{Addr, Coin, Decimal, Order, StdResult, Storage, Timestamp, Uint128};
-use cw_storage_plus::{Bound, Index, IndexList, IndexedMap, Item, MultiIndex, U64Key};
+use cosmwasm_std::{
+    Addr, Coin, Decimal, Order, QuerierWrapper, StdError, StdResult, Storage, Timestamp, Uint128,
+};
+use collectxyz_resource_registry_contract::ResourceRegistry;
+use cw_storage_plus::{
+    Bound, Index, IndexList, IndexedMap, Item, Map, MultiIndex, PrimaryKey, U128Key, U64Key,
+};
 
 use crate::error::ContractError;
 
 pub const OWNER: Item<Addr> = Item::new("owner");
 pub const CONFIG: Item<Config> = Item::new("config");
+/// Treasury share of trade fees and forfeited deposits accrued per denom, pending `WithdrawFees`
+pub const FEES: Map<&str, Uint128> = Map::new("fees");
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
@@ -27,10 +34,44 @@ pub struct Config {
     pub water_contract: Addr,
     pub gem_contract: Addr,
     pub life_contract: Addr,
+    /// When set, resources are resolved against this registry instead of the contracts above
+    #[serde(default)]
+    pub resource_registry: Option<Addr>,
+    /// Percent of each trade's RMI price withheld from the seller as a protocol fee.
+    /// NFT sales priced in a native coin are not charged.
+    #[serde(default)]
+    pub trade_fee_percent: u64,
+    /// Percent of the protocol fee paid to `royalty_xyz_id` rather than the treasury
+    #[serde(default)]
+    pub royalty_percent: u64,
+    #[serde(default)]
+    pub royalty_xyz_id: Option<String>,
+    /// Percent of an expired listing's deposit refunded to the lister when it is swept;
+    /// the rest is forfeited to the treasury
+    #[serde(default)]
+    pub expired_deposit_refund_percent: u64,
+    /// Native reward per swept listing, paid to the sweeper from collected listing fees
+    #[serde(default)]
+    pub sweep_reward: Option<Coin>,
+    /// Amount of the `sweep_reward` denom that `WithdrawFees` leaves in the contract to
+    /// keep paying sweepers
+    #[serde(default)]
+    pub sweep_reward_reserve: Uint128,
 }
 
 impl Config {
-    pub fn resource_addr(&self, resource_id: &str) -> Result<Addr, ContractError> {
+    pub fn resource_addr(
+        &self,
+        querier: &QuerierWrapper,
+        resource_id: &str,
+    ) -> Result<Addr, ContractError> {
+        if let Some(registry) = &self.resource_registry {
+            return ResourceRegistry(registry.clone())
+                .resource(querier, resource_id)?
+                .map(|resource| resource.contract_address)
+                .ok_or_else(|| ContractError::InvalidResourceId(resource_id.to_string()));
+        }
+
         let addr = match resource_id {
             "xyzROCK" => self.rock_contract.clone(),
             "xyzMETAL" => self.metal_contract.clone(),
@@ -48,13 +89,62 @@ impl Config {
     pub fn deposit_rmi_amount(&self, price_rmi: Uint128) -> Uint128 {
         Decimal::from_ratio(self.listing_deposit_percent, 100u128) * price_rmi
     }
+
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if self.trade_fee_percent > 100
+            || self.royalty_percent > 100
+            || self.expired_deposit_refund_percent > 100
+        {
+            return Err(ContractError::InvalidFeePercent {});
+        }
+
+        Ok(())
+    }
+
+    /// Splits a trade's RMI price into the seller's proceeds, the treasury fee, and the royalty
+    pub fn trade_fee(&self, price_rmi: Uint128) -> (Uint128, Uint128, Uint128) {
+        let fee = Decimal::from_ratio(self.trade_fee_percent, 100u128) * price_rmi;
+        let royalty = match self.royalty_xyz_id {
+            Some(_) => Decimal::from_ratio(self.royalty_percent, 100u128) * fee,
+            None => Uint128::zero(),
+        };
+
+        (price_rmi - fee, fee - royalty, royalty)
+    }
+
+    /// Share of an expired listing's deposit that is refunded to the lister
+    pub fn expired_deposit_refund(&self, deposit: Uint128) -> Uint128 {
+        Decimal::from_ratio(self.expired_deposit_refund_percent, 100u128) * deposit
+    }
+
+    /// Deposit for a listing priced in a resource bundle, taken in the price's resources
+    pub fn deposit_resources(&self, price_resources: &[Resource]) -> Vec<Resource> {
+        price_resources
+            .iter()
+            .map(|resource| Resource {
+                id: resource.id.clone(),
+                amount: self.deposit_rmi_amount(resource.amount),
+            })
+            .filter(|resource| !resource.amount.is_zero())
+            .collect()
+    }
 }
 
-pub fn validate_resource_id(resource_id: &str) -> Result<(), ContractError> {
-    let is_valid = vec![
-        "xyzROCK", "xyzMETAL", "xyzICE", "xyzGAS", "xyzWATER", "xyzGEM", "xyzLIFE",
-    ]
-    .contains(&resource_id);
+pub fn validate_resource_id(
+    querier: &QuerierWrapper,
+    config: &Config,
+    resource_id: &str,
+) -> Result<(), ContractError> {
+    let is_valid = if let Some(registry) = &config.resource_registry {
+        ResourceRegistry(registry.clone())
+            .resource(querier, resource_id)?
+            .is_some()
+    } else {
+        vec![
+            "xyzROCK", "xyzMETAL", "xyzICE", "xyzGAS", "xyzWATER", "xyzGEM", "xyzLIFE",
+        ]
+        .contains(&resource_id)
+    };
 
     if !is_valid {
         Err(ContractError::InvalidResourceId(resource_id.to_string()))
@@ -63,8 +153,18 @@ pub fn validate_resource_id(resource_id: &str) -> Result<(), ContractError> {
     }
 }
 
-pub fn validate_rmi_denom(rmi_denom: &str) -> Result<(), ContractError> {
-    let is_valid = vec!["xyzROCK", "xyzMETAL", "xyzICE"].contains(&rmi_denom);
+pub fn validate_rmi_denom(
+    querier: &QuerierWrapper,
+    config: &Config,
+    rmi_denom: &str,
+) -> Result<(), ContractError> {
+    let is_valid = if let Some(registry) = &config.resource_registry {
+        ResourceRegistry(registry.clone())
+            .resource(querier, rmi_denom)?
+            .map_or(false, |resource| resource.is_rmi_denom)
+    } else {
+        vec!["xyzROCK", "xyzMETAL", "xyzICE"].contains(&rmi_denom)
+    };
 
     if !is_valid {
         Err(ContractError::InvalidResourceId(rmi_denom.to_string()))
@@ -90,19 +190,88 @@ pub struct Listing {
     pub active_at: Timestamp,
     pub expired_at: Timestamp,
     pub resources: Vec<Resource>,
+    /// When non-empty, the listing is a barter listing priced in this resource bundle
+    /// instead of `price_rmi`, and its deposit is taken in `deposit_resources`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub price_resources: Vec<Resource>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deposit_resources: Vec<Resource>,
+    /// Divisible listings can be partially taken in whole resource units. Their
+    /// `resources`, `price_rmi` and `deposit_rmi_amount` are what remains unfilled.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub divisible: bool,
+    /// When non-empty, only these xyz can take the listing, and it is active immediately
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_taker_xyz_ids: Vec<String>,
 }
 
-const RESOURCE_INTEGER_AMOUNT: u128 = 1000000;
+pub const RESOURCE_INTEGER_AMOUNT: u128 = 1000000;
+
+/// Validates a resource bundle - not empty, valid resource IDs, no duplicates, integer amounts
+pub fn validate_resource_bundle(
+    querier: &QuerierWrapper,
+    config: &Config,
+    resources: &[Resource],
+) -> Result<(), ContractError> {
+    // Resource bundle must not be empty
+    if resources.len() == 0 {
+        return Err(ContractError::EmptyResourceBundle {});
+    }
+
+    let mut seen: HashSet<String> = HashSet::new();
+    for resource in resources.iter() {
+        // Check resource ID exists
+        validate_resource_id(querier, config, &resource.id)?;
+
+        // Check listed amount is an integer
+        if resource.amount.u128() % RESOURCE_INTEGER_AMOUNT != 0 {
+            return Err(ContractError::PartialResourceAmount(
+                resource.amount.clone(),
+            ));
+        }
+
+        if seen.contains(&resource.id) {
+            return Err(ContractError::DuplicateResourceId(resource.id.clone()));
+        }
+
+        seen.insert(resource.id.clone());
+    }
+
+    Ok(())
+}
 
-#[derive(PartialEq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
 pub enum ListingState {
     Pending,
     Active,
     Expired,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ListingSort {
+    ListingId,
+    /// Listings stored before a migration are missing from this sort until they are
+    /// re-saved by `ReindexListings`; the same holds for `ExpiredAt`
+    UnitPrice,
+    ExpiredAt,
+}
+
+/// Position in a sorted listings query. Passing back a page's `next_cursor` continues
+/// after its last listing, even if listings were added or removed in between.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListingCursor {
+    pub sort_key: Uint128,
+    pub listing_id: u64,
+}
+
+/// Extra decimal places kept in `Listing::unit_price_rmi`
+const UNIT_PRICE_PRECISION: u128 = 1000000;
+
 impl Listing {
     pub fn try_new(
+        querier: &QuerierWrapper,
         config: Config,
         listing_id: u64,
         lister_xyz_id: String,
@@ -110,53 +279,113 @@ impl Listing {
         deposit_rmi_denom: String,
         timestamp: Timestamp,
         resources: Vec<Resource>,
+        price_resources: Vec<Resource>,
+        divisible: bool,
+        allowed_taker_xyz_ids: Vec<String>,
     ) -> Result<Self, ContractError> {
-        // Validate the deposit RMI denom
-        validate_rmi_denom(&deposit_rmi_denom)?;
+        let (deposit_rmi_denom, deposit_rmi_amount, deposit_resources) =
+            if price_resources.is_empty() {
+                // Validate the deposit RMI denom
+                validate_rmi_denom(querier, &config, &deposit_rmi_denom)?;
 
-        // Validate the listing price
-        if !config.allowed_listing_prices.contains(&price_rmi) {
-            return Err(ContractError::InvalidListingPrice {});
-        }
+                // Validate the listing price
+                if !config.allowed_listing_prices.contains(&price_rmi) {
+                    return Err(ContractError::InvalidListingPrice {});
+                }
 
-        // Resource bundle must not be empty
-        if resources.len() == 0 {
-            return Err(ContractError::EmptyResourceBundle {});
-        }
+                (deposit_rmi_denom, config.deposit_rmi_amount(price_rmi), vec![])
+            } else {
+                // Barter listings are priced only in the lister's chosen resource bundle
+                if !price_rmi.is_zero() {
+                    return Err(ContractError::InvalidListingPrice {});
+                }
+                if !deposit_rmi_denom.is_empty() {
+                    return Err(ContractError::InvalidResourceId(deposit_rmi_denom));
+                }
+                validate_resource_bundle(querier, &config, &price_resources)?;
 
-        // Validate the resource bundle - valid resource IDs, no duplicates, integer amounts
-        let mut seen: HashSet<String> = HashSet::new();
-        for resource in resources.iter() {
-            // Check resource ID exists
-            validate_resource_id(&resource.id)?;
-
-            // Check listed amount is an integer
-            if resource.amount.u128() % RESOURCE_INTEGER_AMOUNT != 0 {
-                return Err(ContractError::PartialResourceAmount(
-                    resource.amount.clone(),
-                ));
-            }
+                // Each price resource must be non-zero, or the taker gets it for free
+                if let Some(resource) = price_resources.iter().find(|r| r.amount.is_zero()) {
+                    return Err(ContractError::PartialResourceAmount(resource.amount));
+                }
 
-            if seen.contains(&resource.id) {
-                return Err(ContractError::DuplicateResourceId(resource.id.clone()));
-            }
+                (String::new(), Uint128::zero(), config.deposit_resources(&price_resources))
+            };
 
-            seen.insert(resource.id.clone());
+        // Validate the listed resource bundle
+        validate_resource_bundle(querier, &config, &resources)?;
+
+        // Divisible listings must be a single RMI-priced resource, so fills pro-rate cleanly
+        if divisible && (resources.len() != 1 || !price_resources.is_empty()) {
+            return Err(ContractError::InvalidDivisibleListing {});
         }
 
+        // Private listings skip the pending window, since only the allowed takers can take them
+        let pending_seconds = if allowed_taker_xyz_ids.is_empty() {
+            config.listing_pending_seconds
+        } else {
+            0
+        };
+
         Ok(Listing {
             listing_id,
             lister_xyz_id,
             price_rmi,
             deposit_rmi_denom,
-            deposit_rmi_amount: config.deposit_rmi_amount(price_rmi),
+            deposit_rmi_amount,
             created_at: timestamp,
-            active_at: timestamp.plus_seconds(config.listing_pending_seconds),
+            active_at: timestamp.plus_seconds(pending_seconds),
             expired_at: timestamp.plus_seconds(config.listing_expiry_seconds),
             resources,
+            price_resources,
+            deposit_resources,
+            divisible,
+            allowed_taker_xyz_ids,
         })
     }
 
+    /// Number of whole resource units left in a divisible listing's single resource
+    pub fn units(&self) -> u64 {
+        self.resources
+            .first()
+            .map(|resource| (resource.amount.u128() / RESOURCE_INTEGER_AMOUNT) as u64)
+            .unwrap_or(0)
+    }
+
+    /// Splits off `units` of the listing, returning the filled portion and leaving
+    /// the remainder in `self`. The filled price is rounded up and the returned deposit
+    /// rounded down, so the lister always receives exactly `price_rmi` in total.
+    pub fn fill(&mut self, units: u64) -> Result<Listing, ContractError> {
+        let total_units = self.units();
+        if units == total_units {
+            let filled = self.clone();
+            self.resources = vec![];
+            return Ok(filled);
+        }
+        if !self.divisible {
+            return Err(ContractError::IndivisibleListing {});
+        }
+        if units == 0 || units > total_units {
+            return Err(ContractError::InvalidFillUnits(units));
+        }
+
+        let unfilled_units = total_units - units;
+        let remaining_price = self.price_rmi.multiply_ratio(unfilled_units, total_units);
+        let filled_deposit = self.deposit_rmi_amount.multiply_ratio(units, total_units);
+        let filled_amount = Uint128::from(units as u128 * RESOURCE_INTEGER_AMOUNT);
+
+        let mut filled = self.clone();
+        filled.price_rmi = self.price_rmi - remaining_price;
+        filled.deposit_rmi_amount = filled_deposit;
+        filled.resources[0].amount = filled_amount;
+
+        self.price_rmi = remaining_price;
+        self.deposit_rmi_amount = self.deposit_rmi_amount - filled_deposit;
+        self.resources[0].amount = self.resources[0].amount - filled_amount;
+
+        Ok(filled)
+    }
+
     pub fn state(&self, current_timestamp: Timestamp) -> ListingState {
         if self.expired_at <= current_timestamp {
             ListingState::Expired
@@ -167,6 +396,41 @@ impl Listing {
         }
     }
 
+    pub fn can_be_taken_by(&self, taker_xyz_id: &str) -> bool {
+        self.allowed_taker_xyz_ids.is_empty()
+            || self.allowed_taker_xyz_ids.iter().any(|id| id == taker_xyz_id)
+    }
+
+    /// RMI price of one whole resource unit across the bundle, in millionths of an RMI
+    pub fn unit_price_rmi(&self) -> Uint128 {
+        let total_amount = self
+            .resources
+            .iter()
+            .fold(Uint128::zero(), |total, resource| total + resource.amount);
+        if total_amount.is_zero() {
+            return Uint128::zero();
+        }
+
+        self.price_rmi
+            .multiply_ratio(RESOURCE_INTEGER_AMOUNT * UNIT_PRICE_PRECISION, total_amount)
+    }
+
+    pub fn sort_key(&self, sort: &ListingSort) -> Uint128 {
+        match sort {
+            ListingSort::ListingId => Uint128::from(self.listing_id),
+            ListingSort::UnitPrice => self.unit_price_rmi(),
+            ListingSort::ExpiredAt => Uint128::from(self.expired_at.nanos()),
+        }
+    }
+
+    pub fn has_min_resource_amounts(&self, min_resource_amounts: &[Resource]) -> bool {
+        min_resource_amounts.iter().all(|min| {
+            self.resources
+                .iter()
+                .any(|resource| resource.id == min.id && resource.amount >= min.amount)
+        })
+    }
+
     pub fn resource_tuples(&self) -> Vec<(String, String)> {
         self.resources
             .iter()
@@ -177,6 +441,12 @@ impl Listing {
 
 const DEFAULT_PAGINATION_LIMIT: u32 = 10;
 const MAX_PAGINATION_LIMIT: u32 = 30;
+const DEFAULT_REINDEX_LIMIT: u32 = 100;
+const MAX_REINDEX_LIMIT: u32 = 300;
+/// Length of the buckets that trade volume is aggregated into
+pub const TRADE_BUCKET_SECONDS: u64 = 60 * 60;
+/// Longest window the volume and VWAP queries sum buckets over
+pub const MAX_TRADE_WINDOW_SECONDS: u64 = 30 * 24 * 60 * 60;
 
 pub struct Listings<'a> {
     listings: IndexedMap<'a, U64Key, Listing, ListingIndexes<'a>>,
@@ -185,7 +455,13 @@ pub struct Listings<'a> {
 
 impl Default for Listings<'static> {
     fn default() -> Self {
-        Self::new("listings", "listings__lister", "listings_pk")
+        Self::new(
+            "listings",
+            "listings__lister",
+            "listings__unit_price",
+            "listings__expired_at",
+            "listings_pk",
+        )
     }
 }
 
@@ -193,6 +469,8 @@ impl<'a> Listings<'a> {
     pub fn new(
         listings_key: &'a str,
         listings_lister_key: &'a str,
+        listings_unit_price_key: &'a str,
+        listings_expired_at_key: &'a str,
         listings_pk_key: &'a str,
     ) -> Self {
         let listing_indexes = ListingIndexes {
@@ -201,6 +479,16 @@ impl<'a> Listings<'a> {
                 listings_key,
                 listings_lister_key,
             ),
+            unit_price: MultiIndex::new(
+                |o, k| (U128Key::from(o.unit_price_rmi().u128()), k),
+                listings_key,
+                listings_unit_price_key,
+            ),
+            expired_at: MultiIndex::new(
+                |o, k| (U64Key::from(o.expired_at.nanos()), k),
+                listings_key,
+                listings_expired_at_key,
+            ),
         };
 
         Listings {
@@ -219,16 +507,21 @@ impl<'a> Listings<'a> {
     pub fn save_listing(
         &self,
         storage: &mut dyn Storage,
+        querier: &QuerierWrapper,
         lister_xyz_id: String,
         price_rmi: Uint128,
         deposit_rmi_denom: String,
         timestamp: Timestamp,
         resources: Vec<Resource>,
+        price_resources: Vec<Resource>,
+        divisible: bool,
+        allowed_taker_xyz_ids: Vec<String>,
     ) -> Result<Listing, ContractError> {
         let config = CONFIG.load(storage)?;
         let listing_id = self.next_pk(storage)?;
 
         let listing = Listing::try_new(
+            querier,
             config,
             listing_id,
             lister_xyz_id,
@@ -236,6 +529,9 @@ impl<'a> Listings<'a> {
             deposit_rmi_denom,
             timestamp,
             resources,
+            price_resources,
+            divisible,
+            allowed_taker_xyz_ids,
         )?;
 
         self.listings
@@ -257,6 +553,16 @@ impl<'a> Listings<'a> {
             .map_err(ContractError::Std)
     }
 
+    pub fn update_listing(
+        &self,
+        storage: &mut dyn Storage,
+        listing: &Listing,
+    ) -> Result<(), ContractError> {
+        self.listings
+            .save(storage, listing.listing_id.into(), listing)
+            .map_err(ContractError::Std)
+    }
+
     pub fn fetch_listing(&self, storage: &dyn Storage, listing_id: u64) -> StdResult<Listing> {
         self.listings.load(storage, listing_id.into())
     }
@@ -276,6 +582,58 @@ impl<'a> Listings<'a> {
         Ok(listing)
     }
 
+    /// Expired listings, soonest expired first. The scan walks the `expired_at` index up
+    /// to `timestamp`, so it never visits listings that haven't expired.
+    pub fn fetch_expired_listings(
+        &self,
+        storage: &dyn Storage,
+        timestamp: Timestamp,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Listing>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+            .min(MAX_PAGINATION_LIMIT) as usize;
+
+        // every listing with expired_at <= timestamp, soonest expired first
+        let end = Bound::exclusive(
+            (U64Key::from(timestamp.nanos() + 1), Vec::<u8>::new()).joined_key(),
+        );
+        self.listings
+            .idx
+            .expired_at
+            .range(storage, None, Some(end), Order::Ascending)
+            .map(|item| item.map(|(_, listing)| listing))
+            .take(limit)
+            .collect()
+    }
+
+    /// Re-saves up to `limit` listings after `start_after` so that indexes added since
+    /// they were stored are populated. Returns the ids of the re-saved listings.
+    pub fn reindex_listings(
+        &self,
+        storage: &mut dyn Storage,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<u64>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_REINDEX_LIMIT)
+            .min(MAX_REINDEX_LIMIT) as usize;
+        let start = start_after.map(|id| Bound::exclusive(U64Key::from(id)));
+        let listings = self
+            .listings
+            .range(storage, start, None, Order::Ascending)
+            .take(limit)
+            .map(|item| item.map(|(_, listing)| listing))
+            .collect::<StdResult<Vec<Listing>>>()?;
+
+        for listing in listings.iter() {
+            self.listings
+                .save(storage, listing.listing_id.into(), listing)?;
+        }
+
+        Ok(listings.iter().map(|listing| listing.listing_id).collect())
+    }
+
     pub fn fetch_listings(
         &self,
         storage: &dyn Storage,
@@ -283,25 +641,39 @@ impl<'a> Listings<'a> {
         lister_xyz_id: Option<String>,
         prices: Vec<Uint128>,
         resources: Vec<String>,
+        min_resource_amounts: Vec<Resource>,
+        states: Vec<ListingState>,
         include_inactive: bool,
+        sort: &ListingSort,
         ascending: bool,
         start_after: Option<u64>,
+        cursor: Option<ListingCursor>,
         limit: Option<u32>,
     ) -> StdResult<Vec<Listing>> {
         let limit = limit
             .unwrap_or(DEFAULT_PAGINATION_LIMIT)
             .min(MAX_PAGINATION_LIMIT) as usize;
-        let start_after = start_after.map(|s| Bound::exclusive(s.to_be_bytes()));
-        let (start, end, order) = if ascending {
-            (start_after, None, Order::Ascending)
-        } else {
-            (None, start_after, Order::Descending)
-        };
 
         let resource_set = HashSet::<String>::from_iter(resources.iter().cloned());
+        let lister_filter = match sort {
+            // the lister index already filters listings sorted by ID
+            ListingSort::ListingId => None,
+            _ => lister_xyz_id.clone(),
+        };
         let listing_filter = |listing: &Listing| -> bool {
-            // filter inactive listings if include_inactive is false
-            if !include_inactive && listing.state(timestamp) != ListingState::Active {
+            // filter listings not made by lister_xyz_id
+            if let Some(lister_xyz_id) = &lister_filter {
+                if &listing.lister_xyz_id != lister_xyz_id {
+                    return false;
+                }
+            }
+            // filter listings not in one of the given states, or inactive listings if
+            // no states are given and include_inactive is false
+            if states.len() > 0 {
+                if !states.contains(&listing.state(timestamp)) {
+                    return false;
+                }
+            } else if !include_inactive && listing.state(timestamp) != ListingState::Active {
                 return false;
             }
             // filter listings that don't have one of the given prices
@@ -321,17 +693,64 @@ impl<'a> Listings<'a> {
                     return false;
                 }
             }
+            // filter listings with less than the given amounts of resources
+            if !listing.has_min_resource_amounts(&min_resource_amounts) {
+                return false;
+            }
             return true;
         };
 
-        let range = if let Some(lister_xyz_id) = lister_xyz_id {
-            self.listings
-                .idx
-                .lister_xyz_id
-                .prefix(lister_xyz_id)
-                .range(storage, start, end, order)
+        let order = if ascending {
+            Order::Ascending
         } else {
-            self.listings.range(storage, start, end, order)
+            Order::Descending
+        };
+        let bounds = |after: Option<Bound>| if ascending { (after, None) } else { (None, after) };
+
+        let range = match sort {
+            ListingSort::ListingId => {
+                let start_after = cursor.map(|c| c.listing_id).or(start_after);
+                let (start, end) = bounds(start_after.map(|s| Bound::exclusive(s.to_be_bytes())));
+                if let Some(lister_xyz_id) = lister_xyz_id {
+                    self.listings
+                        .idx
+                        .lister_xyz_id
+                        .prefix(lister_xyz_id)
+                        .range(storage, start, end, order)
+                } else {
+                    self.listings.range(storage, start, end, order)
+                }
+            }
+            ListingSort::UnitPrice => {
+                let (start, end) = bounds(cursor.map(|c| {
+                    Bound::exclusive(
+                        (
+                            U128Key::from(c.sort_key.u128()),
+                            c.listing_id.to_be_bytes().to_vec(),
+                        )
+                            .joined_key(),
+                    )
+                }));
+                self.listings
+                    .idx
+                    .unit_price
+                    .range(storage, start, end, order)
+            }
+            ListingSort::ExpiredAt => {
+                let (start, end) = bounds(cursor.map(|c| {
+                    Bound::exclusive(
+                        (
+                            U64Key::from(c.sort_key.u128() as u64),
+                            c.listing_id.to_be_bytes().to_vec(),
+                        )
+                            .joined_key(),
+                    )
+                }));
+                self.listings
+                    .idx
+                    .expired_at
+                    .range(storage, start, end, order)
+            }
         };
 
         let result: StdResult<Vec<_>> = range
@@ -349,11 +768,937 @@ impl<'a> Listings<'a> {
 
 pub struct ListingIndexes<'a> {
     pub lister_xyz_id: MultiIndex<'a, (String, Vec<u8>), Listing>,
+    pub unit_price: MultiIndex<'a, (U128Key, Vec<u8>), Listing>,
+    pub expired_at: MultiIndex<'a, (U64Key, Vec<u8>), Listing>,
 }
 
 impl<'a> IndexList<Listing> for ListingIndexes<'a> {
     fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Listing>> + '_> {
-        let v: Vec<&dyn Index<Listing>> = vec![&self.lister_xyz_id];
+        let v: Vec<&dyn Index<Listing>> =
+            vec![&self.lister_xyz_id, &self.unit_price, &self.expired_at];
+        Box::new(v.into_iter())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Bid {
+    pub bid_id: u64,
+    pub bidder_xyz_id: String,
+    pub price_rmi: Uint128,
+    pub rmi_denom: String,
+    pub created_at: Timestamp,
+    pub expired_at: Timestamp,
+    pub wanted_resources: Vec<Resource>,
+}
+
+impl Bid {
+    pub fn try_new(
+        querier: &QuerierWrapper,
+        config: Config,
+        bid_id: u64,
+        bidder_xyz_id: String,
+        price_rmi: Uint128,
+        rmi_denom: String,
+        timestamp: Timestamp,
+        wanted_resources: Vec<Resource>,
+    ) -> Result<Self, ContractError> {
+        // Validate the escrowed RMI denom
+        validate_rmi_denom(querier, &config, &rmi_denom)?;
+
+        // Bids use the same price tiers as listings so the two sides can be matched
+        if !config.allowed_listing_prices.contains(&price_rmi) {
+            return Err(ContractError::InvalidListingPrice {});
+        }
+
+        // Validate the wanted resource bundle
+        validate_resource_bundle(querier, &config, &wanted_resources)?;
+
+        Ok(Bid {
+            bid_id,
+            bidder_xyz_id,
+            price_rmi,
+            rmi_denom,
+            created_at: timestamp,
+            expired_at: timestamp.plus_seconds(config.listing_expiry_seconds),
+            wanted_resources,
+        })
+    }
+
+    pub fn is_active(&self, current_timestamp: Timestamp) -> bool {
+        self.expired_at > current_timestamp
+    }
+}
+
+pub struct Bids<'a> {
+    bids: IndexedMap<'a, U64Key, Bid, BidIndexes<'a>>,
+    bids_pk: Item<'a, u64>,
+}
+
+impl Default for Bids<'static> {
+    fn default() -> Self {
+        Self::new("bids", "bids__bidder", "bids_pk")
+    }
+}
+
+impl<'a> Bids<'a> {
+    pub fn new(bids_key: &'a str, bids_bidder_key: &'a str, bids_pk_key: &'a str) -> Self {
+        let bid_indexes = BidIndexes {
+            bidder_xyz_id: MultiIndex::new(
+                |o, k| (o.bidder_xyz_id.clone(), k),
+                bids_key,
+                bids_bidder_key,
+            ),
+        };
+
+        Bids {
+            bids: IndexedMap::new(bids_key, bid_indexes),
+            bids_pk: Item::new(bids_pk_key),
+        }
+    }
+
+    fn next_pk(&self, storage: &mut dyn Storage) -> Result<u64, ContractError> {
+        let pk = 1 + self.bids_pk.load(storage).unwrap_or(0);
+        self.bids_pk.save(storage, &pk)?;
+
+        Ok(pk)
+    }
+
+    pub fn save_bid(
+        &self,
+        storage: &mut dyn Storage,
+        querier: &QuerierWrapper,
+        bidder_xyz_id: String,
+        price_rmi: Uint128,
+        rmi_denom: String,
+        timestamp: Timestamp,
+        wanted_resources: Vec<Resource>,
+    ) -> Result<Bid, ContractError> {
+        let config = CONFIG.load(storage)?;
+        let bid_id = self.next_pk(storage)?;
+
+        let bid = Bid::try_new(
+            querier,
+            config,
+            bid_id,
+            bidder_xyz_id,
+            price_rmi,
+            rmi_denom,
+            timestamp,
+            wanted_resources,
+        )?;
+
+        self.bids
+            .update(storage, bid_id.into(), |old| match old {
+                Some(_) => Err(ContractError::StorageConflict {}),
+                None => Ok(bid.clone()),
+            })?;
+
+        Ok(bid)
+    }
+
+    pub fn remove_bid(&self, storage: &mut dyn Storage, bid_id: u64) -> Result<(), ContractError> {
+        self.bids
+            .remove(storage, bid_id.into())
+            .map_err(ContractError::Std)
+    }
+
+    pub fn fetch_bid(&self, storage: &dyn Storage, bid_id: u64) -> StdResult<Bid> {
+        self.bids.load(storage, bid_id.into())
+    }
+
+    pub fn fetch_active_bid(
+        &self,
+        storage: &dyn Storage,
+        bid_id: u64,
+        timestamp: Timestamp,
+    ) -> Result<Bid, ContractError> {
+        let bid = self.fetch_bid(storage, bid_id)?;
+
+        if !bid.is_active(timestamp) {
+            return Err(ContractError::InactiveBid {});
+        }
+
+        Ok(bid)
+    }
+
+    pub fn fetch_bids(
+        &self,
+        storage: &dyn Storage,
+        timestamp: Timestamp,
+        bidder_xyz_id: Option<String>,
+        prices: Vec<Uint128>,
+        resources: Vec<String>,
+        include_inactive: bool,
+        ascending: bool,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Bid>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+            .min(MAX_PAGINATION_LIMIT) as usize;
+        let start_after = start_after.map(|s| Bound::exclusive(s.to_be_bytes()));
+        let (start, end, order) = if ascending {
+            (start_after, None, Order::Ascending)
+        } else {
+            (None, start_after, Order::Descending)
+        };
+
+        let resource_set = HashSet::<String>::from_iter(resources.iter().cloned());
+        let bid_filter = |bid: &Bid| -> bool {
+            // filter expired bids if include_inactive is false
+            if !include_inactive && !bid.is_active(timestamp) {
+                return false;
+            }
+            // filter bids that don't have one of the given prices
+            if prices.len() > 0 && !prices.contains(&bid.price_rmi) {
+                return false;
+            }
+            // filter bids whose wanted bundles include all the given resources
+            if resource_set.len() > 0 {
+                let bid_resource_set = HashSet::<String>::from_iter(
+                    bid.wanted_resources
+                        .iter()
+                        .map(|r| r.id.clone())
+                        .collect::<Vec<String>>(),
+                );
+                if !resource_set.is_subset(&bid_resource_set) {
+                    return false;
+                }
+            }
+            return true;
+        };
+
+        let range = if let Some(bidder_xyz_id) = bidder_xyz_id {
+            self.bids
+                .idx
+                .bidder_xyz_id
+                .prefix(bidder_xyz_id)
+                .range(storage, start, end, order)
+        } else {
+            self.bids.range(storage, start, end, order)
+        };
+
+        let result: StdResult<Vec<_>> = range
+            .map(|item| item.map(|(_, bid)| bid))
+            .filter(|bid| match bid {
+                Err(_) => false,
+                Ok(bid) => bid_filter(bid),
+            })
+            .take(limit)
+            .collect();
+
+        Ok(result?)
+    }
+}
+
+pub struct BidIndexes<'a> {
+    pub bidder_xyz_id: MultiIndex<'a, (String, Vec<u8>), Bid>,
+}
+
+impl<'a> IndexList<Bid> for BidIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Bid>> + '_> {
+        let v: Vec<&dyn Index<Bid>> = vec![&self.bidder_xyz_id];
+        Box::new(v.into_iter())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AuctionKind {
+    /// Ascending bids, each escrowing its payment, settled after `expired_at`
+    English,
+    /// Price decays linearly from `start_price_rmi` at `created_at` to
+    /// `end_price_rmi` at `expired_at`, and the first buyer takes the bundle
+    Dutch,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AuctionBid {
+    pub bidder_xyz_id: String,
+    pub amount_rmi: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Auction {
+    pub auction_id: u64,
+    pub seller_xyz_id: String,
+    pub kind: AuctionKind,
+    pub rmi_denom: String,
+    pub start_price_rmi: Uint128,
+    pub end_price_rmi: Uint128,
+    pub created_at: Timestamp,
+    pub expired_at: Timestamp,
+    pub resources: Vec<Resource>,
+    pub highest_bid: Option<AuctionBid>,
+}
+
+impl Auction {
+    pub fn try_new(
+        querier: &QuerierWrapper,
+        config: Config,
+        auction_id: u64,
+        seller_xyz_id: String,
+        kind: AuctionKind,
+        rmi_denom: String,
+        start_price_rmi: Uint128,
+        end_price_rmi: Uint128,
+        timestamp: Timestamp,
+        resources: Vec<Resource>,
+    ) -> Result<Self, ContractError> {
+        // Validate the payment RMI denom
+        validate_rmi_denom(querier, &config, &rmi_denom)?;
+
+        // English auctions start at a non-zero reserve, Dutch auctions decay towards a floor
+        let end_price_rmi = match kind {
+            AuctionKind::English => Uint128::zero(),
+            AuctionKind::Dutch => end_price_rmi,
+        };
+        if start_price_rmi.is_zero() || end_price_rmi > start_price_rmi {
+            return Err(ContractError::InvalidAuctionPrice {});
+        }
+
+        // Validate the auctioned resource bundle
+        validate_resource_bundle(querier, &config, &resources)?;
+
+        Ok(Auction {
+            auction_id,
+            seller_xyz_id,
+            kind,
+            rmi_denom,
+            start_price_rmi,
+            end_price_rmi,
+            created_at: timestamp,
+            expired_at: timestamp.plus_seconds(config.listing_expiry_seconds),
+            resources,
+            highest_bid: None,
+        })
+    }
+
+    pub fn is_active(&self, current_timestamp: Timestamp) -> bool {
+        self.expired_at > current_timestamp
+    }
+
+    /// Current Dutch auction price, decaying linearly between `created_at` and `expired_at`
+    pub fn current_price_rmi(&self, current_timestamp: Timestamp) -> Uint128 {
+        let duration = self.expired_at.nanos() - self.created_at.nanos();
+        let elapsed = current_timestamp
+            .nanos()
+            .saturating_sub(self.created_at.nanos())
+            .min(duration);
+        let decay = (self.start_price_rmi - self.end_price_rmi).multiply_ratio(elapsed, duration);
+
+        self.start_price_rmi - decay
+    }
+
+    /// Smallest amount an English auction bid must offer
+    pub fn min_bid_rmi(&self) -> Uint128 {
+        match &self.highest_bid {
+            Some(bid) => bid.amount_rmi + Uint128::new(1),
+            None => self.start_price_rmi,
+        }
+    }
+}
+
+pub struct Auctions<'a> {
+    auctions: IndexedMap<'a, U64Key, Auction, AuctionIndexes<'a>>,
+    auctions_pk: Item<'a, u64>,
+}
+
+impl Default for Auctions<'static> {
+    fn default() -> Self {
+        Self::new("auctions", "auctions__seller", "auctions_pk")
+    }
+}
+
+impl<'a> Auctions<'a> {
+    pub fn new(
+        auctions_key: &'a str,
+        auctions_seller_key: &'a str,
+        auctions_pk_key: &'a str,
+    ) -> Self {
+        let auction_indexes = AuctionIndexes {
+            seller_xyz_id: MultiIndex::new(
+                |o, k| (o.seller_xyz_id.clone(), k),
+                auctions_key,
+                auctions_seller_key,
+            ),
+        };
+
+        Auctions {
+            auctions: IndexedMap::new(auctions_key, auction_indexes),
+            auctions_pk: Item::new(auctions_pk_key),
+        }
+    }
+
+    fn next_pk(&self, storage: &mut dyn Storage) -> Result<u64, ContractError> {
+        let pk = 1 + self.auctions_pk.load(storage).unwrap_or(0);
+        self.auctions_pk.save(storage, &pk)?;
+
+        Ok(pk)
+    }
+
+    pub fn save_auction(
+        &self,
+        storage: &mut dyn Storage,
+        querier: &QuerierWrapper,
+        seller_xyz_id: String,
+        kind: AuctionKind,
+        rmi_denom: String,
+        start_price_rmi: Uint128,
+        end_price_rmi: Uint128,
+        timestamp: Timestamp,
+        resources: Vec<Resource>,
+    ) -> Result<Auction, ContractError> {
+        let config = CONFIG.load(storage)?;
+        let auction_id = self.next_pk(storage)?;
+
+        let auction = Auction::try_new(
+            querier,
+            config,
+            auction_id,
+            seller_xyz_id,
+            kind,
+            rmi_denom,
+            start_price_rmi,
+            end_price_rmi,
+            timestamp,
+            resources,
+        )?;
+
+        self.auctions
+            .update(storage, auction_id.into(), |old| match old {
+                Some(_) => Err(ContractError::StorageConflict {}),
+                None => Ok(auction.clone()),
+            })?;
+
+        Ok(auction)
+    }
+
+    pub fn update_auction(
+        &self,
+        storage: &mut dyn Storage,
+        auction: &Auction,
+    ) -> Result<(), ContractError> {
+        self.auctions
+            .save(storage, auction.auction_id.into(), auction)
+            .map_err(ContractError::Std)
+    }
+
+    pub fn remove_auction(
+        &self,
+        storage: &mut dyn Storage,
+        auction_id: u64,
+    ) -> Result<(), ContractError> {
+        self.auctions
+            .remove(storage, auction_id.into())
+            .map_err(ContractError::Std)
+    }
+
+    pub fn fetch_auction(&self, storage: &dyn Storage, auction_id: u64) -> StdResult<Auction> {
+        self.auctions.load(storage, auction_id.into())
+    }
+
+    pub fn fetch_auctions(
+        &self,
+        storage: &dyn Storage,
+        timestamp: Timestamp,
+        seller_xyz_id: Option<String>,
+        include_inactive: bool,
+        ascending: bool,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Auction>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+            .min(MAX_PAGINATION_LIMIT) as usize;
+        let start_after = start_after.map(|s| Bound::exclusive(s.to_be_bytes()));
+        let (start, end, order) = if ascending {
+            (start_after, None, Order::Ascending)
+        } else {
+            (None, start_after, Order::Descending)
+        };
+
+        let range = if let Some(seller_xyz_id) = seller_xyz_id {
+            self.auctions
+                .idx
+                .seller_xyz_id
+                .prefix(seller_xyz_id)
+                .range(storage, start, end, order)
+        } else {
+            self.auctions.range(storage, start, end, order)
+        };
+
+        let result: StdResult<Vec<_>> = range
+            .map(|item| item.map(|(_, auction)| auction))
+            .filter(|auction| match auction {
+                Err(_) => false,
+                // filter ended auctions if include_inactive is false
+                Ok(auction) => include_inactive || auction.is_active(timestamp),
+            })
+            .take(limit)
+            .collect();
+
+        Ok(result?)
+    }
+}
+
+pub struct AuctionIndexes<'a> {
+    pub seller_xyz_id: MultiIndex<'a, (String, Vec<u8>), Auction>,
+}
+
+impl<'a> IndexList<Auction> for AuctionIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<Auction>> + '_> {
+        let v: Vec<&dyn Index<Auction>> = vec![&self.seller_xyz_id];
+        Box::new(v.into_iter())
+    }
+}
+
+/// A filled trade, recorded for price history. For bids and auctions the bidder is the
+/// buyer; for barter listings `price_rmi` is zero and the price is in `price_resources`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Trade {
+    pub trade_id: u64,
+    pub seller_xyz_id: String,
+    pub buyer_xyz_id: String,
+    pub resources: Vec<Resource>,
+    pub price_rmi: Uint128,
+    pub rmi_denom: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub price_resources: Vec<Resource>,
+    pub timestamp: Timestamp,
+}
+
+/// Trades of a single resource priced in one RMI denom during one bucket, for VWAP
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct VwapBucket {
+    pub trade_count: u64,
+    pub volume: Uint128,
+    pub value_rmi: Uint128,
+}
+
+/// All trades during one bucket, with their RMI volume per denom
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct VolumeBucket {
+    pub trade_count: u64,
+    pub volumes: Vec<Resource>,
+}
+
+pub struct Trades<'a> {
+    trades: Map<'a, U64Key, Trade>,
+    resource_trades: Map<'a, (&'a str, U64Key), Timestamp>,
+    trades_pk: Item<'a, u64>,
+    // mapped by (resource id, RMI denom, bucket)
+    vwap_buckets: Map<'a, (&'a str, &'a str, U64Key), VwapBucket>,
+    volume_buckets: Map<'a, U64Key, VolumeBucket>,
+}
+
+impl Default for Trades<'static> {
+    fn default() -> Self {
+        Self::new(
+            "trades",
+            "trades__resource",
+            "trades_pk",
+            "trades__vwap",
+            "trades__volume",
+        )
+    }
+}
+
+impl<'a> Trades<'a> {
+    pub fn new(
+        trades_key: &'a str,
+        trades_resource_key: &'a str,
+        trades_pk_key: &'a str,
+        trades_vwap_key: &'a str,
+        trades_volume_key: &'a str,
+    ) -> Self {
+        Trades {
+            trades: Map::new(trades_key),
+            resource_trades: Map::new(trades_resource_key),
+            trades_pk: Item::new(trades_pk_key),
+            vwap_buckets: Map::new(trades_vwap_key),
+            volume_buckets: Map::new(trades_volume_key),
+        }
+    }
+
+    fn bucket(timestamp: Timestamp) -> u64 {
+        timestamp.seconds() / TRADE_BUCKET_SECONDS
+    }
+
+    /// Adds the trade to the running volume and, if it trades a single resource for
+    /// RMI, VWAP buckets
+    fn add_to_buckets(&self, storage: &mut dyn Storage, trade: &Trade) -> StdResult<()> {
+        let bucket = Self::bucket(trade.timestamp);
+
+        self.volume_buckets.update(storage, bucket.into(), |volume| -> StdResult<_> {
+            let mut volume = volume.unwrap_or_default();
+            volume.trade_count += 1;
+            if !trade.price_rmi.is_zero() {
+                match volume.volumes.iter_mut().find(|r| r.id == trade.rmi_denom) {
+                    Some(resource) => resource.amount += trade.price_rmi,
+                    None => volume.volumes.push(Resource {
+                        id: trade.rmi_denom.clone(),
+                        amount: trade.price_rmi,
+                    }),
+                }
+            }
+            Ok(volume)
+        })?;
+
+        if trade.resources.len() == 1 && !trade.price_rmi.is_zero() {
+            let key = (
+                trade.resources[0].id.as_str(),
+                trade.rmi_denom.as_str(),
+                bucket.into(),
+            );
+            self.vwap_buckets.update(storage, key, |vwap| -> StdResult<_> {
+                let mut vwap = vwap.unwrap_or_default();
+                vwap.trade_count += 1;
+                vwap.volume += trade.resources[0].amount;
+                vwap.value_rmi += trade.price_rmi;
+                Ok(vwap)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn next_pk(&self, storage: &mut dyn Storage) -> Result<u64, ContractError> {
+        let pk = 1 + self.trades_pk.load(storage).unwrap_or(0);
+        self.trades_pk.save(storage, &pk)?;
+
+        Ok(pk)
+    }
+
+    pub fn save_trade(
+        &self,
+        storage: &mut dyn Storage,
+        seller_xyz_id: String,
+        buyer_xyz_id: String,
+        resources: Vec<Resource>,
+        price_rmi: Uint128,
+        rmi_denom: String,
+        price_resources: Vec<Resource>,
+        timestamp: Timestamp,
+    ) -> Result<Trade, ContractError> {
+        let trade_id = self.next_pk(storage)?;
+        let trade = Trade {
+            trade_id,
+            seller_xyz_id,
+            buyer_xyz_id,
+            resources,
+            price_rmi,
+            rmi_denom,
+            price_resources,
+            timestamp,
+        };
+
+        self.trades.save(storage, trade_id.into(), &trade)?;
+        for resource in trade.resources.iter() {
+            self.resource_trades
+                .save(storage, (resource.id.as_str(), trade_id.into()), &timestamp)?;
+        }
+        self.add_to_buckets(storage, &trade)?;
+
+        Ok(trade)
+    }
+
+    /// Most recent trades including `resource_id`, newest first
+    pub fn fetch_recent_trades(
+        &self,
+        storage: &dyn Storage,
+        resource_id: &str,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<Trade>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+            .min(MAX_PAGINATION_LIMIT) as usize;
+        let end = start_after.map(|s| Bound::exclusive(s.to_be_bytes()));
+
+        self.resource_trades
+            .prefix(resource_id)
+            .range(storage, None, end, Order::Descending)
+            .take(limit)
+            .map(|item| {
+                let (trade_id, _) = item?;
+                self.trades.load(storage, U64Key::from(trade_id))
+            })
+            .collect()
+    }
+
+    /// Checks the window and returns the first bucket it covers. Windows are rounded
+    /// out to whole buckets.
+    fn window_start_bucket(now: Timestamp, window_seconds: u64) -> StdResult<u64> {
+        if window_seconds > MAX_TRADE_WINDOW_SECONDS {
+            return Err(StdError::generic_err(format!(
+                "window_seconds can be at most {}",
+                MAX_TRADE_WINDOW_SECONDS
+            )));
+        }
+        Ok(Self::bucket(Timestamp::from_seconds(
+            now.seconds().saturating_sub(window_seconds),
+        )))
+    }
+
+    /// Sum of the VWAP buckets for `resource_id` priced in `rmi_denom` over the window
+    pub fn vwap_since(
+        &self,
+        storage: &dyn Storage,
+        resource_id: &str,
+        rmi_denom: &str,
+        now: Timestamp,
+        window_seconds: u64,
+    ) -> StdResult<VwapBucket> {
+        let start = Bound::inclusive(U64Key::from(Self::window_start_bucket(now, window_seconds)?));
+        self.vwap_buckets
+            .prefix((resource_id, rmi_denom))
+            .range(storage, Some(start), None, Order::Ascending)
+            .try_fold(VwapBucket::default(), |mut total, item| {
+                let (_, vwap) = item?;
+                total.trade_count += vwap.trade_count;
+                total.volume += vwap.volume;
+                total.value_rmi += vwap.value_rmi;
+                Ok(total)
+            })
+    }
+
+    /// Sum of the volume buckets over the window
+    pub fn volume_since(
+        &self,
+        storage: &dyn Storage,
+        now: Timestamp,
+        window_seconds: u64,
+    ) -> StdResult<VolumeBucket> {
+        let start = Bound::inclusive(U64Key::from(Self::window_start_bucket(now, window_seconds)?));
+        self.volume_buckets
+            .range(storage, Some(start), None, Order::Ascending)
+            .try_fold(VolumeBucket::default(), |mut total, item| {
+                let (_, volume) = item?;
+                total.trade_count += volume.trade_count;
+                for resource in volume.volumes {
+                    match total.volumes.iter_mut().find(|r| r.id == resource.id) {
+                        Some(total_resource) => total_resource.amount += resource.amount,
+                        None => total.volumes.push(resource),
+                    }
+                }
+                Ok(total)
+            })
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NftPrice {
+    /// Paid in native coin sent with `BuyNft`
+    Native(Coin),
+    /// Paid in an RMI denom, burned from the buyer's xyz and minted to `payout_xyz_id`
+    Rmi { amount: Uint128, denom: String },
+}
+
+/// An xyz NFT escrowed by the marketplace for sale, optionally with a resource bundle
+/// that is minted to the sold xyz when it is bought
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NftListing {
+    pub nft_listing_id: u64,
+    /// Numeric xyz token ID, as used by the xyz NFT contract's `TransferNft`
+    pub token_id: String,
+    pub seller: Addr,
+    pub price: NftPrice,
+    pub payout_xyz_id: Option<String>,
+    pub bundle_xyz_id: Option<String>,
+    pub resources: Vec<Resource>,
+    pub created_at: Timestamp,
+}
+
+impl NftListing {
+    pub fn try_new(
+        querier: &QuerierWrapper,
+        config: Config,
+        nft_listing_id: u64,
+        token_id: String,
+        seller: Addr,
+        price: NftPrice,
+        payout_xyz_id: Option<String>,
+        bundle_xyz_id: Option<String>,
+        resources: Vec<Resource>,
+        timestamp: Timestamp,
+    ) -> Result<Self, ContractError> {
+        // Validate the price, RMI prices need an xyz to pay out to
+        match &price {
+            NftPrice::Native(coin) => {
+                if coin.amount.is_zero() {
+                    return Err(ContractError::InvalidListingPrice {});
+                }
+            }
+            NftPrice::Rmi { amount, denom } => {
+                validate_rmi_denom(querier, &config, denom)?;
+                if amount.is_zero() || payout_xyz_id.is_none() {
+                    return Err(ContractError::InvalidListingPrice {});
+                }
+            }
+        }
+
+        // Validate the attached resource bundle, if any
+        if bundle_xyz_id.is_some() {
+            validate_resource_bundle(querier, &config, &resources)?;
+        } else if !resources.is_empty() {
+            return Err(ContractError::InvalidResourceBundle {});
+        }
+
+        Ok(NftListing {
+            nft_listing_id,
+            token_id,
+            seller,
+            price,
+            payout_xyz_id,
+            bundle_xyz_id,
+            resources,
+            created_at: timestamp,
+        })
+    }
+
+    /// Full xyz ID of the listed NFT, as used for resource balances
+    pub fn xyz_id(&self) -> String {
+        format!("xyz #{}", self.token_id)
+    }
+}
+
+pub struct NftListings<'a> {
+    nft_listings: IndexedMap<'a, U64Key, NftListing, NftListingIndexes<'a>>,
+    nft_listings_pk: Item<'a, u64>,
+}
+
+impl Default for NftListings<'static> {
+    fn default() -> Self {
+        Self::new("nft_listings", "nft_listings__seller", "nft_listings_pk")
+    }
+}
+
+impl<'a> NftListings<'a> {
+    pub fn new(
+        nft_listings_key: &'a str,
+        nft_listings_seller_key: &'a str,
+        nft_listings_pk_key: &'a str,
+    ) -> Self {
+        let nft_listing_indexes = NftListingIndexes {
+            seller: MultiIndex::new(
+                |o, k| (o.seller.to_string(), k),
+                nft_listings_key,
+                nft_listings_seller_key,
+            ),
+        };
+
+        NftListings {
+            nft_listings: IndexedMap::new(nft_listings_key, nft_listing_indexes),
+            nft_listings_pk: Item::new(nft_listings_pk_key),
+        }
+    }
+
+    fn next_pk(&self, storage: &mut dyn Storage) -> Result<u64, ContractError> {
+        let pk = 1 + self.nft_listings_pk.load(storage).unwrap_or(0);
+        self.nft_listings_pk.save(storage, &pk)?;
+
+        Ok(pk)
+    }
+
+    pub fn save_nft_listing(
+        &self,
+        storage: &mut dyn Storage,
+        querier: &QuerierWrapper,
+        token_id: String,
+        seller: Addr,
+        price: NftPrice,
+        payout_xyz_id: Option<String>,
+        bundle_xyz_id: Option<String>,
+        resources: Vec<Resource>,
+        timestamp: Timestamp,
+    ) -> Result<NftListing, ContractError> {
+        let config = CONFIG.load(storage)?;
+        let nft_listing_id = self.next_pk(storage)?;
+
+        let nft_listing = NftListing::try_new(
+            querier,
+            config,
+            nft_listing_id,
+            token_id,
+            seller,
+            price,
+            payout_xyz_id,
+            bundle_xyz_id,
+            resources,
+            timestamp,
+        )?;
+
+        self.nft_listings
+            .update(storage, nft_listing_id.into(), |old| match old {
+                Some(_) => Err(ContractError::StorageConflict {}),
+                None => Ok(nft_listing.clone()),
+            })?;
+
+        Ok(nft_listing)
+    }
+
+    pub fn remove_nft_listing(
+        &self,
+        storage: &mut dyn Storage,
+        nft_listing_id: u64,
+    ) -> Result<(), ContractError> {
+        self.nft_listings
+            .remove(storage, nft_listing_id.into())
+            .map_err(ContractError::Std)
+    }
+
+    pub fn fetch_nft_listing(
+        &self,
+        storage: &dyn Storage,
+        nft_listing_id: u64,
+    ) -> StdResult<NftListing> {
+        self.nft_listings.load(storage, nft_listing_id.into())
+    }
+
+    pub fn fetch_nft_listings(
+        &self,
+        storage: &dyn Storage,
+        seller: Option<Addr>,
+        ascending: bool,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<NftListing>> {
+        let limit = limit
+            .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+            .min(MAX_PAGINATION_LIMIT) as usize;
+        let start_after = start_after.map(|s| Bound::exclusive(s.to_be_bytes()));
+        let (start, end, order) = if ascending {
+            (start_after, None, Order::Ascending)
+        } else {
+            (None, start_after, Order::Descending)
+        };
+
+        let range = if let Some(seller) = seller {
+            self.nft_listings
+                .idx
+                .seller
+                .prefix(seller.to_string())
+                .range(storage, start, end, order)
+        } else {
+            self.nft_listings.range(storage, start, end, order)
+        };
+
+        range
+            .take(limit)
+            .map(|item| item.map(|(_, nft_listing)| nft_listing))
+            .collect()
+    }
+}
+
+pub struct NftListingIndexes<'a> {
+    pub seller: MultiIndex<'a, (String, Vec<u8>), NftListing>,
+}
+
+impl<'a> IndexList<NftListing> for NftListingIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<NftListing>> + '_> {
+        let v: Vec<&dyn Index<NftListing>> = vec![&self.seller];
         Box::new(v.into_iter())
     }
 }
@@ -37,6 +37,7 @@ fn mock_resource_config() -> ResourceConfig {
         water_contract: Addr::unchecked("trwater_contract"),
         gem_contract: Addr::unchecked("trgem_contract"),
         life_contract: Addr::unchecked("trlife_contract"),
+        resource_registry: None,
     }
 }
 
@@ -387,7 +388,7 @@ fn test_complete_objective() {
                     .map(|resource| {
                         let contract_addr = resource_config
                             .clone()
-                            .resource_addr(&resource.resource_id)
+                            .resource_addr(&deps.as_ref().querier, &resource.resource_id)
                             .unwrap();
                         burn_resource(
                             owner.to_string(),
@@ -440,6 +441,35 @@ fn test_complete_objective() {
     }
 }
 
+#[test]
+fn test_resource_addr_with_resource_registry() {
+    let deps = mock_dependencies_custom(initial_xyz_balances(), &[]);
+    let resource_config = ResourceConfig {
+        resource_registry: Some(Addr::unchecked("resource-registry")),
+        ..mock_resource_config()
+    };
+
+    // registered resources resolve to the registry's contract, even hardcoded ones
+    assert_eq!(
+        resource_config
+            .resource_addr(&deps.as_ref().querier, "xyzICE")
+            .unwrap(),
+        Addr::unchecked("ice-contract")
+    );
+    assert_eq!(
+        resource_config
+            .resource_addr(&deps.as_ref().querier, "xyzSAND")
+            .unwrap(),
+        Addr::unchecked("sand-contract")
+    );
+
+    // resources missing from the registry are rejected
+    let err = resource_config
+        .resource_addr(&deps.as_ref().querier, "xyzROCK")
+        .unwrap_err();
+    assert_eq!(err, ContractError::InvalidResourceId("xyzROCK".to_string()));
+}
+
 #[test]
 fn test_prize_pool_deposit() {
     let mut deps = mock_dependencies(&[]);
@@ -64,7 +64,7 @@ pub fn execute_complete_objective(
 
     // Fail if user does not have enough resources to complete the objective
     // if these messages fail, we will fail to be able to complete this objective for the user
-    let messages = objective.attempt_to_complete(config, owner, &xyz_id)?;
+    let messages = objective.attempt_to_complete(&deps.querier, config, owner, &xyz_id)?;
 
     // Save completed objective for xyz_id.
     // This will only save if the transaction succeeds.
@@ -1,6 +1,8 @@
 use collectxyz_planet_metaverse::{experience::XyzExperienceMintInfo, util::burn_resource};
+use collectxyz_resource_registry_contract::ResourceRegistry;
 use cosmwasm_std::{
-    Addr, BlockInfo, Coin, StdResult, Storage, Timestamp, Uint128, Uint64, WasmMsg,
+    Addr, BlockInfo, Coin, QuerierWrapper, StdResult, Storage, Timestamp, Uint128, Uint64,
+    WasmMsg,
 };
 use cw_storage_plus::{Item, Map};
 use fixed::types::U64F64;
@@ -118,10 +120,24 @@ pub struct ResourceConfig {
     pub water_contract: Addr,
     pub gem_contract: Addr,
     pub life_contract: Addr,
+    /// When set, resources are resolved against this registry instead of the contracts above
+    #[serde(default)]
+    pub resource_registry: Option<Addr>,
 }
 
 impl ResourceConfig {
-    pub fn resource_addr(&self, resource_id: &str) -> Result<Addr, ContractError> {
+    pub fn resource_addr(
+        &self,
+        querier: &QuerierWrapper,
+        resource_id: &str,
+    ) -> Result<Addr, ContractError> {
+        if let Some(registry) = &self.resource_registry {
+            return ResourceRegistry(registry.clone())
+                .resource(querier, resource_id)?
+                .map(|resource| resource.contract_address)
+                .ok_or_else(|| ContractError::InvalidResourceId(resource_id.to_string()));
+        }
+
         let addr = match resource_id {
             "xyzROCK" => self.rock_contract.clone(),
             "xyzMETAL" => self.metal_contract.clone(),
@@ -252,6 +268,7 @@ impl Objective {
 
     pub fn attempt_to_complete(
         &self,
+        querier: &QuerierWrapper,
         config: Config,
         owner: &String,
         owner_xyz_id: &String,
@@ -268,7 +285,7 @@ impl Objective {
                 owner_xyz_id.to_string(),
                 config
                     .resource_configs
-                    .resource_addr(&required_resource.resource_id)?
+                    .resource_addr(querier, &required_resource.resource_id)?
                     .to_string(),
                 required_resource
                     .required_amount
@@ -0,0 +1,22 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use collectxyz_resource_registry_contract::msg::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, ResourceEntry, ResourceResponse, ResourcesResponse,
+};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(ResourceEntry), &out_dir);
+    export_schema(&schema_for!(ResourceResponse), &out_dir);
+    export_schema(&schema_for!(ResourcesResponse), &out_dir);
+}
@@ -0,0 +1,82 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw2::{get_contract_version, set_contract_version};
+
+use crate::error::ContractError;
+use crate::execute as ExecHandler;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use crate::query as QueryHandler;
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:collectxyz-resource-registry-contract";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    ExecHandler::execute_instantiate(deps, env, info, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::SetResource { resource } => {
+            ExecHandler::execute_set_resource(deps, env, info, resource)
+        }
+        ExecuteMsg::RemoveResource { resource_id } => {
+            ExecHandler::execute_remove_resource(deps, env, info, resource_id)
+        }
+        ExecuteMsg::UpdateOwner { owner } => {
+            ExecHandler::execute_update_owner(deps, env, info, owner)
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
+    let version = get_contract_version(deps.storage)?;
+    if version.contract != CONTRACT_NAME {
+        return Err(StdError::generic_err(
+            "can't migrate to contract with different name",
+        ));
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    ExecHandler::execute_migrate(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Resource { resource_id } => {
+            to_binary(&QueryHandler::query_resource(deps, env, resource_id)?)
+        }
+        QueryMsg::Resources {
+            rmi_denoms_only,
+            start_after,
+            limit,
+        } => to_binary(&QueryHandler::query_resources(
+            deps,
+            env,
+            rmi_denoms_only,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::Owner {} => to_binary(&QueryHandler::query_owner(deps, env)?),
+    }
+}
@@ -0,0 +1,173 @@
+use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
+use cosmwasm_std::{from_binary, Addr, DepsMut};
+
+use crate::contract;
+use crate::error::ContractError;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, QueryMsg, ResourceEntry, ResourceResponse, ResourcesResponse,
+};
+
+const OWNER: &str = "owner";
+const NONOWNER: &str = "nonowner";
+
+fn entry(id: &str, is_rmi_denom: bool) -> ResourceEntry {
+    ResourceEntry {
+        id: id.to_string(),
+        contract_address: Addr::unchecked(format!("{}-contract", id)),
+        is_rmi_denom,
+        decimals: 6,
+        display_name: id.trim_start_matches("xyz").to_string(),
+    }
+}
+
+fn setup_contract(deps: DepsMut) {
+    let msg = InstantiateMsg {
+        resources: vec![
+            entry("xyzROCK", true),
+            entry("xyzICE", true),
+            entry("xyzGAS", false),
+        ],
+    };
+    let res = contract::instantiate(deps, mock_env(), mock_info(OWNER, &[]), msg).unwrap();
+    assert_eq!(res.messages.len(), 0);
+}
+
+fn query_resources(deps: cosmwasm_std::Deps, rmi_denoms_only: bool) -> Vec<String> {
+    let res: ResourcesResponse = from_binary(
+        &contract::query(
+            deps,
+            mock_env(),
+            QueryMsg::Resources {
+                rmi_denoms_only: Some(rmi_denoms_only),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    res.resources.into_iter().map(|r| r.id).collect()
+}
+
+#[test]
+fn test_query_resources() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut());
+
+    let res: ResourceResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Resource {
+                resource_id: "xyzICE".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.resource, Some(entry("xyzICE", true)));
+
+    let res: ResourceResponse = from_binary(
+        &contract::query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Resource {
+                resource_id: "xyzSAND".to_string(),
+            },
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(res.resource, None);
+
+    assert_eq!(
+        query_resources(deps.as_ref(), false),
+        vec!["xyzGAS", "xyzICE", "xyzROCK"]
+    );
+    assert_eq!(query_resources(deps.as_ref(), true), vec!["xyzICE", "xyzROCK"]);
+}
+
+#[test]
+fn test_set_and_remove_resource() {
+    let mut deps = mock_dependencies(&[]);
+    setup_contract(deps.as_mut());
+
+    // only the owner can change the registry
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(NONOWNER, &[]),
+        ExecuteMsg::SetResource {
+            resource: entry("xyzSAND", false),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::Unauthorized {});
+
+    // the resource's contract address must be valid
+    let mut invalid = entry("xyzSAND", false);
+    invalid.contract_address = Addr::unchecked("x");
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetResource { resource: invalid },
+    )
+    .unwrap_err();
+    assert!(matches!(err, ContractError::Std(_)));
+
+    // adding a resource is a config change
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetResource {
+            resource: entry("xyzSAND", false),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_resources(deps.as_ref(), false),
+        vec!["xyzGAS", "xyzICE", "xyzROCK", "xyzSAND"]
+    );
+
+    // replacing an entry updates it in place
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::SetResource {
+            resource: entry("xyzGAS", true),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_resources(deps.as_ref(), true),
+        vec!["xyzGAS", "xyzICE", "xyzROCK"]
+    );
+
+    contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RemoveResource {
+            resource_id: "xyzROCK".to_string(),
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        query_resources(deps.as_ref(), false),
+        vec!["xyzGAS", "xyzICE", "xyzSAND"]
+    );
+
+    let err = contract::execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(OWNER, &[]),
+        ExecuteMsg::RemoveResource {
+            resource_id: "xyzROCK".to_string(),
+        },
+    )
+    .unwrap_err();
+    assert_eq!(err, ContractError::InvalidResourceId("xyzROCK".to_string()));
+}
@@ -0,0 +1,17 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid Resource Id: {0}")]
+    InvalidResourceId(String),
+
+    #[error("Invalid Decimals: {0}")]
+    InvalidDecimals(u8),
+}
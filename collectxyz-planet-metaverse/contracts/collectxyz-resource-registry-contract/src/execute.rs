@@ -0,0 +1,94 @@
+use cosmwasm_std::{Addr, Api, DepsMut, Env, MessageInfo, Response, StdResult};
+
+use crate::error::ContractError;
+use crate::msg::{InstantiateMsg, MigrateMsg, ResourceEntry};
+use crate::state::{OWNER, RESOURCES};
+
+fn validate_resource(api: &dyn Api, resource: &ResourceEntry) -> Result<(), ContractError> {
+    if resource.id.is_empty() {
+        return Err(ContractError::InvalidResourceId(resource.id.clone()));
+    }
+    api.addr_validate(resource.contract_address.as_str())?;
+    if resource.decimals > 18 {
+        return Err(ContractError::InvalidDecimals(resource.decimals));
+    }
+    Ok(())
+}
+
+fn check_owner(deps: &DepsMut, info: &MessageInfo) -> Result<(), ContractError> {
+    if info.sender != OWNER.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn execute_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    OWNER.save(deps.storage, &info.sender)?;
+    for resource in msg.resources.iter() {
+        validate_resource(deps.api, resource)?;
+        RESOURCES.save(deps.storage, &resource.id, resource)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("method", "instantiate")
+        .add_attribute("owner", info.sender))
+}
+
+pub fn execute_set_resource(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    resource: ResourceEntry,
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    validate_resource(deps.api, &resource)?;
+    RESOURCES.save(deps.storage, &resource.id, &resource)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute")
+        .add_attribute("action", "set_resource")
+        .add_attribute("resource_id", resource.id))
+}
+
+pub fn execute_remove_resource(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    resource_id: String,
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    if !RESOURCES.has(deps.storage, &resource_id) {
+        return Err(ContractError::InvalidResourceId(resource_id));
+    }
+    RESOURCES.remove(deps.storage, &resource_id);
+
+    Ok(Response::new()
+        .add_attribute("method", "execute")
+        .add_attribute("action", "remove_resource")
+        .add_attribute("resource_id", resource_id))
+}
+
+pub fn execute_update_owner(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    owner: Addr,
+) -> Result<Response, ContractError> {
+    check_owner(&deps, &info)?;
+    let owner = deps.api.addr_validate(owner.as_str())?;
+    OWNER.save(deps.storage, &owner)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "execute")
+        .add_attribute("action", "update_owner")
+        .add_attribute("owner", owner))
+}
+
+pub fn execute_migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("method", "migrate"))
+}
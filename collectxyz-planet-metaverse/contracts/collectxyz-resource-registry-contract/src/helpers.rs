@@ -0,0 +1,50 @@
+use cosmwasm_std::{Addr, QuerierWrapper, StdResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::msg::{QueryMsg, ResourceEntry, ResourceResponse, ResourcesResponse};
+
+/// ResourceRegistry is a wrapper around the registry contract address that resolves
+/// resource metadata for contracts depending on it (build with the "library" feature).
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ResourceRegistry(pub Addr);
+
+impl ResourceRegistry {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    /// Returns the registry entry for resource_id, if registered
+    pub fn resource(
+        &self,
+        querier: &QuerierWrapper,
+        resource_id: &str,
+    ) -> StdResult<Option<ResourceEntry>> {
+        let res: ResourceResponse = querier.query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Resource {
+                resource_id: resource_id.to_string(),
+            },
+        )?;
+        Ok(res.resource)
+    }
+
+    /// Returns one page of registered resources, optionally restricted to RMI denoms
+    pub fn resources(
+        &self,
+        querier: &QuerierWrapper,
+        rmi_denoms_only: bool,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    ) -> StdResult<Vec<ResourceEntry>> {
+        let res: ResourcesResponse = querier.query_wasm_smart(
+            self.addr(),
+            &QueryMsg::Resources {
+                rmi_denoms_only: Some(rmi_denoms_only),
+                start_after,
+                limit,
+            },
+        )?;
+        Ok(res.resources)
+    }
+}
@@ -0,0 +1,13 @@
+pub mod contract;
+mod error;
+pub mod execute;
+pub mod helpers;
+pub mod msg;
+pub mod query;
+pub mod state;
+
+#[cfg(test)]
+pub mod contract_tests;
+
+pub use crate::error::ContractError;
+pub use crate::helpers::ResourceRegistry;
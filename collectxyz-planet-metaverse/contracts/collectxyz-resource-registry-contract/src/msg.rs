@@ -0,0 +1,72 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Addr;
+
+/// Metadata of a resource known to the registry
+///
+/// * `id` - Resource id, e.g. `xyzROCK`. Unique across all resources
+/// * `contract_address` - Contract address of the resource token
+/// * `is_rmi_denom` - Whether the resource can be used as an RMI (raw material index) denom
+/// * `decimals` - Decimals of the resource token
+/// * `display_name` - Human readable name of the resource
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ResourceEntry {
+    pub id: String,
+    pub contract_address: Addr,
+    pub is_rmi_denom: bool,
+    pub decimals: u8,
+    pub display_name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub resources: Vec<ResourceEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Add a resource to the registry, or replace the entry with the same id.
+    /// Requires that the caller is the owner.
+    SetResource { resource: ResourceEntry },
+    /// Remove a resource from the registry. Requires that the caller is the owner.
+    RemoveResource { resource_id: String },
+    /// Transfer ownership of the registry. Requires that the caller is the owner.
+    UpdateOwner { owner: Addr },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    /// Returns the entry for the given resource id, if registered.
+    /// Return type: ResourceResponse.
+    Resource { resource_id: String },
+    /// Returns registered resources ordered by id. Supports pagination.
+    /// Return type: ResourcesResponse.
+    Resources {
+        rmi_denoms_only: Option<bool>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the owner of the registry.
+    /// Return type: Addr.
+    Owner {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ResourceResponse {
+    pub resource: Option<ResourceEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ResourcesResponse {
+    pub resources: Vec<ResourceEntry>,
+}
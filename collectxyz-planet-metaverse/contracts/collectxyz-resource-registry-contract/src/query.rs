@@ -0,0 +1,29 @@
+use cosmwasm_std::{Addr, Deps, Env, StdResult};
+
+use crate::msg::{ResourceResponse, ResourcesResponse};
+use crate::state::{fetch_resources, OWNER, RESOURCES};
+
+pub fn query_resource(deps: Deps, _env: Env, resource_id: String) -> StdResult<ResourceResponse> {
+    let resource = RESOURCES.may_load(deps.storage, &resource_id)?;
+    Ok(ResourceResponse { resource })
+}
+
+pub fn query_resources(
+    deps: Deps,
+    _env: Env,
+    rmi_denoms_only: Option<bool>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ResourcesResponse> {
+    let resources = fetch_resources(
+        deps.storage,
+        rmi_denoms_only.unwrap_or(false),
+        start_after,
+        limit,
+    )?;
+    Ok(ResourcesResponse { resources })
+}
+
+pub fn query_owner(deps: Deps, _env: Env) -> StdResult<Addr> {
+    OWNER.load(deps.storage)
+}
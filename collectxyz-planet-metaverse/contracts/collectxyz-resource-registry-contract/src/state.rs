@@ -0,0 +1,34 @@
+use cosmwasm_std::{Addr, Order, StdResult, Storage};
+use cw_storage_plus::{Bound, Item, Map};
+
+use crate::msg::ResourceEntry;
+
+pub const OWNER: Item<Addr> = Item::new("owner");
+
+/// Registered resources, mapped by resource id
+pub const RESOURCES: Map<&str, ResourceEntry> = Map::new("resources");
+
+const DEFAULT_PAGINATION_LIMIT: u32 = 10;
+const MAX_PAGINATION_LIMIT: u32 = 30;
+
+pub fn fetch_resources(
+    storage: &dyn Storage,
+    rmi_denoms_only: bool,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<ResourceEntry>> {
+    let limit = limit
+        .unwrap_or(DEFAULT_PAGINATION_LIMIT)
+        .min(MAX_PAGINATION_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    RESOURCES
+        .range(storage, start, None, Order::Ascending)
+        .map(|item| item.map(|(_, resource)| resource))
+        .filter(|resource| match resource {
+            Err(_) => true,
+            Ok(resource) => !rmi_denoms_only || resource.is_rmi_denom,
+        })
+        .take(limit)
+        .collect()
+}
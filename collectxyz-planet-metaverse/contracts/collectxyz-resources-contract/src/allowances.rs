@@ -1,12 +1,18 @@
-use collectxyz_planet_metaverse::util::{validate_nft_is_owned_by_wallet};
+use collectxyz_planet_metaverse::util::{fetch_nft_data, validate_nft_is_owned_by_wallet};
 use cosmwasm_std::{
     attr, Addr, BlockInfo, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
     Storage, Uint128,
 };
 use collectxyz_resources::{AllowanceResponse, Expiration};
 
+use std::collections::BTreeMap;
+
+use crate::contract::move_balance;
 use crate::error::ContractError;
-use crate::state::{ALLOWANCES, BALANCES, TOKEN_INFO};
+use crate::msg::XyzAmount;
+use crate::state::{
+    balance_key, ALLOWANCES, BALANCES, TOKEN_INFO, TOTAL_SUPPLY_HISTORY, TOTAL_SUPPLY_KEY,
+};
 
 pub fn execute_increase_allowance(
     deps: DepsMut,
@@ -138,6 +144,62 @@ pub fn deduct_allowance(
     })
 }
 
+pub fn execute_transfer_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    owner_xyz_id: String,
+    to_xyz_id: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount == Uint128::zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let config = TOKEN_INFO.load(deps.storage)?;
+
+    // Validate owner_xyz_id is owned by owner
+    if !validate_nft_is_owned_by_wallet(
+        &owner_xyz_id,
+        &owner.to_string(),
+        &deps.querier,
+        &config.xyz_contract_address
+    )? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // validate that the recipient nft id is valid
+    let _ = fetch_nft_data(
+        &to_xyz_id,
+        &config.xyz_contract_address,
+        &deps.querier
+    )?;
+
+    // deduct allowance before doing anything else have enough allowance
+    deduct_allowance(deps.storage, &owner_addr, owner_xyz_id.to_string(), &info.sender, &env.block, amount)?;
+
+    move_balance(
+        deps.storage,
+        config.xyz_competition_season,
+        &owner_xyz_id,
+        &to_xyz_id,
+        amount,
+        env.block.height,
+    )?;
+
+    let res = Response::new().add_attributes(vec![
+        attr("action", "transfer_from"),
+        attr("from", owner),
+        attr("from_xyz_id", owner_xyz_id),
+        attr("to_xyz_id", to_xyz_id),
+        attr("by", info.sender),
+        attr("amount", amount),
+    ]);
+    Ok(res)
+}
+
 pub fn execute_burn_from(
     deps: DepsMut,
     env: Env,
@@ -165,16 +227,18 @@ pub fn execute_burn_from(
     // lower balance
     BALANCES.update(
         deps.storage,
-        &owner_xyz_id.to_string(),
+        balance_key(config.xyz_competition_season, &owner_xyz_id),
+        env.block.height,
         |balance: Option<Uint128>| -> StdResult<_> {
             Ok(balance.unwrap_or_default().checked_sub(amount)?)
         },
     )?;
     // reduce total_supply
-    TOKEN_INFO.update(deps.storage, |mut meta| -> StdResult<_> {
+    let meta = TOKEN_INFO.update(deps.storage, |mut meta| -> StdResult<_> {
         meta.total_supply = meta.total_supply.checked_sub(amount)?;
         Ok(meta)
     })?;
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &meta.total_supply, env.block.height)?;
 
     let res = Response::new().add_attributes(vec![
         attr("action", "burn_from"),
@@ -186,6 +250,70 @@ pub fn execute_burn_from(
     Ok(res)
 }
 
+pub fn execute_batch_burn_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    burns: Vec<XyzAmount>,
+) -> Result<Response, ContractError> {
+    if burns.is_empty() || burns.iter().any(|burn| burn.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let config = TOKEN_INFO.load(deps.storage)?;
+
+    // group the batch by xyz id so ownership and allowance are checked once per xyz
+    let mut totals: BTreeMap<String, Uint128> = BTreeMap::new();
+    for burn in burns {
+        let total = totals.entry(burn.xyz_id).or_default();
+        *total = total.checked_add(burn.amount).map_err(StdError::from)?;
+    }
+
+    let mut burned = Uint128::zero();
+    for (owner_xyz_id, amount) in totals.iter() {
+        // Validate owner_xyz_id is owned by owner
+        if !validate_nft_is_owned_by_wallet(
+            owner_xyz_id,
+            &owner,
+            &deps.querier,
+            &config.xyz_contract_address
+        )? {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        deduct_allowance(deps.storage, &owner_addr, owner_xyz_id.to_string(), &info.sender, &env.block, *amount)?;
+
+        // lower balance
+        BALANCES.update(
+            deps.storage,
+            balance_key(config.xyz_competition_season, owner_xyz_id),
+            env.block.height,
+            |balance: Option<Uint128>| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().checked_sub(*amount)?)
+            },
+        )?;
+        burned += *amount;
+    }
+
+    // reduce total_supply once for the whole batch
+    let meta = TOKEN_INFO.update(deps.storage, |mut meta| -> StdResult<_> {
+        meta.total_supply = meta.total_supply.checked_sub(burned)?;
+        Ok(meta)
+    })?;
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &meta.total_supply, env.block.height)?;
+
+    let res = Response::new().add_attributes(vec![
+        attr("action", "batch_burn_from"),
+        attr("from", owner),
+        attr("count", totals.len().to_string()),
+        attr("by", info.sender),
+        attr("amount", burned),
+    ]);
+    Ok(res)
+}
+
 pub fn query_allowance(deps: Deps, owner: String, owner_xyz_id: String, spender: String) -> StdResult<AllowanceResponse> {
     let owner_addr = deps.api.addr_validate(&owner)?;
     let spender_addr = deps.api.addr_validate(&spender)?;
@@ -200,12 +328,12 @@ mod tests {
     use super::*;
 
     use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, NFT_CONTRACT_ADDRESS, NFT_OWNER_ADDRESS, NOW, XYZ_NFT_ID, default_xyz_nft_data, mock_dependencies_custom};
-    use collectxyz_resources::{TokenInfoResponse,XyzPlanetResource, CollectXyzResourceExecuteMsg};
+    use collectxyz_resources::{TokenInfoResponse,XyzPlanetResource};
     use cosmwasm_std::{Coin, Timestamp, Uint64};
     use cosmwasm_std::testing::{mock_env, mock_info};
 
     use crate::contract::{execute, instantiate, query_balance, query_token_info};
-    use crate::msg::{InstantiateMsg};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
 
     fn coins(count: u64, token: &str) -> Vec<Coin> {
         let mut _coins = vec![];
@@ -221,7 +349,7 @@ mod tests {
     }
 
     fn get_balance<T: Into<String>>(deps: Deps, address: T) -> Uint128 {
-        query_balance(deps, address.into()).unwrap().balance
+        query_balance(deps, address.into(), None).unwrap().balance
     }
 
     // this will set up the instantiation for other tests
@@ -279,7 +407,7 @@ mod tests {
         // set allowance with height expiration
         let allow1 = Uint128::new(7777);
         let expires = Expiration::AtHeight(5432);
-        let msg = CollectXyzResourceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: Some(expires),
@@ -305,7 +433,7 @@ mod tests {
         // decrease it a bit with no expire set - stays the same
         let lower = Uint128::new(4444);
         let allow2 = allow1.checked_sub(lower).unwrap();
-        let msg = CollectXyzResourceExecuteMsg::DecreaseAllowance {
+        let msg = ExecuteMsg::DecreaseAllowance {
             spender: spender.clone(),
             amount: lower,
             expires: None,
@@ -330,7 +458,7 @@ mod tests {
         let raise = Uint128::new(87654);
         let allow3 = allow2 + raise;
         let new_expire = Expiration::AtTime(Timestamp::from_seconds(8888888888));
-        let msg = CollectXyzResourceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: raise,
             expires: Some(new_expire),
@@ -352,7 +480,7 @@ mod tests {
         );
 
         // decrease it below 0
-        let msg = CollectXyzResourceExecuteMsg::DecreaseAllowance {
+        let msg = ExecuteMsg::DecreaseAllowance {
             spender: spender.clone(),
             amount: Uint128::new(99988647623876347),
             expires: None,
@@ -418,7 +546,7 @@ mod tests {
         // set allowance with height expiration
         let allow1 = Uint128::new(7777);
         let expires = Expiration::AtHeight(5432);
-        let msg = CollectXyzResourceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: Some(expires),
@@ -428,7 +556,7 @@ mod tests {
 
         // set other allowance with no expiration
         let allow2 = Uint128::new(87654);
-        let msg = CollectXyzResourceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender2.clone(),
             amount: allow2,
             expires: None,
@@ -491,7 +619,7 @@ mod tests {
         do_instantiate(deps.as_mut(), &owner, Uint128::new(12340000));
 
         // self-allowance
-        let msg = CollectXyzResourceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: owner.clone(),
             amount: Uint128::new(7777),
             expires: None,
@@ -501,7 +629,7 @@ mod tests {
         assert_eq!(err, ContractError::CannotSetOwnAccount {});
 
         // decrease self-allowance
-        let msg = CollectXyzResourceExecuteMsg::DecreaseAllowance {
+        let msg = ExecuteMsg::DecreaseAllowance {
             spender: owner,
             amount: Uint128::new(7777),
             expires: None,
@@ -529,7 +657,7 @@ mod tests {
 
         // provide an allowance
         let allow1 = Uint128::new(77777);
-        let msg = CollectXyzResourceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: None,
@@ -541,7 +669,7 @@ mod tests {
 
         // valid burn of part of the allowance
         let transfer = Uint128::new(44444);
-        let msg = CollectXyzResourceExecuteMsg::BurnFrom {
+        let msg = ExecuteMsg::BurnFrom {
             owner: owner.clone(),
             amount: transfer,
             owner_xyz_id: owner_xyz_id.to_string(),
@@ -566,7 +694,7 @@ mod tests {
         assert_eq!(expect, allowance);
 
         // cannot burn more than the allowance
-        let msg = CollectXyzResourceExecuteMsg::BurnFrom {
+        let msg = ExecuteMsg::BurnFrom {
             owner: owner.clone(),
             amount: Uint128::new(33443),
             owner_xyz_id: owner_xyz_id.to_string(),
@@ -579,7 +707,7 @@ mod tests {
         // let us increase limit, but set the expiration (default env height is 12_345)
         let info = mock_info(owner.as_ref(), &[]);
         let env = mock_env();
-        let msg = CollectXyzResourceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: Uint128::new(1000),
             expires: Some(Expiration::AtHeight(env.block.height)),
@@ -588,7 +716,7 @@ mod tests {
         execute(deps.as_mut(), env, info, msg).unwrap();
 
         // we should now get the expiration error
-        let msg = CollectXyzResourceExecuteMsg::BurnFrom {
+        let msg = ExecuteMsg::BurnFrom {
             owner,
             amount: Uint128::new(33443),
             owner_xyz_id: owner_xyz_id.to_string(),
@@ -599,6 +727,141 @@ mod tests {
         assert_eq!(err, ContractError::Expired {});
     }
 
+    #[test]
+    fn batch_burn_from_respects_limits() {
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let owner = NFT_OWNER_ADDRESS.to_string();
+        let owner_xyz_id = XYZ_NFT_ID.to_string();
+        let spender = String::from("addr0002");
+
+        let start = Uint128::new(999999);
+        do_instantiate(deps.as_mut(), &owner, start);
+
+        let allow1 = Uint128::new(1000);
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: spender.clone(),
+            amount: allow1,
+            expires: None,
+            owner_xyz_id: owner_xyz_id.to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(owner.as_ref(), &[]), msg).unwrap();
+
+        let burn = |amount: u128| XyzAmount {
+            xyz_id: owner_xyz_id.to_string(),
+            amount: Uint128::new(amount),
+        };
+
+        // the allowance is checked against the total burned from each xyz
+        let msg = ExecuteMsg::BatchBurnFrom {
+            owner: owner.clone(),
+            burns: vec![burn(600), burn(600)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(spender.as_ref(), &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+
+        let msg = ExecuteMsg::BatchBurnFrom {
+            owner: owner.clone(),
+            burns: vec![burn(600), burn(300)],
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(spender.as_ref(), &[]), msg).unwrap();
+        assert_eq!(res.attributes[0], attr("action", "batch_burn_from"));
+        assert_eq!(get_balance(deps.as_ref(), owner_xyz_id.to_string()), Uint128::new(999099));
+        assert_eq!(query_token_info(deps.as_ref()).unwrap().total_supply, Uint128::new(999099));
+        assert_eq!(
+            query_allowance(deps.as_ref(), owner, owner_xyz_id.to_string(), spender).unwrap().allowance,
+            Uint128::new(100)
+        );
+    }
+
+    #[test]
+    fn transfer_from_respects_limits() {
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let owner = NFT_OWNER_ADDRESS.to_string();
+        let owner_xyz_id = XYZ_NFT_ID.to_string();
+        let to_xyz_id = String::from("xyz #2");
+        let spender = String::from("addr0002");
+
+        let start = Uint128::new(999999);
+        do_instantiate(deps.as_mut(), &owner, start);
+
+        // provide an allowance
+        let allow1 = Uint128::new(77777);
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: spender.clone(),
+            amount: allow1,
+            expires: None,
+            owner_xyz_id: owner_xyz_id.to_string(),
+        };
+        let info = mock_info(owner.as_ref(), &[]);
+        let env = mock_env();
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        // valid transfer of part of the allowance
+        let transfer = Uint128::new(44444);
+        let msg = ExecuteMsg::TransferFrom {
+            owner: owner.clone(),
+            owner_xyz_id: owner_xyz_id.to_string(),
+            to_xyz_id: to_xyz_id.clone(),
+            amount: transfer,
+        };
+        let info = mock_info(spender.as_ref(), &[]);
+        let env = mock_env();
+        let res = execute(deps.as_mut(), env, info, msg).unwrap();
+        assert_eq!(res.attributes[0], attr("action", "transfer_from"));
+
+        // make sure money moved, and supply is unchanged
+        assert_eq!(
+            get_balance(deps.as_ref(), owner_xyz_id.to_string()),
+            start.checked_sub(transfer).unwrap()
+        );
+        assert_eq!(get_balance(deps.as_ref(), to_xyz_id.clone()), transfer);
+        assert_eq!(query_token_info(deps.as_ref()).unwrap().total_supply, start);
+
+        // ensure it looks good
+        let allowance = query_allowance(deps.as_ref(), owner.clone(), owner_xyz_id.to_string(), spender.clone()).unwrap();
+        let expect = AllowanceResponse {
+            allowance: allow1.checked_sub(transfer).unwrap(),
+            expires: Expiration::Never {},
+        };
+        assert_eq!(expect, allowance);
+
+        // cannot transfer more than the allowance
+        let msg = ExecuteMsg::TransferFrom {
+            owner: owner.clone(),
+            owner_xyz_id: owner_xyz_id.to_string(),
+            to_xyz_id: to_xyz_id.clone(),
+            amount: Uint128::new(33443),
+        };
+        let info = mock_info(spender.as_ref(), &[]);
+        let env = mock_env();
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+
+        // cannot transfer from an xyz the owner doesn't own
+        let msg = ExecuteMsg::TransferFrom {
+            owner: spender.clone(),
+            owner_xyz_id: owner_xyz_id.to_string(),
+            to_xyz_id,
+            amount: Uint128::new(1),
+        };
+        let info = mock_info(spender.as_ref(), &[]);
+        let env = mock_env();
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+    }
+
     #[test]
     fn test_non_owner_cant_increase_allowance() {
         let mut deps = mock_dependencies_custom(
@@ -628,7 +891,7 @@ mod tests {
         // set allowance with height expiration
         let allow1 = Uint128::new(7777);
         let expires = Expiration::AtHeight(5432);
-        let msg = CollectXyzResourceExecuteMsg::IncreaseAllowance {
+        let msg = ExecuteMsg::IncreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: Some(expires),
@@ -668,7 +931,7 @@ mod tests {
         // set allowance with height expiration
         let allow1 = Uint128::new(7777);
         let expires = Expiration::AtHeight(5432);
-        let msg = CollectXyzResourceExecuteMsg::DecreaseAllowance {
+        let msg = ExecuteMsg::DecreaseAllowance {
             spender: spender.clone(),
             amount: allow1,
             expires: Some(expires),
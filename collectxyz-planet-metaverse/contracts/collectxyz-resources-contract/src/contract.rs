@@ -1,8 +1,9 @@
-use collectxyz_planet_metaverse::util::{fetch_nft_data};
+use collectxyz_planet_metaverse::util::{fetch_nft_data, validate_nft_is_owned_by_wallet};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    Addr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult, Uint128,
+    Addr, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError,
+    StdResult, Storage, Uint128,
 };
 
 use cw2::set_contract_version;
@@ -12,12 +13,25 @@ use collectxyz_resources::{
 };
 
 use crate::allowances::{
-    execute_burn_from, execute_decrease_allowance, execute_increase_allowance, query_allowance,
+    execute_batch_burn_from, execute_burn_from, execute_decrease_allowance,
+    execute_increase_allowance, execute_transfer_from, query_allowance,
 };
 use crate::enumerable::{query_all_accounts, query_all_allowances};
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use crate::state::{BALANCES, LOGO, MARKETING_INFO, MinterData, TOKEN_INFO, TOKEN_INFO_OLD, XyzPlanetResourceInfo};
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, TotalSupplyResponse, XyzAmount,
+};
+use crate::multi::{
+    execute_burn_resources_from, execute_mint_resources, execute_send_resources,
+    execute_set_resource, execute_set_resource_allowances, execute_transfer_resources,
+    query_all_resources, query_resource, query_resource_allowance, query_resource_balance,
+    query_resource_balance_at, query_resource_balances, reset_resource_supplies,
+};
+use crate::receiver::XyzResourceReceiveMsg;
+use crate::state::{
+    balance_key, BALANCES, BALANCES_OLD, LOGO, MARKETING_INFO, MinterData, OWNER, TOKEN_INFO,
+    TOTAL_SUPPLY_HISTORY, TOTAL_SUPPLY_HISTORY_START, TOTAL_SUPPLY_KEY, XyzPlanetResourceInfo,
+};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:collectxyz-resources-contract";
@@ -25,6 +39,10 @@ const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const LOGO_SIZE_CAP: usize = 5 * 1024;
 
+// balances moved into seasons per migration page
+const DEFAULT_MIGRATE_LIMIT: u32 = 100;
+const MAX_MIGRATE_LIMIT: u32 = 300;
+
 /// Checks if data starts with XML preamble
 fn verify_xml_preamble(data: &[u8]) -> Result<(), ContractError> {
     // The easiest way to perform this check would be just match on regex, however regex
@@ -89,8 +107,8 @@ fn verify_logo(logo: &Logo) -> Result<(), ContractError> {
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     mut deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
+    env: Env,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
@@ -98,7 +116,11 @@ pub fn instantiate(
     msg.validate()?;
     // create initial accounts
     let total_supply = create_accounts(
-        &mut deps, &msg.initial_balances, &msg.xyz_contract_address
+        &mut deps,
+        &msg.initial_balances,
+        &msg.xyz_contract_address,
+        msg.xyz_competition_season,
+        env.block.height,
     )?;
 
     if let Some(limit) = msg.get_max_cap() {
@@ -132,6 +154,8 @@ pub fn instantiate(
         xyz_competition_season: msg.xyz_competition_season
     };
     TOKEN_INFO.save(deps.storage, &data)?;
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &total_supply, env.block.height)?;
+    OWNER.save(deps.storage, &info.sender)?;
 
     if let Some(marketing) = msg.marketing {
         let logo = if let Some(logo) = marketing.logo {
@@ -161,14 +185,20 @@ pub fn instantiate(
     Ok(Response::default())
 }
 
-pub fn create_accounts(deps: &mut DepsMut, accounts: &[XyzPlanetResource], xyz_contract_addr: &Addr) -> StdResult<Uint128> {
+pub fn create_accounts(
+    deps: &mut DepsMut,
+    accounts: &[XyzPlanetResource],
+    xyz_contract_addr: &Addr,
+    season: u64,
+    height: u64,
+) -> StdResult<Uint128> {
     let mut total_supply = Uint128::zero();
     for row in accounts {
         // validate that nft_id exists
         let _ = fetch_nft_data(
             &row.xyz_id, &xyz_contract_addr, &deps.querier
         )?;
-        BALANCES.save(deps.storage, &row.xyz_id, &row.amount)?;
+        BALANCES.save(deps.storage, balance_key(season, &row.xyz_id), &row.amount, height)?;
         total_supply += row.amount;
     }
     Ok(total_supply)
@@ -182,7 +212,18 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
+        ExecuteMsg::Transfer { from_xyz_id, to_xyz_id, amount } => {
+            execute_transfer(deps, env, info, from_xyz_id, to_xyz_id, amount)
+        }
+        ExecuteMsg::Send {
+            from_xyz_id,
+            contract,
+            to_xyz_id,
+            amount,
+            msg,
+        } => execute_send(deps, env, info, from_xyz_id, contract, to_xyz_id, amount, msg),
         ExecuteMsg::Mint { recipient_xyz_id, amount } => execute_mint(deps, env, info, recipient_xyz_id, amount),
+        ExecuteMsg::BatchMint { mints } => execute_batch_mint(deps, env, info, mints),
         ExecuteMsg::IncreaseAllowance {
             spender,
             amount,
@@ -195,19 +236,198 @@ pub fn execute(
             expires,
             owner_xyz_id,
         } => execute_decrease_allowance(deps, env, info, spender, amount, expires, owner_xyz_id),
+        ExecuteMsg::TransferFrom {
+            owner,
+            owner_xyz_id,
+            to_xyz_id,
+            amount,
+        } => execute_transfer_from(deps, env, info, owner, owner_xyz_id, to_xyz_id, amount),
         ExecuteMsg::BurnFrom { owner, amount, owner_xyz_id } => execute_burn_from(deps, env, info, owner, owner_xyz_id, amount),
+        ExecuteMsg::BatchBurnFrom { owner, burns } => execute_batch_burn_from(deps, env, info, owner, burns),
         ExecuteMsg::UpdateMarketing {
             project,
             description,
             marketing,
         } => execute_update_marketing(deps, env, info, project, description, marketing),
         ExecuteMsg::UploadLogo(logo) => execute_upload_logo(deps, env, info, logo),
+        ExecuteMsg::StartNewSeason {} => execute_start_new_season(deps, env, info),
+        ExecuteMsg::MigrateBalances { limit } => execute_migrate_balances(deps, env, info, limit),
+        ExecuteMsg::SetResource {
+            resource_id,
+            name,
+            symbol,
+            decimals,
+            minters,
+        } => execute_set_resource(deps, env, info, resource_id, name, symbol, decimals, minters),
+        ExecuteMsg::MintResources { mints } => execute_mint_resources(deps, env, info, mints),
+        ExecuteMsg::TransferResources {
+            from_xyz_id,
+            to_xyz_id,
+            amounts,
+        } => execute_transfer_resources(deps, env, info, from_xyz_id, to_xyz_id, amounts),
+        ExecuteMsg::SendResources {
+            from_xyz_id,
+            contract,
+            to_xyz_id,
+            amounts,
+            msg,
+        } => execute_send_resources(
+            deps, env, info, from_xyz_id, contract, to_xyz_id, amounts, msg,
+        ),
+        ExecuteMsg::SetResourceAllowances {
+            spender,
+            owner_xyz_id,
+            allowances,
+            expires,
+        } => execute_set_resource_allowances(deps, env, info, spender, owner_xyz_id, allowances, expires),
+        ExecuteMsg::BurnResourcesFrom {
+            owner,
+            owner_xyz_id,
+            amounts,
+        } => execute_burn_resources_from(deps, env, info, owner, owner_xyz_id, amounts),
     }
 }
 
+pub fn execute_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_xyz_id: String,
+    to_xyz_id: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount == Uint128::zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let config = TOKEN_INFO.load(deps.storage)?;
+
+    // Validate from_xyz_id is owned by sender
+    if !validate_nft_is_owned_by_wallet(
+        &from_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_contract_address
+    )? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // validate that the recipient nft id is valid
+    let _ = fetch_nft_data(
+        &to_xyz_id,
+        &config.xyz_contract_address,
+        &deps.querier
+    )?;
+
+    move_balance(
+        deps.storage,
+        config.xyz_competition_season,
+        &from_xyz_id,
+        &to_xyz_id,
+        amount,
+        env.block.height,
+    )?;
+
+    let res = Response::new()
+        .add_attribute("action", "transfer")
+        .add_attribute("from_xyz_id", from_xyz_id)
+        .add_attribute("to_xyz_id", to_xyz_id)
+        .add_attribute("amount", amount);
+    Ok(res)
+}
+
+pub fn execute_send(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_xyz_id: String,
+    contract: String,
+    to_xyz_id: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    if amount == Uint128::zero() {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let config = TOKEN_INFO.load(deps.storage)?;
+    let contract_addr = deps.api.addr_validate(&contract)?;
+
+    // Validate from_xyz_id is owned by sender
+    if !validate_nft_is_owned_by_wallet(
+        &from_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_contract_address
+    )? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // validate that the recipient nft id is valid
+    let _ = fetch_nft_data(
+        &to_xyz_id,
+        &config.xyz_contract_address,
+        &deps.querier
+    )?;
+
+    move_balance(
+        deps.storage,
+        config.xyz_competition_season,
+        &from_xyz_id,
+        &to_xyz_id,
+        amount,
+        env.block.height,
+    )?;
+
+    let res = Response::new()
+        .add_attribute("action", "send")
+        .add_attribute("from_xyz_id", &from_xyz_id)
+        .add_attribute("to_xyz_id", &to_xyz_id)
+        .add_attribute("contract", &contract)
+        .add_attribute("amount", amount)
+        .add_message(
+            XyzResourceReceiveMsg {
+                sender: info.sender.into(),
+                from_xyz_id,
+                to_xyz_id,
+                amount,
+                msg,
+            }
+            .into_cosmos_msg(contract_addr)?,
+        );
+    Ok(res)
+}
+
+/// Moves amount from one xyz balance to another within a season, failing if the sender has
+/// insufficient balance
+pub fn move_balance(
+    storage: &mut dyn Storage,
+    season: u64,
+    from_xyz_id: &str,
+    to_xyz_id: &str,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()> {
+    BALANCES.update(
+        storage,
+        balance_key(season, from_xyz_id),
+        height,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_sub(amount)?)
+        },
+    )?;
+    BALANCES.update(
+        storage,
+        balance_key(season, to_xyz_id),
+        height,
+        |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
+    )?;
+    Ok(())
+}
+
 pub fn execute_mint(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     recipient_xyz_id: String,
     amount: Uint128,
@@ -230,6 +450,7 @@ pub fn execute_mint(
         }
     }
     TOKEN_INFO.save(deps.storage, &config)?;
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &config.total_supply, env.block.height)?;
 
     // validate that the nft id is valid  
     let _ = fetch_nft_data(
@@ -241,7 +462,8 @@ pub fn execute_mint(
     // add amount to recipient balance
     BALANCES.update(
         deps.storage,
-        &recipient_xyz_id,
+        balance_key(config.xyz_competition_season, &recipient_xyz_id),
+        env.block.height,
         |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
     )?;
 
@@ -252,6 +474,96 @@ pub fn execute_mint(
     Ok(res)
 }
 
+pub fn execute_batch_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mints: Vec<XyzAmount>,
+) -> Result<Response, ContractError> {
+    if mints.is_empty() || mints.iter().any(|mint| mint.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+
+    let mut config = TOKEN_INFO.load(deps.storage)?;
+    if config.get_minter(info.sender.to_string()).is_none() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // update supply and enforce cap once for the whole batch
+    let total = mints
+        .iter()
+        .try_fold(Uint128::zero(), |total, mint| total.checked_add(mint.amount))
+        .map_err(StdError::from)?;
+    config.total_supply = config.total_supply.checked_add(total).map_err(StdError::from)?;
+    if let Some(limit) = config.get_cap(info.sender.to_string()) {
+        if config.total_supply > limit {
+            return Err(ContractError::CannotExceedCap {});
+        }
+    }
+    TOKEN_INFO.save(deps.storage, &config)?;
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &config.total_supply, env.block.height)?;
+
+    for mint in mints.iter() {
+        // validate that the nft id is valid
+        let _ = fetch_nft_data(
+            &mint.xyz_id,
+            &config.xyz_contract_address,
+            &deps.querier
+        )?;
+
+        BALANCES.update(
+            deps.storage,
+            balance_key(config.xyz_competition_season, &mint.xyz_id),
+            env.block.height,
+            |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + mint.amount) },
+        )?;
+    }
+
+    let res = Response::new()
+        .add_attribute("action", "batch_mint")
+        .add_attribute("count", mints.len().to_string())
+        .add_attribute("amount", total);
+    Ok(res)
+}
+
+/// Archives the balances of the current season under its season number and starts the next
+/// season with empty balances and zero total supply, for every resource in multi-resource
+/// mode too. Minter caps apply per season.
+pub fn execute_start_new_season(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    if info.sender != OWNER.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // old balances still to be moved belong to the current season
+    if BALANCES_OLD
+        .range(deps.storage, None, None, Order::Ascending)
+        .next()
+        .is_some()
+    {
+        return Err(ContractError::BalanceMigrationPending {});
+    }
+
+    let mut config = TOKEN_INFO.load(deps.storage)?;
+    let archived_season = config.xyz_competition_season;
+    let archived_supply = config.total_supply;
+    config.xyz_competition_season += 1;
+    config.total_supply = Uint128::zero();
+    TOKEN_INFO.save(deps.storage, &config)?;
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &config.total_supply, env.block.height)?;
+    reset_resource_supplies(deps.storage)?;
+
+    let res = Response::new()
+        .add_attribute("action", "start_new_season")
+        .add_attribute("archived_season", archived_season.to_string())
+        .add_attribute("archived_total_supply", archived_supply)
+        .add_attribute("season", config.xyz_competition_season.to_string());
+    Ok(res)
+}
+
 pub fn execute_update_marketing(
     deps: DepsMut,
     _env: Env,
@@ -341,39 +653,97 @@ pub fn execute_upload_logo(
 }
 
 #[entry_point]
-pub fn migrate(deps: DepsMut, _env: Env, msg: MigrateMsg) -> StdResult<Response> {
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> StdResult<Response> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
-    let old_config = TOKEN_INFO_OLD.load(deps.storage)?;
-    let minters: Option<Vec<MinterData>> = match msg.mint {
-        Some(m) => Some(
-            m.iter().map(|mr| 
-                Ok(
-                    MinterData {
+    let mut config = TOKEN_INFO.load(deps.storage)?;
+    if let Some(mint) = msg.mint {
+        config.minters = Some(
+            mint.iter()
+                .map(|mr| {
+                    Ok(MinterData {
                         minter: deps.api.addr_validate(&mr.minter)?,
                         cap: mr.cap,
-                    }
-                )
-            ).collect::<StdResult<Vec<MinterData>>>()?
-        ),
-        None => None,
-    };
-    let new_config = XyzPlanetResourceInfo {
-        name: old_config.name,
-        symbol: old_config.symbol,
-        decimals: old_config.decimals,
-        total_supply: old_config.total_supply,
-        minters: minters,
-        xyz_competition_season: old_config.xyz_competition_season,
-        xyz_contract_address: old_config.xyz_contract_address,
-    };
-    TOKEN_INFO.save(deps.storage, &new_config)?;
-    return Ok(Response::default());
+                    })
+                })
+                .collect::<StdResult<Vec<MinterData>>>()?,
+        );
+        TOKEN_INFO.save(deps.storage, &config)?;
+    }
+    // total supply history starts from the first migration that records it
+    if TOTAL_SUPPLY_HISTORY_START.may_load(deps.storage)?.is_none() {
+        TOTAL_SUPPLY_HISTORY_START.save(deps.storage, &env.block.height)?;
+    }
+    TOTAL_SUPPLY_HISTORY.save(deps.storage, TOTAL_SUPPLY_KEY, &config.total_supply, env.block.height)?;
+    if let Some(owner) = msg.owner {
+        OWNER.save(deps.storage, &deps.api.addr_validate(&owner)?)?;
+    }
+
+    // move the first page of balances from before seasons were introduced into the
+    // current season, the rest are moved by MigrateBalances
+    let moved = move_old_balances(deps.storage, &config, env.block.height, DEFAULT_MIGRATE_LIMIT)?;
+    Ok(Response::new()
+        .add_attribute("action", "migrate")
+        .add_attribute("moved_balances", moved.to_string()))
+}
+
+/// Moves up to `limit` balances from before seasons were introduced into the current
+/// season, adding to any balance the xyz already has there. Returns how many were moved.
+fn move_old_balances(
+    storage: &mut dyn Storage,
+    config: &XyzPlanetResourceInfo,
+    height: u64,
+    limit: u32,
+) -> StdResult<usize> {
+    let old_balances = BALANCES_OLD
+        .range(storage, None, None, Order::Ascending)
+        .take(limit as usize)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((String::from_utf8(k)?, v))
+        })
+        .collect::<StdResult<Vec<(String, Uint128)>>>()?;
+    for (xyz_id, balance) in old_balances.iter() {
+        BALANCES.update(
+            storage,
+            balance_key(config.xyz_competition_season, xyz_id),
+            height,
+            |current| -> StdResult<_> { Ok(current.unwrap_or_default() + *balance) },
+        )?;
+        BALANCES_OLD.remove(storage, xyz_id);
+    }
+    Ok(old_balances.len())
+}
+
+/// Only the owner. Moves the next page of balances from before seasons were introduced
+/// into the current season, for contracts with more than the migration moved.
+pub fn execute_migrate_balances(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    if info.sender != OWNER.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let config = TOKEN_INFO.load(deps.storage)?;
+    let limit = limit.unwrap_or(DEFAULT_MIGRATE_LIMIT).min(MAX_MIGRATE_LIMIT);
+    let moved = move_old_balances(deps.storage, &config, env.block.height, limit)?;
+
+    let res = Response::new()
+        .add_attribute("action", "migrate_balances")
+        .add_attribute("moved_balances", moved.to_string());
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Balance { xyz_id } => to_binary(&query_balance(deps, xyz_id)?),
+        QueryMsg::Balance { xyz_id, season } => to_binary(&query_balance(deps, xyz_id, season)?),
+        QueryMsg::BalanceAt { xyz_id, season, height } => {
+            to_binary(&query_balance_at(deps, xyz_id, season, height)?)
+        }
+        QueryMsg::TotalSupplyAt { height } => to_binary(&query_total_supply_at(deps, height)?),
         QueryMsg::TokenInfo {} => to_binary(&query_token_info(deps)?),
         QueryMsg::Minter {} => to_binary(&query_minters(deps)?),
         QueryMsg::Allowance { owner_xyz_id, owner, spender } => {
@@ -390,16 +760,80 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         }
         QueryMsg::MarketingInfo {} => to_binary(&query_marketing_info(deps)?),
         QueryMsg::DownloadLogo {} => to_binary(&query_download_logo(deps)?),
+        QueryMsg::Resource { resource_id } => to_binary(&query_resource(deps, resource_id)?),
+        QueryMsg::AllResources { start_after, limit } => {
+            to_binary(&query_all_resources(deps, start_after, limit)?)
+        }
+        QueryMsg::ResourceBalance { resource_id, xyz_id, season } => {
+            to_binary(&query_resource_balance(deps, resource_id, xyz_id, season)?)
+        }
+        QueryMsg::ResourceBalanceAt { resource_id, xyz_id, season, height } => {
+            to_binary(&query_resource_balance_at(deps, resource_id, xyz_id, season, height)?)
+        }
+        QueryMsg::ResourceBalances { xyz_id, resource_ids, season } => {
+            to_binary(&query_resource_balances(deps, xyz_id, resource_ids, season)?)
+        }
+        QueryMsg::ResourceAllowance {
+            resource_id,
+            owner_xyz_id,
+            owner,
+            spender,
+        } => to_binary(&query_resource_allowance(deps, resource_id, owner, owner_xyz_id, spender)?),
     }
 }
 
-pub fn query_balance(deps: Deps, xyz_id: String) -> StdResult<BalanceResponse> {
+/// Errors for heights up to the migration that started the balance and supply history,
+/// since history from before it wasn't kept
+pub fn check_history_height(deps: Deps, height: u64) -> StdResult<()> {
+    if let Some(start) = TOTAL_SUPPLY_HISTORY_START.may_load(deps.storage)? {
+        if height <= start {
+            return Err(StdError::generic_err(format!(
+                "history is only available after height {}",
+                start
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Resolves an optional season to the current season
+pub fn season_or_current(deps: Deps, season: Option<u64>) -> StdResult<u64> {
+    match season {
+        Some(season) => Ok(season),
+        None => Ok(TOKEN_INFO.load(deps.storage)?.xyz_competition_season),
+    }
+}
+
+pub fn query_balance(deps: Deps, xyz_id: String, season: Option<u64>) -> StdResult<BalanceResponse> {
+    let season = season_or_current(deps, season)?;
+    let balance = BALANCES
+        .may_load(deps.storage, balance_key(season, &xyz_id))?
+        .unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+pub fn query_balance_at(
+    deps: Deps,
+    xyz_id: String,
+    season: Option<u64>,
+    height: u64,
+) -> StdResult<BalanceResponse> {
+    check_history_height(deps, height)?;
+    let season = season_or_current(deps, season)?;
     let balance = BALANCES
-        .may_load(deps.storage, &xyz_id)?
+        .may_load_at_height(deps.storage, balance_key(season, &xyz_id), height)?
         .unwrap_or_default();
     Ok(BalanceResponse { balance })
 }
 
+pub fn query_total_supply_at(deps: Deps, height: u64) -> StdResult<TotalSupplyResponse> {
+    check_history_height(deps, height)?;
+    let total_supply = TOTAL_SUPPLY_HISTORY
+        .may_load_at_height(deps.storage, TOTAL_SUPPLY_KEY, height)?
+        .unwrap_or_default();
+    Ok(TotalSupplyResponse { total_supply })
+}
+
 pub fn query_token_info(deps: Deps) -> StdResult<TokenInfoResponse> {
     let info = TOKEN_INFO.load(deps.storage)?;
     let res = TokenInfoResponse {
@@ -458,7 +892,7 @@ mod tests {
     use super::*;
 
     fn get_balance<T: Into<String>>(deps: Deps, address: T) -> Uint128 {
-        query_balance(deps, address.into()).unwrap().balance
+        query_balance(deps, address.into(), None).unwrap().balance
     }
 
     // this will set up the instantiation for other tests
@@ -773,6 +1207,247 @@ mod tests {
         assert_eq!(err, ContractError::CannotExceedCap {});
     }
 
+    #[test]
+    fn batch_mint() {
+        let mut deps = mock_dependencies_custom(
+            None,
+            None,
+            None,
+            vec![],
+            &[],
+        );
+
+        let genesis = "xyz id 1";
+        let amount = Uint128::new(1000);
+        let minter = String::from("asmodat");
+        let limit = Uint128::new(2000);
+        do_instantiate_with_minter(deps.as_mut(), &genesis, amount, &minter, Some(limit));
+
+        let mint = |xyz_id: &str, amount: u128| crate::msg::XyzAmount {
+            xyz_id: xyz_id.to_string(),
+            amount: Uint128::new(amount),
+        };
+
+        // the cap applies to the total of the batch
+        let msg = ExecuteMsg::BatchMint {
+            mints: vec![mint("xyz #2", 600), mint("xyz #3", 600)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(minter.as_ref(), &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::CannotExceedCap {});
+
+        // no zero amounts in a batch
+        let msg = ExecuteMsg::BatchMint {
+            mints: vec![mint("xyz #2", 600), mint("xyz #3", 0)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(minter.as_ref(), &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidZeroAmount {});
+
+        // only minters can batch mint
+        let msg = ExecuteMsg::BatchMint {
+            mints: vec![mint("xyz #2", 600)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("anyone else", &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        let msg = ExecuteMsg::BatchMint {
+            mints: vec![mint("xyz #2", 600), mint("xyz #3", 300), mint("xyz #2", 50)],
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info(minter.as_ref(), &[]), msg).unwrap();
+        assert_eq!(0, res.messages.len());
+        assert_eq!(get_balance(deps.as_ref(), "xyz #2"), Uint128::new(650));
+        assert_eq!(get_balance(deps.as_ref(), "xyz #3"), Uint128::new(300));
+        assert_eq!(query_token_info(deps.as_ref()).unwrap().total_supply, Uint128::new(1950));
+    }
+
+    #[test]
+    fn transfer() {
+        use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, NFT_OWNER_ADDRESS, NOW, default_xyz_nft_data};
+
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let from_xyz_id = XYZ_NFT_ID.to_string();
+        let to_xyz_id = String::from("xyz #2");
+        let amount = Uint128::new(12340000);
+        do_instantiate(deps.as_mut(), &from_xyz_id, amount);
+
+        // cannot transfer nothing
+        let msg = ExecuteMsg::Transfer {
+            from_xyz_id: from_xyz_id.clone(),
+            to_xyz_id: to_xyz_id.clone(),
+            amount: Uint128::zero(),
+        };
+        let info = mock_info(NFT_OWNER_ADDRESS, &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::InvalidZeroAmount {});
+
+        // cannot transfer from an xyz the sender doesn't own
+        let transfer = Uint128::new(76543);
+        let msg = ExecuteMsg::Transfer {
+            from_xyz_id: from_xyz_id.clone(),
+            to_xyz_id: to_xyz_id.clone(),
+            amount: transfer,
+        };
+        let info = mock_info("anyone else", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // cannot send more than the balance
+        let msg = ExecuteMsg::Transfer {
+            from_xyz_id: from_xyz_id.clone(),
+            to_xyz_id: to_xyz_id.clone(),
+            amount: amount + Uint128::new(1),
+        };
+        let info = mock_info(NFT_OWNER_ADDRESS, &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+
+        // valid transfer
+        let msg = ExecuteMsg::Transfer {
+            from_xyz_id: from_xyz_id.clone(),
+            to_xyz_id: to_xyz_id.clone(),
+            amount: transfer,
+        };
+        let info = mock_info(NFT_OWNER_ADDRESS, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 0);
+        assert_eq!(get_balance(deps.as_ref(), from_xyz_id), amount.checked_sub(transfer).unwrap());
+        assert_eq!(get_balance(deps.as_ref(), to_xyz_id), transfer);
+        assert_eq!(query_token_info(deps.as_ref()).unwrap().total_supply, amount);
+    }
+
+    #[test]
+    fn send() {
+        use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, NFT_OWNER_ADDRESS, NOW, default_xyz_nft_data};
+        use cosmwasm_std::{CosmosMsg, SubMsg, WasmMsg};
+
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let from_xyz_id = XYZ_NFT_ID.to_string();
+        let to_xyz_id = String::from("xyz #2");
+        let contract = String::from("addr0004");
+        let amount = Uint128::new(12340000);
+        let transfer = Uint128::new(76543);
+        let send_msg = Binary::from(r#"{"some":123}"#.as_bytes());
+        do_instantiate(deps.as_mut(), &from_xyz_id, amount);
+
+        // cannot send from an xyz the sender doesn't own
+        let msg = ExecuteMsg::Send {
+            from_xyz_id: from_xyz_id.clone(),
+            contract: contract.clone(),
+            to_xyz_id: to_xyz_id.clone(),
+            amount: transfer,
+            msg: send_msg.clone(),
+        };
+        let info = mock_info("anyone else", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // valid send
+        let msg = ExecuteMsg::Send {
+            from_xyz_id: from_xyz_id.clone(),
+            contract: contract.clone(),
+            to_xyz_id: to_xyz_id.clone(),
+            amount: transfer,
+            msg: send_msg.clone(),
+        };
+        let info = mock_info(NFT_OWNER_ADDRESS, &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+
+        // ensure proper send message sent
+        let binary_msg = XyzResourceReceiveMsg {
+            sender: NFT_OWNER_ADDRESS.to_string(),
+            from_xyz_id: from_xyz_id.clone(),
+            to_xyz_id: to_xyz_id.clone(),
+            amount: transfer,
+            msg: send_msg,
+        }
+        .into_binary()
+        .unwrap();
+        assert_eq!(
+            res.messages[0],
+            SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: contract,
+                msg: binary_msg,
+                funds: vec![],
+            }))
+        );
+
+        // ensure balance is properly transferred
+        assert_eq!(get_balance(deps.as_ref(), from_xyz_id), amount.checked_sub(transfer).unwrap());
+        assert_eq!(get_balance(deps.as_ref(), to_xyz_id), transfer);
+        assert_eq!(query_token_info(deps.as_ref()).unwrap().total_supply, amount);
+    }
+
+    #[test]
+    fn balance_and_supply_history() {
+        use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, NFT_OWNER_ADDRESS, NOW, default_xyz_nft_data};
+
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let xyz_id = XYZ_NFT_ID.to_string();
+        let other_xyz_id = String::from("xyz #2");
+        let minter = String::from("minter");
+        let start = Uint128::new(1000);
+        do_instantiate_with_minter(deps.as_mut(), &xyz_id, start, &minter, None);
+        let start_height = mock_env().block.height;
+
+        // mint a few blocks later
+        let mut env = mock_env();
+        env.block.height = start_height + 5;
+        let msg = ExecuteMsg::Mint {
+            recipient_xyz_id: xyz_id.clone(),
+            amount: Uint128::new(500),
+        };
+        execute(deps.as_mut(), env, mock_info(&minter, &[]), msg).unwrap();
+
+        // transfer a few blocks after that
+        let mut env = mock_env();
+        env.block.height = start_height + 10;
+        let msg = ExecuteMsg::Transfer {
+            from_xyz_id: xyz_id.clone(),
+            to_xyz_id: other_xyz_id.clone(),
+            amount: Uint128::new(300),
+        };
+        execute(deps.as_mut(), env, mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+
+        let balance_at = |deps: Deps, xyz_id: &str, height: u64| {
+            query_balance_at(deps, xyz_id.to_string(), None, height).unwrap().balance
+        };
+        let supply_at = |deps: Deps, height: u64| {
+            query_total_supply_at(deps, height).unwrap().total_supply
+        };
+
+        // balances as of the beginning of each block
+        assert_eq!(balance_at(deps.as_ref(), &xyz_id, start_height + 1), start);
+        assert_eq!(balance_at(deps.as_ref(), &xyz_id, start_height + 5), start);
+        assert_eq!(balance_at(deps.as_ref(), &xyz_id, start_height + 6), Uint128::new(1500));
+        assert_eq!(balance_at(deps.as_ref(), &xyz_id, start_height + 11), Uint128::new(1200));
+        assert_eq!(balance_at(deps.as_ref(), &other_xyz_id, start_height + 10), Uint128::zero());
+        assert_eq!(balance_at(deps.as_ref(), &other_xyz_id, start_height + 11), Uint128::new(300));
+
+        // total supply only changes on mint
+        assert_eq!(supply_at(deps.as_ref(), start_height), Uint128::zero());
+        assert_eq!(supply_at(deps.as_ref(), start_height + 1), start);
+        assert_eq!(supply_at(deps.as_ref(), start_height + 6), Uint128::new(1500));
+        assert_eq!(supply_at(deps.as_ref(), start_height + 11), Uint128::new(1500));
+    }
+
     #[test]
     fn others_cannot_mint() {
         let mut deps = mock_dependencies_custom(
@@ -821,4 +1496,151 @@ mod tests {
         let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
         assert_eq!(err, ContractError::Unauthorized {});
     }
-}
\ No newline at end of file
+    #[test]
+    fn seasons() {
+        use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, NFT_OWNER_ADDRESS, NOW, default_xyz_nft_data};
+
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let xyz_id = XYZ_NFT_ID.to_string();
+        let minter = String::from("minter");
+        let start = Uint128::new(1000);
+        do_instantiate_with_minter(deps.as_mut(), &xyz_id, start, &minter, None);
+
+        // only the owner can start a new season
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&minter, &[]),
+            ExecuteMsg::StartNewSeason {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::StartNewSeason {},
+        )
+        .unwrap();
+        let meta = query_token_info(deps.as_ref()).unwrap();
+        assert_eq!(meta.xyz_competition_season, 1);
+        assert_eq!(meta.total_supply, Uint128::zero());
+
+        // balances start empty in the new season
+        assert_eq!(get_balance(deps.as_ref(), &xyz_id), Uint128::zero());
+        assert_eq!(
+            query_all_accounts(deps.as_ref(), None, None).unwrap().accounts,
+            Vec::<String>::new()
+        );
+        let msg = ExecuteMsg::Mint {
+            recipient_xyz_id: xyz_id.clone(),
+            amount: Uint128::new(50),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(&minter, &[]), msg).unwrap();
+
+        // the old season is archived under its season number
+        assert_eq!(query_balance(deps.as_ref(), xyz_id.clone(), Some(0)).unwrap().balance, start);
+        assert_eq!(
+            query_balance(deps.as_ref(), xyz_id.clone(), Some(1)).unwrap().balance,
+            Uint128::new(50)
+        );
+        assert_eq!(get_balance(deps.as_ref(), &xyz_id), Uint128::new(50));
+    }
+
+    #[test]
+    fn migrate_balances() {
+        use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, NFT_OWNER_ADDRESS, NOW, default_xyz_nft_data};
+
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let xyz_id = XYZ_NFT_ID.to_string();
+        let minter = String::from("minter");
+        let start = Uint128::new(1000);
+        do_instantiate_with_minter(deps.as_mut(), &xyz_id, start, &minter, None);
+        for i in 0..(DEFAULT_MIGRATE_LIMIT + 5) {
+            BALANCES_OLD
+                .save(deps.as_mut().storage, &format!("xyz #{}", i + 100), &Uint128::new(7))
+                .unwrap();
+        }
+        BALANCES_OLD.save(deps.as_mut().storage, &xyz_id, &Uint128::new(5)).unwrap();
+
+        // the current token info is kept when no minters are given
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { mint: None, owner: None }).unwrap();
+        assert_eq!(res.attributes[1].value, DEFAULT_MIGRATE_LIMIT.to_string());
+
+        // history from before the migration isn't kept, so those heights error
+        let height = mock_env().block.height;
+        let err = StdError::generic_err(format!("history is only available after height {}", height));
+        assert_eq!(query_balance_at(deps.as_ref(), xyz_id.clone(), None, height).unwrap_err(), err);
+        assert_eq!(query_total_supply_at(deps.as_ref(), height).unwrap_err(), err);
+        assert_eq!(
+            query_resource_balance_at(deps.as_ref(), "xyzROCK".to_string(), xyz_id.clone(), None, height)
+                .unwrap_err(),
+            err
+        );
+        assert_eq!(
+            query_total_supply_at(deps.as_ref(), height + 1).unwrap().total_supply,
+            start
+        );
+        let meta = query_token_info(deps.as_ref()).unwrap();
+        assert_eq!(meta.total_supply, start);
+        assert_eq!(query_minters(deps.as_ref()).unwrap().unwrap()[0].minter, minter);
+
+        // only the owner can move the remaining balances
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(&minter, &[]),
+            ExecuteMsg::MigrateBalances { limit: None },
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        // a new season can't start until every old balance is moved
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::StartNewSeason {},
+        )
+        .unwrap_err();
+        assert_eq!(err, ContractError::BalanceMigrationPending {});
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::MigrateBalances { limit: None },
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1].value, "6");
+        assert_eq!(
+            BALANCES_OLD.range(&deps.storage, None, None, Order::Ascending).count(),
+            0
+        );
+
+        // old balances are added to the current season's balances
+        assert_eq!(get_balance(deps.as_ref(), &xyz_id), Uint128::new(1005));
+        assert_eq!(get_balance(deps.as_ref(), "xyz #100"), Uint128::new(7));
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::StartNewSeason {},
+        )
+        .unwrap();
+    }
+}
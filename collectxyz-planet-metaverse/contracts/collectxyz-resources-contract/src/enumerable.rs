@@ -1,7 +1,7 @@
 use cosmwasm_std::{Deps, Order, StdResult};
 use collectxyz_resources::{AllAccountsResponse, AllAllowancesResponse, AllowanceInfo};
 
-use crate::state::{ALLOWANCES, BALANCES};
+use crate::state::{ALLOWANCES, BALANCES, TOKEN_INFO};
 use cw_storage_plus::Bound;
 
 // settings for pagination
@@ -44,11 +44,16 @@ pub fn query_all_accounts(
 ) -> StdResult<AllAccountsResponse> {
     let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
     let start = start_after.map(Bound::exclusive);
+    let season = TOKEN_INFO.load(deps.storage)?.xyz_competition_season;
 
-    let accounts: Result<Vec<_>, _> = BALANCES
-        .keys(deps.storage, start, None, Order::Ascending)
-        .map(String::from_utf8)
+    let accounts: StdResult<Vec<_>> = BALANCES
+        .prefix(season.into())
+        .range(deps.storage, start, None, Order::Ascending)
         .take(limit)
+        .map(|item| {
+            let (k, _) = item?;
+            Ok(String::from_utf8(k)?)
+        })
         .collect();
 
     Ok(AllAccountsResponse {
@@ -32,4 +32,7 @@ pub enum ContractError {
 
     #[error("Invalid png header")]
     InvalidPngHeader {},
+
+    #[error("Balances from before seasons must be migrated first")]
+    BalanceMigrationPending {},
 }
@@ -3,6 +3,8 @@ pub mod contract;
 pub mod enumerable;
 mod error;
 pub mod msg;
+pub mod multi;
+pub mod receiver;
 pub mod state;
 
 pub use crate::error::ContractError;
@@ -1,10 +1,8 @@
-use cosmwasm_std::{Addr, StdError, StdResult, Uint128};
-use collectxyz_resources::{XyzPlanetResource, Logo, MinterResponse};
+use cosmwasm_std::{Addr, Binary, StdError, StdResult, Uint128};
+use collectxyz_resources::{Expiration, XyzPlanetResource, Logo, MinterResponse};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-pub use collectxyz_resources::CollectXyzResourceExecuteMsg as ExecuteMsg;
-
 #[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, PartialEq)]
 pub struct InstantiateMarketingInfo {
     pub project: Option<String>,
@@ -79,18 +77,180 @@ fn is_valid_symbol(symbol: &str) -> bool {
     true
 }
 
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct XyzAmount {
+    pub xyz_id: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ResourceAmount {
+    pub resource_id: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ResourceMint {
+    pub resource_id: String,
+    pub xyz_id: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Transfer is a base message to move tokens from one xyz to another.
+    /// Requires that the sender owns `from_xyz_id`.
+    Transfer {
+        from_xyz_id: String,
+        to_xyz_id: String,
+        amount: Uint128,
+    },
+    /// Send is a base message to transfer tokens from one xyz to another and trigger an action
+    /// on the receiving contract. Requires that the sender owns `from_xyz_id`.
+    Send {
+        from_xyz_id: String,
+        contract: String,
+        to_xyz_id: String,
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Only with "mintable" extension. If authorized, creates amount new tokens
+    /// and adds to the recipient balance.
+    Mint { recipient_xyz_id: String, amount: Uint128 },
+    /// Only with "mintable" extension. Mints to many xyz ids at once, checking the minter's
+    /// cap once against the total of the batch.
+    BatchMint { mints: Vec<XyzAmount> },
+    /// Only with "approval" extension. Allows spender to access an additional amount tokens
+    /// from the owner's (env.sender) xyz. If expires is Some(), overwrites current allowance
+    /// expiration with this one.
+    IncreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+        owner_xyz_id: String,
+    },
+    /// Only with "approval" extension. Lowers the spender's access of tokens
+    /// from the owner's (env.sender) xyz by amount. If expires is Some(), overwrites current
+    /// allowance expiration with this one.
+    DecreaseAllowance {
+        spender: String,
+        amount: Uint128,
+        expires: Option<Expiration>,
+        owner_xyz_id: String,
+    },
+    /// Only with "approval" extension. Transfers amount tokens from owner_xyz_id -> to_xyz_id
+    /// if `env.sender` has sufficient pre-approval.
+    TransferFrom {
+        owner: String,
+        owner_xyz_id: String,
+        to_xyz_id: String,
+        amount: Uint128,
+    },
+    /// Only with "approval" extension. Destroys tokens forever
+    BurnFrom {
+        owner: String,
+        amount: Uint128,
+        owner_xyz_id: String,
+    },
+    /// Only with "approval" extension. Destroys tokens from many of the owner's xyz ids at once,
+    /// deducting the allowance once per xyz id for the total burned from it.
+    BatchBurnFrom {
+        owner: String,
+        burns: Vec<XyzAmount>,
+    },
+    /// Only with the "marketing" extension. If authorized, updates marketing metadata.
+    /// Setting None/null for any of these will leave it unchanged.
+    /// Setting Some("") will clear this field on the contract storage
+    UpdateMarketing {
+        /// A URL pointing to the project behind this token.
+        project: Option<String>,
+        /// A longer description of the token and it's utility. Designed for tooltips or such
+        description: Option<String>,
+        /// The address (if any) who can update this data structure
+        marketing: Option<String>,
+    },
+    /// If set as the "marketing" role on the contract, upload a new URL, SVG, or PNG for the token
+    UploadLogo(Logo),
+    /// Only the owner. Archives the current balances, including multi-resource balances,
+    /// under the current season number and starts the next season with empty balances.
+    /// Allowances aren't season-scoped and carry over. Fails until `MigrateBalances`
+    /// has moved every balance from before seasons were introduced.
+    StartNewSeason {},
+    /// Only the owner. Moves up to `limit` balances from before seasons were introduced
+    /// into the current season. Migrating moves the first page.
+    MigrateBalances { limit: Option<u32> },
+    /// Multi-resource mode, only the owner. Registers a new resource, or updates the
+    /// metadata and minters of an existing one.
+    SetResource {
+        resource_id: String,
+        name: String,
+        symbol: String,
+        decimals: u8,
+        minters: Vec<MinterResponse>,
+    },
+    /// Multi-resource mode. Mints several resources to several xyz ids at once. The sender
+    /// must be a minter of every resource in the batch; caps are checked once per resource.
+    MintResources { mints: Vec<ResourceMint> },
+    /// Multi-resource mode. Moves several resources from one xyz to another.
+    /// Requires that the sender owns `from_xyz_id`.
+    TransferResources {
+        from_xyz_id: String,
+        to_xyz_id: String,
+        amounts: Vec<ResourceAmount>,
+    },
+    /// Multi-resource mode. Moves several resources from one xyz to another and triggers
+    /// an action on the receiving contract. Requires that the sender owns `from_xyz_id`.
+    SendResources {
+        from_xyz_id: String,
+        contract: String,
+        to_xyz_id: String,
+        amounts: Vec<ResourceAmount>,
+        msg: Binary,
+    },
+    /// Multi-resource mode. Sets the allowances of spender over several resources of the
+    /// owner's (env.sender) xyz. An amount of zero removes the allowance.
+    SetResourceAllowances {
+        spender: String,
+        owner_xyz_id: String,
+        allowances: Vec<ResourceAmount>,
+        expires: Option<Expiration>,
+    },
+    /// Multi-resource mode. Destroys several resources of owner_xyz_id if `env.sender` has
+    /// sufficient pre-approval for each.
+    BurnResourcesFrom {
+        owner: String,
+        owner_xyz_id: String,
+        amounts: Vec<ResourceAmount>,
+    },
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
 pub struct MigrateMsg {
-    pub mint: Option<Vec<MinterResponse>>
+    pub mint: Option<Vec<MinterResponse>>,
+    pub owner: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
-    /// Returns the current balance of the given address, 0 if unset.
+    /// Returns the balance of the given xyz in the given season (defaults to the current
+    /// season), 0 if unset.
+    /// Return type: BalanceResponse.
+    Balance { xyz_id: String, season: Option<u64> },
+    /// Returns the balance of the given xyz in the given season (defaults to the current
+    /// season) at the beginning of the given block height, 0 if unset. Errors for
+    /// heights up to and including the migration that introduced balance history.
     /// Return type: BalanceResponse.
-    Balance { xyz_id: String },
+    BalanceAt { xyz_id: String, season: Option<u64>, height: u64 },
+    /// Returns the total supply at the beginning of the given block height. Errors for
+    /// heights up to and including the migration that introduced supply history.
+    /// Return type: TotalSupplyResponse.
+    TotalSupplyAt { height: u64 },
     /// Returns metadata on the contract - name, decimals, supply, etc.
     /// Return type: TokenInfoResponse.
     TokenInfo {},
@@ -128,4 +288,73 @@ pub enum QueryMsg {
     /// contract.
     /// Return type: DownloadLogoResponse.
     DownloadLogo {},
+    /// Multi-resource mode. Returns the metadata and current season total supply of a
+    /// resource.
+    /// Return type: ResourceInfo.
+    Resource { resource_id: String },
+    /// Multi-resource mode. Returns the registered resource ids. Supports pagination.
+    /// Return type: AllResourcesResponse.
+    AllResources {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Multi-resource mode. Returns the balance of a resource for an xyz in the given season
+    /// (defaults to the current season), 0 if unset.
+    /// Return type: BalanceResponse.
+    ResourceBalance {
+        resource_id: String,
+        xyz_id: String,
+        season: Option<u64>,
+    },
+    /// Multi-resource mode. Returns the balance of a resource for an xyz in the given season
+    /// (defaults to the current season) as of the given block height, 0 if unset. Errors
+    /// for heights up to and including the migration that introduced balance history.
+    /// Return type: BalanceResponse.
+    ResourceBalanceAt {
+        resource_id: String,
+        xyz_id: String,
+        season: Option<u64>,
+        height: u64,
+    },
+    /// Multi-resource mode. Returns the balances of an xyz in the given season (defaults to
+    /// the current season) for the given resources, or every registered resource if unset.
+    /// Return type: ResourceBalancesResponse.
+    ResourceBalances {
+        xyz_id: String,
+        resource_ids: Option<Vec<String>>,
+        season: Option<u64>,
+    },
+    /// Multi-resource mode. Returns how much of a resource spender can use from the owner's xyz.
+    /// Return type: AllowanceResponse.
+    ResourceAllowance {
+        resource_id: String,
+        owner_xyz_id: String,
+        owner: String,
+        spender: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct TotalSupplyResponse {
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct AllResourcesResponse {
+    pub resource_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ResourceBalance {
+    pub resource_id: String,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ResourceBalancesResponse {
+    pub balances: Vec<ResourceBalance>,
 }
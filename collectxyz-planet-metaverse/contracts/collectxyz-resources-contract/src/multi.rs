@@ -0,0 +1,783 @@
+use std::collections::BTreeMap;
+
+use collectxyz_planet_metaverse::util::{fetch_nft_data, validate_nft_is_owned_by_wallet};
+use cosmwasm_std::{
+    attr, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult,
+    Storage, Uint128,
+};
+use collectxyz_resources::{AllowanceResponse, BalanceResponse, Expiration, MinterResponse};
+use cw_storage_plus::Bound;
+
+use crate::contract::{check_history_height, season_or_current};
+use crate::error::ContractError;
+use crate::msg::{
+    AllResourcesResponse, ResourceAmount, ResourceBalance, ResourceBalancesResponse, ResourceMint,
+};
+use crate::receiver::XyzResourcesReceiveMsg;
+use crate::state::{
+    resource_balance_key, MinterData, ResourceAllowance, ResourceInfo, OWNER, RESOURCES,
+    RESOURCE_ALLOWANCES, RESOURCE_BALANCES, TOKEN_INFO,
+};
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Registers a new resource or updates the metadata and minters of an existing one.
+/// The total supply of an existing resource is kept.
+pub fn execute_set_resource(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    resource_id: String,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    minters: Vec<MinterResponse>,
+) -> Result<Response, ContractError> {
+    if info.sender != OWNER.load(deps.storage)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    if resource_id.is_empty() {
+        return Err(StdError::generic_err("Resource id must not be empty").into());
+    }
+    if decimals > 18 {
+        return Err(StdError::generic_err("Decimals must not exceed 18").into());
+    }
+
+    let minters = minters
+        .iter()
+        .map(|mr| {
+            Ok(MinterData {
+                minter: deps.api.addr_validate(&mr.minter)?,
+                cap: mr.cap,
+            })
+        })
+        .collect::<StdResult<Vec<MinterData>>>()?;
+
+    RESOURCES.update(deps.storage, &resource_id, |resource| -> StdResult<_> {
+        Ok(ResourceInfo {
+            name,
+            symbol,
+            decimals,
+            total_supply: resource.map(|r| r.total_supply).unwrap_or_default(),
+            minters,
+        })
+    })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_resource")
+        .add_attribute("resource_id", resource_id))
+}
+
+pub fn execute_mint_resources(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mints: Vec<ResourceMint>,
+) -> Result<Response, ContractError> {
+    if mints.is_empty() || mints.iter().any(|mint| mint.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let config = TOKEN_INFO.load(deps.storage)?;
+
+    // update supply and enforce caps once per resource
+    let mut totals: BTreeMap<&str, Uint128> = BTreeMap::new();
+    for mint in mints.iter() {
+        let total = totals.entry(&mint.resource_id).or_default();
+        *total = total.checked_add(mint.amount).map_err(StdError::from)?;
+    }
+    for (resource_id, amount) in totals.iter() {
+        let mut resource = RESOURCES.load(deps.storage, *resource_id)?;
+        let minter = resource
+            .minters
+            .iter()
+            .find(|m| m.minter == info.sender)
+            .ok_or(ContractError::Unauthorized {})?;
+        let cap = minter.cap;
+        resource.total_supply = resource.total_supply.checked_add(*amount).map_err(StdError::from)?;
+        if let Some(limit) = cap {
+            if resource.total_supply > limit {
+                return Err(ContractError::CannotExceedCap {});
+            }
+        }
+        RESOURCES.save(deps.storage, *resource_id, &resource)?;
+    }
+
+    let mut validated: Vec<&str> = vec![];
+    for mint in mints.iter() {
+        // validate that the nft id is valid
+        if !validated.contains(&mint.xyz_id.as_str()) {
+            let _ = fetch_nft_data(&mint.xyz_id, &config.xyz_contract_address, &deps.querier)?;
+            validated.push(&mint.xyz_id);
+        }
+        RESOURCE_BALANCES.update(
+            deps.storage,
+            resource_balance_key(config.xyz_competition_season, &mint.resource_id, &mint.xyz_id),
+            env.block.height,
+            |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + mint.amount) },
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "mint_resources")
+        .add_attribute("count", mints.len().to_string()))
+}
+
+pub fn execute_transfer_resources(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_xyz_id: String,
+    to_xyz_id: String,
+    amounts: Vec<ResourceAmount>,
+) -> Result<Response, ContractError> {
+    if amounts.is_empty() || amounts.iter().any(|a| a.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let config = TOKEN_INFO.load(deps.storage)?;
+
+    // Validate from_xyz_id is owned by sender
+    if !validate_nft_is_owned_by_wallet(
+        &from_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_contract_address
+    )? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // validate that the recipient nft id is valid
+    let _ = fetch_nft_data(&to_xyz_id, &config.xyz_contract_address, &deps.querier)?;
+
+    for a in amounts.iter() {
+        RESOURCES.load(deps.storage, &a.resource_id)?;
+        move_resource_balance(
+            deps.storage,
+            config.xyz_competition_season,
+            &a.resource_id,
+            &from_xyz_id,
+            &to_xyz_id,
+            a.amount,
+            env.block.height,
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "transfer_resources")
+        .add_attribute("from_xyz_id", from_xyz_id)
+        .add_attribute("to_xyz_id", to_xyz_id)
+        .add_attribute("count", amounts.len().to_string()))
+}
+
+pub fn execute_send_resources(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    from_xyz_id: String,
+    contract: String,
+    to_xyz_id: String,
+    amounts: Vec<ResourceAmount>,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    if amounts.is_empty() || amounts.iter().any(|a| a.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let config = TOKEN_INFO.load(deps.storage)?;
+    let contract_addr = deps.api.addr_validate(&contract)?;
+
+    // Validate from_xyz_id is owned by sender
+    if !validate_nft_is_owned_by_wallet(
+        &from_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_contract_address
+    )? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // validate that the recipient nft id is valid
+    let _ = fetch_nft_data(&to_xyz_id, &config.xyz_contract_address, &deps.querier)?;
+
+    for a in amounts.iter() {
+        RESOURCES.load(deps.storage, &a.resource_id)?;
+        move_resource_balance(
+            deps.storage,
+            config.xyz_competition_season,
+            &a.resource_id,
+            &from_xyz_id,
+            &to_xyz_id,
+            a.amount,
+            env.block.height,
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "send_resources")
+        .add_attribute("from_xyz_id", &from_xyz_id)
+        .add_attribute("to_xyz_id", &to_xyz_id)
+        .add_attribute("contract", &contract)
+        .add_attribute("count", amounts.len().to_string())
+        .add_message(
+            XyzResourcesReceiveMsg {
+                sender: info.sender.into(),
+                from_xyz_id,
+                to_xyz_id,
+                amounts,
+                msg,
+            }
+            .into_cosmos_msg(contract_addr)?,
+        ))
+}
+
+/// Moves amount of a resource from one xyz balance to another within a season, failing if
+/// the sender has insufficient balance
+fn move_resource_balance(
+    storage: &mut dyn Storage,
+    season: u64,
+    resource_id: &str,
+    from_xyz_id: &str,
+    to_xyz_id: &str,
+    amount: Uint128,
+    height: u64,
+) -> StdResult<()> {
+    RESOURCE_BALANCES.update(
+        storage,
+        resource_balance_key(season, resource_id, from_xyz_id),
+        height,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default().checked_sub(amount)?)
+        },
+    )?;
+    RESOURCE_BALANCES.update(
+        storage,
+        resource_balance_key(season, resource_id, to_xyz_id),
+        height,
+        |balance: Option<Uint128>| -> StdResult<_> { Ok(balance.unwrap_or_default() + amount) },
+    )?;
+    Ok(())
+}
+
+/// Resets the supply of every resource for a new season, their balances start empty
+pub fn reset_resource_supplies(storage: &mut dyn Storage) -> StdResult<()> {
+    let resources = RESOURCES
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (resource_id, mut resource) in resources {
+        resource.total_supply = Uint128::zero();
+        RESOURCES.save(storage, &String::from_utf8(resource_id)?, &resource)?;
+    }
+    Ok(())
+}
+
+/// Sets the allowances of spender over several resources of owner_xyz_id. Setting an allowance
+/// to zero removes it.
+pub fn execute_set_resource_allowances(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    spender: String,
+    owner_xyz_id: String,
+    allowances: Vec<ResourceAmount>,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let config = TOKEN_INFO.load(deps.storage)?;
+    // Validate owner_xyz_id is owned by owner
+    if !validate_nft_is_owned_by_wallet(
+        &owner_xyz_id,
+        &info.sender.to_string(),
+        &deps.querier,
+        &config.xyz_contract_address
+    )? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    if spender_addr == info.sender {
+        return Err(ContractError::CannotSetOwnAccount {});
+    }
+
+    for a in allowances.iter() {
+        let key = (a.resource_id.as_str(), owner_xyz_id.as_str(), &spender_addr);
+        if a.amount.is_zero() {
+            RESOURCE_ALLOWANCES.remove(deps.storage, key);
+        } else {
+            RESOURCES.load(deps.storage, &a.resource_id)?;
+            let allowance = ResourceAllowance {
+                owner: info.sender.clone(),
+                allowance: a.amount,
+                expires: expires.unwrap_or_default(),
+            };
+            RESOURCE_ALLOWANCES.save(deps.storage, key, &allowance)?;
+        }
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_resource_allowances"),
+        attr("owner", info.sender),
+        attr("owner_xyz_id", owner_xyz_id),
+        attr("spender", spender),
+    ]))
+}
+
+pub fn execute_burn_resources_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    owner_xyz_id: String,
+    amounts: Vec<ResourceAmount>,
+) -> Result<Response, ContractError> {
+    if amounts.is_empty() || amounts.iter().any(|a| a.amount.is_zero()) {
+        return Err(ContractError::InvalidZeroAmount {});
+    }
+    let owner_addr = deps.api.addr_validate(&owner)?;
+    let config = TOKEN_INFO.load(deps.storage)?;
+
+    // Validate owner_xyz_id is owned by owner
+    if !validate_nft_is_owned_by_wallet(
+        &owner_xyz_id,
+        &owner,
+        &deps.querier,
+        &config.xyz_contract_address
+    )? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    for a in amounts.iter() {
+        // deduct allowance before doing anything else have enough allowance
+        RESOURCE_ALLOWANCES.update(
+            deps.storage,
+            (a.resource_id.as_str(), owner_xyz_id.as_str(), &info.sender),
+            |current| match current {
+                // allowances granted by a previous owner of the xyz don't carry over
+                Some(mut allowance) if allowance.owner == owner_addr => {
+                    if allowance.expires.is_expired(&env.block) {
+                        return Err(ContractError::Expired {});
+                    }
+                    allowance.allowance = allowance
+                        .allowance
+                        .checked_sub(a.amount)
+                        .map_err(StdError::overflow)?;
+                    Ok(allowance)
+                }
+                _ => Err(ContractError::NoAllowance {}),
+            },
+        )?;
+
+        RESOURCE_BALANCES.update(
+            deps.storage,
+            resource_balance_key(config.xyz_competition_season, &a.resource_id, &owner_xyz_id),
+            env.block.height,
+            |balance: Option<Uint128>| -> StdResult<_> {
+                Ok(balance.unwrap_or_default().checked_sub(a.amount)?)
+            },
+        )?;
+        RESOURCES.update(deps.storage, &a.resource_id, |resource| -> StdResult<_> {
+            let mut resource = resource.ok_or_else(|| StdError::not_found("resource"))?;
+            resource.total_supply = resource.total_supply.checked_sub(a.amount)?;
+            Ok(resource)
+        })?;
+    }
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "burn_resources_from"),
+        attr("from", owner),
+        attr("from_xyz_id", owner_xyz_id),
+        attr("by", info.sender),
+        attr("count", amounts.len().to_string()),
+    ]))
+}
+
+pub fn query_resource(deps: Deps, resource_id: String) -> StdResult<ResourceInfo> {
+    RESOURCES.load(deps.storage, &resource_id)
+}
+
+pub fn query_all_resources(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<AllResourcesResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let resource_ids: StdResult<Vec<_>> = RESOURCES
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|k| Ok(String::from_utf8(k)?))
+        .collect();
+    Ok(AllResourcesResponse {
+        resource_ids: resource_ids?,
+    })
+}
+
+pub fn query_resource_balance(
+    deps: Deps,
+    resource_id: String,
+    xyz_id: String,
+    season: Option<u64>,
+) -> StdResult<BalanceResponse> {
+    let season = season_or_current(deps, season)?;
+    let balance = RESOURCE_BALANCES
+        .may_load(deps.storage, resource_balance_key(season, &resource_id, &xyz_id))?
+        .unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+pub fn query_resource_balance_at(
+    deps: Deps,
+    resource_id: String,
+    xyz_id: String,
+    season: Option<u64>,
+    height: u64,
+) -> StdResult<BalanceResponse> {
+    check_history_height(deps, height)?;
+    let season = season_or_current(deps, season)?;
+    let key = resource_balance_key(season, &resource_id, &xyz_id);
+    let balance = RESOURCE_BALANCES
+        .may_load_at_height(deps.storage, key, height)?
+        .unwrap_or_default();
+    Ok(BalanceResponse { balance })
+}
+
+/// Returns the balances of an xyz in a season for the given resources, or all registered
+/// resources
+pub fn query_resource_balances(
+    deps: Deps,
+    xyz_id: String,
+    resource_ids: Option<Vec<String>>,
+    season: Option<u64>,
+) -> StdResult<ResourceBalancesResponse> {
+    let season = season_or_current(deps, season)?;
+    let resource_ids = match resource_ids {
+        Some(ids) => ids,
+        None => RESOURCES
+            .keys(deps.storage, None, None, Order::Ascending)
+            .map(|k| Ok(String::from_utf8(k)?))
+            .collect::<StdResult<Vec<String>>>()?,
+    };
+    let balances = resource_ids
+        .into_iter()
+        .map(|resource_id| {
+            let balance = RESOURCE_BALANCES
+                .may_load(deps.storage, resource_balance_key(season, &resource_id, &xyz_id))?
+                .unwrap_or_default();
+            Ok(ResourceBalance { resource_id, balance })
+        })
+        .collect::<StdResult<Vec<ResourceBalance>>>()?;
+    Ok(ResourceBalancesResponse { balances })
+}
+
+pub fn query_resource_allowance(
+    deps: Deps,
+    resource_id: String,
+    owner: String,
+    owner_xyz_id: String,
+    spender: String,
+) -> StdResult<AllowanceResponse> {
+    let owner_addr: Addr = deps.api.addr_validate(&owner)?;
+    let spender_addr = deps.api.addr_validate(&spender)?;
+    let allowance = RESOURCE_ALLOWANCES
+        .may_load(deps.storage, (resource_id.as_str(), owner_xyz_id.as_str(), &spender_addr))?
+        .filter(|allowance| allowance.owner == owner_addr);
+    Ok(match allowance {
+        Some(a) => AllowanceResponse {
+            allowance: a.allowance,
+            expires: a.expires,
+        },
+        None => AllowanceResponse::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use collectxyz_planet_metaverse::mock_querier::{DEFAULT_RAND, NFT_CONTRACT_ADDRESS, NFT_OWNER_ADDRESS, NOW, XYZ_NFT_ID, default_xyz_nft_data, mock_dependencies_custom};
+    use cosmwasm_std::testing::{mock_env, mock_info};
+
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InstantiateMsg};
+
+    fn do_instantiate(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            name: "Resources".to_string(),
+            symbol: "XYZRES".to_string(),
+            decimals: 6,
+            initial_balances: vec![],
+            mint: None,
+            marketing: None,
+            xyz_competition_season: 0,
+            xyz_contract_address: Addr::unchecked(NFT_CONTRACT_ADDRESS),
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn set_resource(deps: DepsMut, resource_id: &str, minter: &str, cap: Option<Uint128>) {
+        let msg = ExecuteMsg::SetResource {
+            resource_id: resource_id.to_string(),
+            name: resource_id.to_string(),
+            symbol: resource_id.to_string(),
+            decimals: 6,
+            minters: vec![MinterResponse {
+                minter: minter.to_string(),
+                cap,
+            }],
+        };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn amount(resource_id: &str, amount: u128) -> ResourceAmount {
+        ResourceAmount {
+            resource_id: resource_id.to_string(),
+            amount: Uint128::new(amount),
+        }
+    }
+
+    #[test]
+    fn register_and_mint_resources() {
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let minter = "minter";
+        do_instantiate(deps.as_mut());
+
+        // only the owner can register resources
+        let msg = ExecuteMsg::SetResource {
+            resource_id: "xyzROCK".to_string(),
+            name: "Rock".to_string(),
+            symbol: "ROCK".to_string(),
+            decimals: 6,
+            minters: vec![],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+
+        set_resource(deps.as_mut(), "xyzROCK", minter, Some(Uint128::new(1000)));
+        set_resource(deps.as_mut(), "xyzICE", minter, None);
+        assert_eq!(
+            query_all_resources(deps.as_ref(), None, None).unwrap().resource_ids,
+            vec!["xyzICE".to_string(), "xyzROCK".to_string()]
+        );
+
+        let mint = |resource_id: &str, xyz_id: &str, amount: u128| ResourceMint {
+            resource_id: resource_id.to_string(),
+            xyz_id: xyz_id.to_string(),
+            amount: Uint128::new(amount),
+        };
+
+        // caps are checked per resource against the batch total
+        let msg = ExecuteMsg::MintResources {
+            mints: vec![mint("xyzROCK", XYZ_NFT_ID, 600), mint("xyzROCK", "xyz #2", 600)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap_err();
+        assert_eq!(err, ContractError::CannotExceedCap {});
+
+        // unregistered resources can't be minted
+        let msg = ExecuteMsg::MintResources {
+            mints: vec![mint("xyzGAS", XYZ_NFT_ID, 1)],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap_err();
+
+        let msg = ExecuteMsg::MintResources {
+            mints: vec![
+                mint("xyzROCK", XYZ_NFT_ID, 600),
+                mint("xyzICE", XYZ_NFT_ID, 100),
+                mint("xyzROCK", "xyz #2", 50),
+            ],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap();
+        assert_eq!(
+            query_resource_balances(deps.as_ref(), XYZ_NFT_ID.to_string(), None, None).unwrap().balances,
+            vec![
+                ResourceBalance {
+                    resource_id: "xyzICE".to_string(),
+                    balance: Uint128::new(100),
+                },
+                ResourceBalance {
+                    resource_id: "xyzROCK".to_string(),
+                    balance: Uint128::new(600),
+                },
+            ]
+        );
+        assert_eq!(
+            query_resource(deps.as_ref(), "xyzROCK".to_string()).unwrap().total_supply,
+            Uint128::new(650)
+        );
+
+        // updating a resource keeps its supply
+        set_resource(deps.as_mut(), "xyzROCK", minter, Some(Uint128::new(2000)));
+        assert_eq!(
+            query_resource(deps.as_ref(), "xyzROCK".to_string()).unwrap().total_supply,
+            Uint128::new(650)
+        );
+    }
+
+    #[test]
+    fn transfer_and_burn_resources() {
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let minter = "minter";
+        let spender = "spender";
+        do_instantiate(deps.as_mut());
+        set_resource(deps.as_mut(), "xyzROCK", minter, None);
+        set_resource(deps.as_mut(), "xyzICE", minter, None);
+        let msg = ExecuteMsg::MintResources {
+            mints: vec![
+                ResourceMint {
+                    resource_id: "xyzROCK".to_string(),
+                    xyz_id: XYZ_NFT_ID.to_string(),
+                    amount: Uint128::new(500),
+                },
+                ResourceMint {
+                    resource_id: "xyzICE".to_string(),
+                    xyz_id: XYZ_NFT_ID.to_string(),
+                    amount: Uint128::new(500),
+                },
+            ],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap();
+
+        // only the owner of the xyz can transfer
+        let msg = ExecuteMsg::TransferResources {
+            from_xyz_id: XYZ_NFT_ID.to_string(),
+            to_xyz_id: "xyz #2".to_string(),
+            amounts: vec![amount("xyzROCK", 100), amount("xyzICE", 200)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(spender, &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        execute(deps.as_mut(), mock_env(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+        let balance = |deps: Deps, resource_id: &str, xyz_id: &str| {
+            query_resource_balance(deps, resource_id.to_string(), xyz_id.to_string(), None).unwrap().balance
+        };
+        assert_eq!(balance(deps.as_ref(), "xyzROCK", XYZ_NFT_ID), Uint128::new(400));
+        assert_eq!(balance(deps.as_ref(), "xyzICE", "xyz #2"), Uint128::new(200));
+
+        // burning requires an allowance for each resource
+        let burn = ExecuteMsg::BurnResourcesFrom {
+            owner: NFT_OWNER_ADDRESS.to_string(),
+            owner_xyz_id: XYZ_NFT_ID.to_string(),
+            amounts: vec![amount("xyzROCK", 100), amount("xyzICE", 100)],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(spender, &[]), burn.clone()).unwrap_err();
+        assert_eq!(err, ContractError::NoAllowance {});
+
+        let msg = ExecuteMsg::SetResourceAllowances {
+            spender: spender.to_string(),
+            owner_xyz_id: XYZ_NFT_ID.to_string(),
+            allowances: vec![amount("xyzROCK", 150), amount("xyzICE", 100)],
+            expires: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info(spender, &[]), burn.clone()).unwrap();
+        assert_eq!(balance(deps.as_ref(), "xyzROCK", XYZ_NFT_ID), Uint128::new(300));
+        assert_eq!(balance(deps.as_ref(), "xyzICE", XYZ_NFT_ID), Uint128::new(200));
+        assert_eq!(
+            query_resource(deps.as_ref(), "xyzICE".to_string()).unwrap().total_supply,
+            Uint128::new(400)
+        );
+        assert_eq!(
+            query_resource_allowance(
+                deps.as_ref(),
+                "xyzROCK".to_string(),
+                NFT_OWNER_ADDRESS.to_string(),
+                XYZ_NFT_ID.to_string(),
+                spender.to_string(),
+            )
+            .unwrap()
+            .allowance,
+            Uint128::new(50)
+        );
+
+        // the ice allowance is used up
+        let err = execute(deps.as_mut(), mock_env(), mock_info(spender, &[]), burn).unwrap_err();
+        assert!(matches!(err, ContractError::Std(StdError::Overflow { .. })));
+    }
+
+    #[test]
+    fn resource_seasons_and_send() {
+        let mut deps = mock_dependencies_custom(
+            Some(NFT_OWNER_ADDRESS.to_string()),
+            Some(DEFAULT_RAND),
+            Some(default_xyz_nft_data(NOW, false, None)),
+            vec![],
+            &[],
+        );
+        let minter = "minter";
+        do_instantiate(deps.as_mut());
+        set_resource(deps.as_mut(), "xyzROCK", minter, None);
+        let msg = ExecuteMsg::MintResources {
+            mints: vec![ResourceMint {
+                resource_id: "xyzROCK".to_string(),
+                xyz_id: XYZ_NFT_ID.to_string(),
+                amount: Uint128::new(500),
+            }],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg).unwrap();
+
+        // sending moves the resources and notifies the receiving contract
+        let send_msg = Binary::from(br#"{"some":123}"#);
+        let msg = ExecuteMsg::SendResources {
+            from_xyz_id: XYZ_NFT_ID.to_string(),
+            contract: "contract".to_string(),
+            to_xyz_id: "xyz #2".to_string(),
+            amounts: vec![amount("xyzROCK", 200)],
+            msg: send_msg.clone(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info(minter, &[]), msg.clone()).unwrap_err();
+        assert_eq!(err, ContractError::Unauthorized {});
+        let res = execute(deps.as_mut(), mock_env(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            XyzResourcesReceiveMsg {
+                sender: NFT_OWNER_ADDRESS.to_string(),
+                from_xyz_id: XYZ_NFT_ID.to_string(),
+                to_xyz_id: "xyz #2".to_string(),
+                amounts: vec![amount("xyzROCK", 200)],
+                msg: send_msg,
+            }
+            .into_cosmos_msg("contract")
+            .unwrap()
+        );
+
+        // balances are snapshotted per block
+        let mut env = mock_env();
+        env.block.height += 1;
+        let msg = ExecuteMsg::TransferResources {
+            from_xyz_id: XYZ_NFT_ID.to_string(),
+            to_xyz_id: "xyz #2".to_string(),
+            amounts: vec![amount("xyzROCK", 100)],
+        };
+        execute(deps.as_mut(), env.clone(), mock_info(NFT_OWNER_ADDRESS, &[]), msg).unwrap();
+        let balance_at = |deps: Deps, height: u64| {
+            query_resource_balance_at(deps, "xyzROCK".to_string(), XYZ_NFT_ID.to_string(), None, height)
+                .unwrap()
+                .balance
+        };
+        assert_eq!(balance_at(deps.as_ref(), env.block.height), Uint128::new(300));
+        assert_eq!(balance_at(deps.as_ref(), env.block.height + 1), Uint128::new(200));
+
+        // a new season archives the balances and resets the supply
+        execute(deps.as_mut(), env, mock_info("creator", &[]), ExecuteMsg::StartNewSeason {}).unwrap();
+        let balance = |deps: Deps, season: Option<u64>| {
+            query_resource_balance(deps, "xyzROCK".to_string(), XYZ_NFT_ID.to_string(), season)
+                .unwrap()
+                .balance
+        };
+        assert_eq!(balance(deps.as_ref(), None), Uint128::zero());
+        assert_eq!(balance(deps.as_ref(), Some(0)), Uint128::new(200));
+        assert_eq!(
+            query_resource(deps.as_ref(), "xyzROCK".to_string()).unwrap().total_supply,
+            Uint128::zero()
+        );
+    }
+}
@@ -2,9 +2,9 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, Map};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy, U64Key};
 
-use collectxyz_resources::{AllowanceResponse, Logo, MarketingInfoResponse};
+use collectxyz_resources::{AllowanceResponse, Expiration, Logo, MarketingInfoResponse};
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
 #[serde(rename_all = "snake_case")]
@@ -51,11 +51,81 @@ impl XyzPlanetResourceInfo {
     }
 }
 
-pub const TOKEN_INFO_OLD: Item<XyzPlanetResourceInfo> = Item::new("token_info");
 pub const TOKEN_INFO: Item<XyzPlanetResourceInfo> = Item::new("token_info_v2");
 pub const MARKETING_INFO: Item<MarketingInfoResponse> = Item::new("marketing_info");
 pub const LOGO: Item<Logo> = Item::new("logo");
-/// Balances are mapped by XYZ ID
-pub const BALANCES: Map<&str, Uint128> = Map::new("balance");
-/// Allowance is granted for xyz_id,xyz_owner -> some_addr
+/// Owner can start new competition seasons
+pub const OWNER: Item<Addr> = Item::new("owner");
+/// Balances from before seasons were introduced, mapped by XYZ ID. Moved into the
+/// current season on migrate.
+pub const BALANCES_OLD: Map<&str, Uint128> = Map::new("balance");
+/// Balances are mapped by (season, XYZ ID), so the balances of past seasons stay archived
+/// under their season number. Every change is recorded in a changelog so that balances
+/// can be queried as of a past block height.
+pub const BALANCES: SnapshotMap<(U64Key, &str), Uint128> = SnapshotMap::new(
+    "season_balance",
+    "season_balance__checkpoints",
+    "season_balance__changelog",
+    Strategy::EveryBlock,
+);
+
+pub fn balance_key(season: u64, xyz_id: &str) -> (U64Key, &str) {
+    (season.into(), xyz_id)
+}
+/// Total supply history, mirroring `total_supply` in TOKEN_INFO under a single key
+pub const TOTAL_SUPPLY_HISTORY: SnapshotMap<&str, Uint128> = SnapshotMap::new(
+    "total_supply",
+    "total_supply__checkpoints",
+    "total_supply__changelog",
+    Strategy::EveryBlock,
+);
+pub const TOTAL_SUPPLY_KEY: &str = "total_supply";
+/// Height of the migration that introduced TOTAL_SUPPLY_HISTORY; unset if the history was
+/// recorded since instantiation
+pub const TOTAL_SUPPLY_HISTORY_START: Item<u64> = Item::new("total_supply_history_start");
+/// A resource in multi-resource mode, mapped by resource id in RESOURCES
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+#[serde(rename_all = "snake_case")]
+pub struct ResourceInfo {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub total_supply: Uint128,
+    pub minters: Vec<MinterData>,
+}
+
+/// Allowance over one resource of an xyz. Only valid while `owner` still owns the xyz.
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema, Debug)]
+pub struct ResourceAllowance {
+    pub owner: Addr,
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+/// Resources registered in multi-resource mode
+pub const RESOURCES: Map<&str, ResourceInfo> = Map::new("resources");
+/// Multi-resource balances are mapped by (season, resource_id, xyz_id), archived and
+/// snapshotted like BALANCES
+pub const RESOURCE_BALANCES: SnapshotMap<(U64Key, &str, &str), Uint128> = SnapshotMap::new(
+    "season_resource_balance",
+    "season_resource_balance__checkpoints",
+    "season_resource_balance__changelog",
+    Strategy::EveryBlock,
+);
+
+pub fn resource_balance_key<'a>(
+    season: u64,
+    resource_id: &'a str,
+    xyz_id: &'a str,
+) -> (U64Key, &'a str, &'a str) {
+    (season.into(), resource_id, xyz_id)
+}
+/// Multi-resource allowance is granted for resource_id,xyz_id -> some_addr. Like ALLOWANCES,
+/// these aren't season-scoped.
+pub const RESOURCE_ALLOWANCES: Map<(&str, &str, &Addr), ResourceAllowance> =
+    Map::new("resource_allowance");
+
+/// Allowance is granted for xyz_id,xyz_owner -> some_addr. Allowances aren't scoped by
+/// season: they cap what a spender may take from the xyz rather than holding tokens, so
+/// they carry over to the balance of each new season until spent, expired or decreased.
 pub const ALLOWANCES: Map<(&str, &Addr, &Addr), AllowanceResponse> = Map::new("allowance");